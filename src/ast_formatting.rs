@@ -1,6 +1,6 @@
-/// some general formatting for various ast nodes. might update this eventually
-/// for better output or use type aliases so im not dumping String everywhere
-/// and getting confused
+//! some general formatting for various ast nodes. might update this eventually
+//! for better output or use type aliases so im not dumping String everywhere
+//! and getting confused
 
 pub fn format_ret_type(ty: &syn::ReturnType) -> String {
     match ty {
@@ -21,6 +21,39 @@ pub fn format_idnt(ident: &syn::Ident) -> String {
     ident.to_string()
 }
 
+pub fn format_bound(bound: &syn::TypeParamBound) -> String {
+    format!("{}", quote!(#bound))
+}
+
+/// the line an item starts on, its attributes and doc comments included
+pub fn format_line(item: &impl syn::spanned::Spanned) -> usize {
+    item.span().start().line
+}
+
+/// the `///` comments on an item. rustc turns each line into a
+/// `#[doc = " line"]` attribute, so we join them back up
+pub fn format_docs(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(line),
+                ..
+            })) => Some(line.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect();
+
+    let docs = lines.join("\n").trim().to_string();
+    if docs.is_empty() {
+        None
+    } else {
+        Some(docs)
+    }
+}
+
 pub fn format_lit(lit: &syn::Lit) -> String {
     match lit {
         syn::Lit::Str(str) => str.value(),
@@ -62,4 +95,22 @@ mod tests {
             "Formatting an option should work"
         )
     }
+
+    #[test]
+    fn format_doc_comments() {
+        let item: syn::ItemStruct = syn::parse_str(
+            "
+            /// Some docs
+            /// over two lines
+            #[derive(Debug)]
+            struct Documented;
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            format_docs(&item.attrs),
+            Some("Some docs\nover two lines".to_string()),
+            "Formatting doc comments joins the lines"
+        );
+    }
 }
@@ -0,0 +1,351 @@
+//! what changed in the api release by release, worked out from the git
+//! history. the sources are read at every tag (or the revisions asked for)
+//! straight from git, so the working tree is never touched
+
+use crate::cli::module_path;
+use crate::config::Config;
+use crate::diff::{bump_name, diff, DiffReport};
+use crate::rocket_document::RocketDocument;
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// the changes that went into a revision since the one before it
+pub struct Release {
+    pub name: String,
+    pub date: Option<String>,
+    // None for the first release, everything in it is new
+    pub previous: Option<String>,
+    pub report: DiffReport,
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    git_in(Path::new("."), args)
+}
+
+fn git_in(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|err| format!("could not run git: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// `/repo/./src/../src` as `/repo/src`, for paths that can't be
+/// canonicalized because they aren't there anymore
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    path.components().for_each(|component| match component {
+        Component::CurDir => (),
+        Component::ParentDir => {
+            normalized.pop();
+        }
+        component => normalized.push(component),
+    });
+    normalized
+}
+
+/// the inputs as paths from the top of the repository, the way
+/// `ls-tree --full-tree` lists files. relative inputs are relative to `dir`,
+/// where git runs, and the top itself is an empty path
+fn repo_paths(dir: &Path, inputs: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let top = PathBuf::from(git_in(dir, &["rev-parse", "--show-toplevel"])?.trim());
+    let top = top.canonicalize().unwrap_or(top);
+    let dir = dir
+        .canonicalize()
+        .map_err(|err| format!("could not read {}: {}", dir.display(), err))?;
+    inputs
+        .iter()
+        .map(|input| {
+            let absolute = dir.join(input);
+            let absolute = absolute
+                .canonicalize()
+                .unwrap_or_else(|_| normalize(&absolute));
+            absolute
+                .strip_prefix(&top)
+                .map(|path| path.to_path_buf())
+                .map_err(|_| {
+                    format!(
+                        "{} is outside the git repository at {}",
+                        input.display(),
+                        top.display()
+                    )
+                })
+        })
+        .collect()
+}
+
+/// the document for the inputs as they were at `rev`. the config is the
+/// current one, it isn't read from history
+pub fn document_at(
+    rev: &str,
+    inputs: &[PathBuf],
+    config: &Config,
+) -> Result<RocketDocument, String> {
+    document_in(Path::new("."), rev, inputs, config)
+}
+
+/// document_at for git running in `dir`. an input without any rust files at
+/// `rev` is an error, a typo would otherwise look like an api without changes
+fn document_in(
+    dir: &Path,
+    rev: &str,
+    inputs: &[PathBuf],
+    config: &Config,
+) -> Result<RocketDocument, String> {
+    let paths = repo_paths(dir, inputs)?;
+    let listing = git_in(dir, &["ls-tree", "-r", "--name-only", "--full-tree", rev])?;
+    let sources: Vec<&Path> = listing
+        .lines()
+        .map(Path::new)
+        .filter(|path| path.extension().map(|ext| ext == "rs").unwrap_or(false))
+        .collect();
+    if let Some(idx) = paths
+        .iter()
+        .position(|input| !sources.iter().any(|path| path.starts_with(input)))
+    {
+        return Err(format!(
+            "{} has no rust files at {}",
+            inputs[idx].display(),
+            rev
+        ));
+    }
+
+    let mut files = vec![];
+    for path in sources {
+        // a file given directly is a module on its own, like collect_sources
+        let root = match paths.iter().find(|input| path.starts_with(input)) {
+            Some(input) if input == path => path.parent().unwrap_or_else(|| Path::new("")),
+            Some(input) => input.as_path(),
+            None => continue,
+        };
+        let module = module_path(root, path);
+        if config.excludes(&module) {
+            continue;
+        }
+        let content = git_in(dir, &["show", &format!("{}:{}", rev, path.display())])?;
+        let ast = syn::parse_file(&content)
+            .map_err(|err| format!("could not parse {} at {}: {}", path.display(), rev, err))?;
+        files.push((module, ast));
+    }
+
+    let mut document =
+        RocketDocument::parse_files(files.iter().map(|(module, ast)| (module.to_owned(), ast)));
+    config.apply(&mut document)?;
+    Ok(document)
+}
+
+/// a release per tag, oldest first, and one for what's on HEAD since the
+/// last tag if anything changed. `from` and `to` compare just those two
+pub fn releases(
+    inputs: &[PathBuf],
+    config: &Config,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<Release>, String> {
+    let revisions: Vec<(Option<String>, String)> = match from {
+        Some(from) => vec![(Some(from.to_string()), to.unwrap_or("HEAD").to_string())],
+        None => {
+            let tags: Vec<String> = git(&["tag", "--list", "--sort=v:refname"])?
+                .lines()
+                .map(|tag| tag.to_string())
+                .collect();
+            if tags.is_empty() {
+                return Err("there are no tags, use --from to pick a revision".to_string());
+            }
+            let mut revisions: Vec<_> = std::iter::once(None)
+                .chain(tags.iter().cloned().map(Some))
+                .zip(tags.iter().cloned())
+                .collect();
+            revisions.push((tags.last().cloned(), to.unwrap_or("HEAD").to_string()));
+            revisions
+        }
+    };
+
+    let mut releases = vec![];
+    let mut previous_document = None;
+    for (previous, rev) in revisions {
+        let old = match (previous_document.take(), &previous) {
+            (Some(document), _) => document,
+            (None, Some(previous)) => document_at(previous, inputs, config)?,
+            (None, None) => RocketDocument::default(),
+        };
+        let new = document_at(&rev, inputs, config)?;
+        let date = git(&["log", "-1", "--format=%cd", "--date=short", &rev])?;
+        releases.push(Release {
+            name: rev,
+            date: Some(date.trim().to_string()).filter(|date| !date.is_empty()),
+            previous,
+            report: diff(&old, &new),
+        });
+        previous_document = Some(new);
+    }
+
+    // HEAD only gets a section when there's something unreleased
+    if from.is_none() {
+        if let Some(last) = releases.last() {
+            if last.report.changes.is_empty() {
+                releases.pop();
+            } else if to.is_none() {
+                let unreleased = releases.last_mut().unwrap();
+                unreleased.name = "Unreleased".to_string();
+                unreleased.date = None;
+            }
+        }
+    }
+    Ok(releases)
+}
+
+/// newest release first, each with its changes grouped the way `diff`
+/// groups them
+pub fn to_markdown(releases: &[Release]) -> String {
+    let mut out = "# API changelog\n".to_string();
+    releases.iter().rev().for_each(|release| {
+        match &release.date {
+            Some(date) => write!(out, "\n## {} ({})\n", release.name, date).unwrap(),
+            None => write!(out, "\n## {}\n", release.name).unwrap(),
+        }
+        match &release.previous {
+            _ if release.report.changes.is_empty() => {
+                out.push_str("\nNo changes to the API.\n");
+                return;
+            }
+            Some(previous) => writeln!(
+                out,
+                "\nA {} change since {}.",
+                bump_name(release.report.bump),
+                previous
+            )
+            .unwrap(),
+            None => out.push_str("\nThe first release.\n"),
+        }
+        release
+            .report
+            .sections()
+            .iter()
+            .for_each(|(title, changes)| {
+                let mut title = title.to_string();
+                title[..1].make_ascii_uppercase();
+                write!(out, "\n### {}\n\n", title).unwrap();
+                changes.iter().for_each(|change| {
+                    writeln!(out, "- `{}`: {}", change.location, change.message).unwrap()
+                });
+            });
+    });
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document(content: &str) -> RocketDocument {
+        RocketDocument::parse_file(&syn::parse_str(content).unwrap())
+    }
+
+    #[test]
+    fn writes_a_section_per_release() {
+        let v1 = document("#[get(\"/users\")] fn list_users() {}");
+        let v2 = document(
+            "
+            #[get(\"/users\")] fn list_users() {}
+            #[get(\"/users/<id>\")] fn get_user(id: u64) {}
+            ",
+        );
+        let releases = vec![
+            Release {
+                name: "v1.0.0".to_string(),
+                date: Some("2024-01-01".to_string()),
+                previous: None,
+                report: diff(&RocketDocument::default(), &v1),
+            },
+            Release {
+                name: "v1.1.0".to_string(),
+                date: None,
+                previous: Some("v1.0.0".to_string()),
+                report: diff(&v1, &v2),
+            },
+        ];
+
+        assert_eq!(
+            to_markdown(&releases),
+            "# API changelog\n\n\
+             ## v1.1.0\n\n\
+             A minor change since v1.0.0.\n\n\
+             ### Additions\n\n\
+             - `GET /users/{id}`: added\n\n\
+             ## v1.0.0 (2024-01-01)\n\n\
+             The first release.\n\n\
+             ### Additions\n\n\
+             - `GET /users`: added\n"
+        );
+    }
+
+    #[test]
+    fn finds_inputs_however_they_are_given() {
+        let repo =
+            std::env::temp_dir().join(format!("rocket-doc-gen-changelog-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(
+            repo.join("src/main.rs"),
+            "#[get(\"/users\")] fn list_users() {}",
+        )
+        .unwrap();
+        std::fs::write(
+            repo.join("src/api.rs"),
+            "#[get(\"/items\")] fn list_items() {}",
+        )
+        .unwrap();
+        let setup = [
+            vec!["init", "-q"],
+            vec!["add", "."],
+            vec![
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "-c",
+                "commit.gpgsign=false",
+                "commit",
+                "-q",
+                "-m",
+                "v1",
+            ],
+            vec!["tag", "v1"],
+        ];
+        setup.iter().for_each(|args| {
+            git_in(&repo, args).unwrap();
+        });
+        let config = Config::default();
+        let routes = |dir: &Path, input: &Path| {
+            document_in(dir, "v1", &[input.to_path_buf()], &config)
+                .map(|document| document.routes.len())
+        };
+
+        let absolute = routes(&repo, &repo.join("src"));
+        let dot = routes(&repo, Path::new("."));
+        let from_subdirectory = routes(&repo.join("src"), Path::new("."));
+        let file = routes(&repo.join("src"), Path::new("./api.rs"));
+        let typo = routes(&repo, Path::new("scr"));
+        std::fs::remove_dir_all(&repo).unwrap();
+
+        assert_eq!(absolute, Ok(2));
+        assert_eq!(dot, Ok(2));
+        assert_eq!(from_subdirectory, Ok(2));
+        assert_eq!(file, Ok(1));
+        assert_eq!(
+            typo,
+            Err("scr has no rust files at v1".to_string()),
+            "An input that matches nothing isn't an api without changes"
+        );
+    }
+}
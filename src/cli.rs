@@ -0,0 +1,491 @@
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "\
+usage: rocket-doc-gen [options] [INPUT...]
+       rocket-doc-gen diff [--format text|json] [-o PATH] OLD NEW
+       rocket-doc-gen changelog [--from REV] [--to REV] [-c FILE] [-o PATH] [INPUT...]
+       rocket-doc-gen lint [--format text|sarif|junit] [-o PATH] [-c FILE] [INPUT...]
+       rocket-doc-gen coverage [--format text|json] [--fail-under PERCENT] [-c FILE] [INPUT...]
+
+INPUT is a rust source file or a directory that is searched for them.
+with no input the inputs in the config file are documented, or the built
+in example if it doesn't list any.
+
+options:
+    -c, --config <FILE>     the config file to use, rocket-doc-gen.toml in
+                            the working directory is used if there is one.
+                            anything given on the command line wins over it
+    -f, --format <FORMAT>   json (default), openapi, openapi-yaml, markdown,
+                            html, json-schema, typescript, rust-client,
+                            postman, insomnia, bruno, http, curl. without it
+                            every format in the config's [outputs] is written
+    -o, --output <PATH>     write to PATH instead of stdout. html,
+                            json-schema, bruno and --templates need a
+                            directory to write into
+    -t, --templates <DIR>   render the handlebars templates (*.hbs) in DIR
+                            instead of a built in format
+    -e, --examples <FILE>   a json file with example values to use instead of
+                            made up ones, with `types` (keyed by type) and
+                            `fields` (keyed by `Type.field`) objects
+    -m, --type-mappings <FILE>
+                            a json file with schemas for types, keyed by
+                            type, to use instead of the built in ones
+    --title <TITLE>         the api's title
+    --api-version <VERSION> the api's version
+    --server <URL>          where the api is deployed, can be given more
+                            than once
+    --check                 don't write anything, compare what would be
+                            written with the files that are there and exit
+                            with an error (and a diff) if they don't match
+    --types-crate <NAME>    the crate the api's types live in, rust-client
+                            uses them from there instead of mirroring them
+    -h, --help              print this message
+
+diff compares two documents written by the json format and lists what
+changed between them, graded by whether it breaks existing clients, along
+with the semver bump that calls for. --format json writes it as a report
+for other tools.
+
+changelog reads the inputs at every git tag and writes a markdown changelog
+of what changed in the api between them, with what's on HEAD since the last
+tag as Unreleased. --from (and --to, HEAD by default) compare just those two
+revisions. the inputs come from the config when none are given
+
+lint checks the routes and responders for missing docs, mutating routes
+without an auth guard, bodies on GET routes, params without an argument,
+colliding routes, responders without a status and paths cased unlike the
+rest. the config's [lints] table sets each rule to allow, warn or deny, and
+it exits with an error on any denied finding or file that doesn't parse.
+--format sarif or junit writes the findings for code scanning and CI
+dashboards. --rules lists the rules with their default severity
+
+coverage reports how many routes, types and fields have doc comments, how
+many responders give their status and how many bodies have examples in the
+config, per module. with --fail-under it exits with an error when the
+overall coverage is below PERCENT";
+
+/// every --format there is, aliases included
+pub const FORMATS: &[&str] = &[
+    "json",
+    "openapi",
+    "openapi-json",
+    "openapi-yaml",
+    "markdown",
+    "md",
+    "html",
+    "json-schema",
+    "typescript",
+    "ts",
+    "rust-client",
+    "rust",
+    "postman",
+    "insomnia",
+    "bruno",
+    "http",
+    "curl",
+];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub config: Option<PathBuf>,
+    pub format: Option<String>,
+    pub output: Option<PathBuf>,
+    pub templates: Option<PathBuf>,
+    pub types_crate: Option<String>,
+    pub examples: Option<PathBuf>,
+    pub type_mappings: Option<PathBuf>,
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub servers: Vec<String>,
+    pub inputs: Vec<PathBuf>,
+    pub check: bool,
+    pub help: bool,
+}
+
+impl Options {
+    pub fn parse_args(args: &[String]) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .map(|value| value.to_owned())
+                    .ok_or_else(|| format!("{} needs a value", name))
+            };
+
+            match arg.as_str() {
+                "-c" | "--config" => options.config = Some(PathBuf::from(value(arg)?)),
+                "-f" | "--format" => options.format = Some(value(arg)?),
+                "-o" | "--output" => options.output = Some(PathBuf::from(value(arg)?)),
+                "-t" | "--templates" => options.templates = Some(PathBuf::from(value(arg)?)),
+                "-e" | "--examples" => options.examples = Some(PathBuf::from(value(arg)?)),
+                "-m" | "--type-mappings" => {
+                    options.type_mappings = Some(PathBuf::from(value(arg)?))
+                }
+                "--types-crate" => options.types_crate = Some(value(arg)?),
+                "--title" => options.title = Some(value(arg)?),
+                "--api-version" => options.version = Some(value(arg)?),
+                "--server" => options.servers.push(value(arg)?),
+                "--check" => options.check = true,
+                "-h" | "--help" => options.help = true,
+                flag if flag.starts_with('-') && flag != "-" => {
+                    return Err(format!("unknown option {}", flag))
+                }
+                input => options.inputs.push(PathBuf::from(input)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// `diff [options] OLD NEW`
+#[derive(Debug, PartialEq)]
+pub struct DiffOptions {
+    pub old: PathBuf,
+    pub new: PathBuf,
+    pub format: String,
+    pub output: Option<PathBuf>,
+}
+
+impl DiffOptions {
+    pub fn parse_args(args: &[String]) -> Result<Self, String> {
+        let mut format = "text".to_string();
+        let mut output = None;
+        let mut documents = vec![];
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .map(|value| value.to_owned())
+                    .ok_or_else(|| format!("{} needs a value", name))
+            };
+
+            match arg.as_str() {
+                "-f" | "--format" => format = value(arg)?,
+                "-o" | "--output" => output = Some(PathBuf::from(value(arg)?)),
+                flag if flag.starts_with('-') => {
+                    return Err(format!("unknown option {} for diff", flag))
+                }
+                document => documents.push(PathBuf::from(document)),
+            }
+        }
+
+        match (documents.as_slice(), format.as_str()) {
+            (_, format) if format != "text" && format != "json" => Err(format!(
+                "unknown diff format {}, it's either text or json",
+                format
+            )),
+            ([old, new], _) => Ok(DiffOptions {
+                old: old.to_owned(),
+                new: new.to_owned(),
+                format,
+                output,
+            }),
+            _ => Err("diff needs two documents, OLD and NEW".to_string()),
+        }
+    }
+}
+
+/// `changelog [options] [INPUT...]`
+#[derive(Debug, Default, PartialEq)]
+pub struct ChangelogOptions {
+    pub config: Option<PathBuf>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub output: Option<PathBuf>,
+    pub inputs: Vec<PathBuf>,
+}
+
+impl ChangelogOptions {
+    pub fn parse_args(args: &[String]) -> Result<Self, String> {
+        let mut options = ChangelogOptions::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .map(|value| value.to_owned())
+                    .ok_or_else(|| format!("{} needs a value", name))
+            };
+
+            match arg.as_str() {
+                "-c" | "--config" => options.config = Some(PathBuf::from(value(arg)?)),
+                "--from" => options.from = Some(value(arg)?),
+                "--to" => options.to = Some(value(arg)?),
+                "-o" | "--output" => options.output = Some(PathBuf::from(value(arg)?)),
+                flag if flag.starts_with('-') => {
+                    return Err(format!("unknown option {} for changelog", flag))
+                }
+                input => options.inputs.push(PathBuf::from(input)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// `lint [options] [INPUT...]`
+#[derive(Debug, Default, PartialEq)]
+pub struct LintOptions {
+    pub config: Option<PathBuf>,
+    // text, sarif or junit
+    pub format: String,
+    pub output: Option<PathBuf>,
+    pub inputs: Vec<PathBuf>,
+    // list the rules instead of linting
+    pub rules: bool,
+}
+
+impl LintOptions {
+    pub fn parse_args(args: &[String]) -> Result<Self, String> {
+        let mut options = LintOptions {
+            format: "text".to_string(),
+            ..LintOptions::default()
+        };
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .map(|value| value.to_owned())
+                    .ok_or_else(|| format!("{} needs a value", name))
+            };
+
+            match arg.as_str() {
+                "-c" | "--config" => options.config = Some(PathBuf::from(value(arg)?)),
+                "-f" | "--format" => options.format = value(arg)?,
+                "-o" | "--output" => options.output = Some(PathBuf::from(value(arg)?)),
+                "--rules" => options.rules = true,
+                flag if flag.starts_with('-') => {
+                    return Err(format!("unknown option {} for lint", flag))
+                }
+                input => options.inputs.push(PathBuf::from(input)),
+            }
+        }
+
+        if !["text", "sarif", "junit"].contains(&options.format.as_str()) {
+            return Err(format!(
+                "unknown lint format {}, it's text, sarif or junit",
+                options.format
+            ));
+        }
+        Ok(options)
+    }
+}
+
+/// `coverage [options] [INPUT...]`
+#[derive(Debug, Default, PartialEq)]
+pub struct CoverageOptions {
+    pub config: Option<PathBuf>,
+    // text or json
+    pub format: String,
+    pub output: Option<PathBuf>,
+    pub fail_under: Option<f64>,
+    pub inputs: Vec<PathBuf>,
+}
+
+impl CoverageOptions {
+    pub fn parse_args(args: &[String]) -> Result<Self, String> {
+        let mut options = CoverageOptions {
+            format: "text".to_string(),
+            ..CoverageOptions::default()
+        };
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .map(|value| value.to_owned())
+                    .ok_or_else(|| format!("{} needs a value", name))
+            };
+
+            match arg.as_str() {
+                "-c" | "--config" => options.config = Some(PathBuf::from(value(arg)?)),
+                "-f" | "--format" => options.format = value(arg)?,
+                "-o" | "--output" => options.output = Some(PathBuf::from(value(arg)?)),
+                "--fail-under" => {
+                    let percent = value(arg)?;
+                    options.fail_under = Some(
+                        percent
+                            .trim_end_matches('%')
+                            .parse()
+                            .ok()
+                            .filter(|percent| (0.0..=100.0).contains(percent))
+                            .ok_or_else(|| {
+                                format!("--fail-under takes a percentage, not {}", percent)
+                            })?,
+                    )
+                }
+                flag if flag.starts_with('-') => {
+                    return Err(format!("unknown option {} for coverage", flag))
+                }
+                input => options.inputs.push(PathBuf::from(input)),
+            }
+        }
+
+        if options.format != "text" && options.format != "json" {
+            return Err(format!(
+                "unknown coverage format {}, it's either text or json",
+                options.format
+            ));
+        }
+        Ok(options)
+    }
+}
+
+/// a source file and the module path it ends up at
+#[derive(Debug, PartialEq)]
+pub struct Source {
+    pub path: PathBuf,
+    pub module: Vec<String>,
+}
+
+/// `api/users.rs` is `api::users` and `api/mod.rs` is `api`. main.rs and
+/// lib.rs at the root are the crate itself
+pub fn module_path(root: &Path, path: &Path) -> Vec<String> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let mut module: Vec<String> = relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match relative.file_stem().map(|stem| stem.to_string_lossy()) {
+        Some(stem) if stem == "mod" => (),
+        Some(stem) if module.is_empty() && (stem == "main" || stem == "lib") => (),
+        Some(stem) => module.push(stem.to_string()),
+        None => (),
+    }
+    module
+}
+
+/// every .rs file under the inputs, directories are searched recursively.
+/// sorted so the output doesn't change with directory listing order
+pub fn collect_sources(inputs: &[PathBuf]) -> Result<Vec<Source>, String> {
+    fn visit(root: &Path, path: &Path, out: &mut Vec<Source>) -> Result<(), String> {
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path)
+                .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+            for entry in entries {
+                let entry = entry.map_err(|err| format!("{}: {}", path.display(), err))?;
+                visit(root, &entry.path(), out)?;
+            }
+        } else if path.extension().map(|ext| ext == "rs").unwrap_or(false) {
+            out.push(Source {
+                path: path.to_owned(),
+                module: module_path(root, path),
+            });
+        } else if !path.exists() {
+            return Err(format!("{} does not exist", path.display()));
+        }
+        Ok(())
+    }
+
+    let mut sources = vec![];
+    for input in inputs {
+        // a file given directly is a module on its own
+        let root = if input.is_dir() {
+            input.as_path()
+        } else {
+            input.parent().unwrap_or_else(|| Path::new(""))
+        };
+        visit(root, input, &mut sources)?;
+    }
+    sources.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(sources)
+}
+
+pub fn read_source(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map_err(|err| format!("could not read {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_options_and_inputs() {
+        assert_eq!(
+            Options::parse_args(&args(&[
+                "src",
+                "-f",
+                "openapi",
+                "--output",
+                "api.json",
+                "--server",
+                "https://api.example.com",
+            ])),
+            Ok(Options {
+                config: None,
+                format: Some("openapi".to_string()),
+                output: Some(PathBuf::from("api.json")),
+                templates: None,
+                types_crate: None,
+                examples: None,
+                type_mappings: None,
+                title: None,
+                version: None,
+                servers: vec!["https://api.example.com".to_string()],
+                inputs: vec![PathBuf::from("src")],
+                check: false,
+                help: false,
+            })
+        );
+    }
+
+    #[test]
+    fn module_paths_follow_the_file_layout() {
+        let root = Path::new("src");
+        assert_eq!(
+            module_path(root, Path::new("src/main.rs")),
+            Vec::<String>::new()
+        );
+        assert_eq!(module_path(root, Path::new("src/api/mod.rs")), vec!["api"]);
+        assert_eq!(
+            module_path(root, Path::new("src/api/users.rs")),
+            vec!["api", "users"]
+        );
+    }
+
+    #[test]
+    fn parses_diff_options() {
+        assert_eq!(
+            DiffOptions::parse_args(&args(&["old.json", "new.json", "-f", "json"])),
+            Ok(DiffOptions {
+                old: PathBuf::from("old.json"),
+                new: PathBuf::from("new.json"),
+                format: "json".to_string(),
+                output: None,
+            })
+        );
+        assert!(DiffOptions::parse_args(&args(&["old.json"])).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        assert!(Options::parse_args(&args(&["--nope"])).is_err());
+        assert!(LintOptions::parse_args(&args(&["--format", "xml"])).is_err());
+        assert_eq!(
+            CoverageOptions::parse_args(&args(&["--fail-under", "80%"]))
+                .unwrap()
+                .fail_under,
+            Some(80.0)
+        );
+        assert!(CoverageOptions::parse_args(&args(&["--fail-under", "lots"])).is_err());
+        assert!(
+            Options::parse_args(&args(&["--format"])).is_err(),
+            "Options that need a value fail without one"
+        );
+    }
+}
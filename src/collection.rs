@@ -0,0 +1,712 @@
+//! requests to paste into an api client. every route (at every place it's
+//! mounted) becomes a `SampleRequest` with example values filled in, and the
+//! postman, insomnia and bruno formats are different ways of writing those
+
+use crate::example::{plain_text, ExampleGenerator};
+use crate::guard::GuardKind;
+use crate::rocket_document::{by_module, RocketDocument};
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_uri::{RocketUri, UriSegment};
+use crate::rocket_wrapper::{media_type, status_reason};
+use crate::type_ref::TypeRef;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// rocket's default address, the collections keep it in a variable
+pub const BASE_URL: &str = "http://localhost:8000";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleParam {
+    pub name: String,
+    pub value: String,
+    // Option params are there but turned off
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SampleBody {
+    Json(Value),
+    Form(Vec<(String, String)>),
+    // plain text, or empty for bytes we can't make up
+    Text(String),
+}
+
+/// what the route can answer with, for clients that keep example responses
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleResponse {
+    pub status: u32,
+    pub content_type: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleRequest {
+    pub name: String,
+    pub docs: Option<String>,
+    pub method: String,
+    pub uri: RocketUri,
+    pub path_params: Vec<SampleParam>,
+    pub query: Vec<SampleParam>,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<SampleBody>,
+    pub responses: Vec<SampleResponse>,
+}
+
+/// `Option<T>` and `Result<T, E>` params are T with the option of leaving
+/// them out (or getting them wrong)
+fn unwrap_param(ty: &TypeRef) -> (&TypeRef, bool) {
+    match (ty.ident(), ty.args()) {
+        (Some("Option"), [inner]) => (unwrap_param(inner).0, false),
+        (Some("Result"), [inner, ..]) => unwrap_param(inner),
+        _ => (ty, true),
+    }
+}
+
+/// the header a classified guard reads, with a value to fill in
+fn guard_header(kind: &GuardKind) -> Option<(String, String)> {
+    Some(match kind {
+        GuardKind::Header { name } => (name.to_owned(), "string".to_string()),
+        GuardKind::Cookie { name } => ("Cookie".to_string(), format!("{}=string", name)),
+        GuardKind::ApiKey { name, location } if location == "cookie" => {
+            ("Cookie".to_string(), format!("{}=key", name))
+        }
+        GuardKind::ApiKey { name, .. } => (name.to_owned(), "key".to_string()),
+        GuardKind::Auth { scheme } if scheme.eq_ignore_ascii_case("basic") => (
+            "Authorization".to_string(),
+            "Basic dXNlcjpwYXNzd29yZA==".to_string(),
+        ),
+        GuardKind::Auth { scheme } if scheme.eq_ignore_ascii_case("bearer") => {
+            ("Authorization".to_string(), "Bearer token".to_string())
+        }
+        GuardKind::Auth { scheme } => ("Authorization".to_string(), format!("{} token", scheme)),
+        GuardKind::Ignore => return None,
+    })
+}
+
+/// a value as form fields the way rocket reads them, lists are the same
+/// name over and over and nested structs are `parent.field`
+fn form_fields(name: &str, value: &Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(object) => object.iter().for_each(|(key, value)| {
+            let key = if name.is_empty() {
+                key.to_owned()
+            } else {
+                format!("{}.{}", name, key)
+            };
+            form_fields(&key, value, fields)
+        }),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| form_fields(name, value, fields)),
+        value => fields.push((name.to_string(), plain_text(value))),
+    }
+}
+
+impl SampleRequest {
+    /// one request per full uri of the route. a route mounted in several
+    /// places gets the uri in its name so they can be told apart
+    pub fn from_route(
+        document: &RocketDocument,
+        examples: &ExampleGenerator,
+        route: &RocketRoute,
+    ) -> Vec<Self> {
+        let uris = route.full_uris();
+        uris.iter()
+            .map(|uri| {
+                let name = if uris.len() > 1 {
+                    format!("{} ({})", route.ident, uri.templated_path())
+                } else {
+                    route.ident.to_owned()
+                };
+                let param = |name: &str, ty: &TypeRef| {
+                    let (ty, required) = unwrap_param(ty);
+                    SampleParam {
+                        name: name.to_string(),
+                        value: plain_text(&examples.example(ty)),
+                        required,
+                    }
+                };
+
+                let path_params = route
+                    .params_of_kind(ParamKind::Path)
+                    .map(|p| param(&p.ident, &p.ty))
+                    .collect();
+
+                let mut query = vec![];
+                uri.query.iter().for_each(|segment| match segment {
+                    UriSegment::Static { value } => {
+                        let mut kv = value.splitn(2, '=');
+                        query.push(SampleParam {
+                            name: kv.next().unwrap_or_default().to_string(),
+                            value: kv.next().unwrap_or_default().to_string(),
+                            required: true,
+                        })
+                    }
+                    _ => route
+                        .params
+                        .iter()
+                        .filter(|p| Some(p.ident.as_str()) == segment.name())
+                        .for_each(|p| match document.find_struct(unwrap_param(&p.ty).0) {
+                            // a FromForm struct, its fields are the params
+                            Some(form) if p.kind == ParamKind::QueryForm => {
+                                form.fields.iter().for_each(|(name, ty)| {
+                                    query.push(param(name, &TypeRef::parse(ty)))
+                                })
+                            }
+                            _ => query.push(param(&p.ident, &p.ty)),
+                        }),
+                });
+
+                let mut headers = vec![];
+                let body = route.body.as_ref().map(|body| {
+                    let content_type = body.media_type.as_deref().unwrap_or("application/json");
+                    headers.push(("Content-Type".to_string(), content_type.to_string()));
+                    match &body.schema {
+                        Some(schema) if content_type.contains("form") => {
+                            let mut fields = vec![];
+                            form_fields("", &examples.example(schema), &mut fields);
+                            SampleBody::Form(fields)
+                        }
+                        Some(schema) if content_type.contains("json") => {
+                            SampleBody::Json(examples.example(schema))
+                        }
+                        Some(schema) if content_type.starts_with("text/") => {
+                            SampleBody::Text(plain_text(&examples.example(schema)))
+                        }
+                        _ => SampleBody::Text(String::new()),
+                    }
+                });
+                // on routes without a body, format is matched against Accept
+                if let (None, Some(format)) = (&body, &route.route.format) {
+                    headers.push(("Accept".to_string(), media_type(format)));
+                }
+                route.params_of_kind(ParamKind::Guard).for_each(|param| {
+                    headers.extend(
+                        document
+                            .guard_inputs(&param.ty)
+                            .iter()
+                            .filter_map(guard_header),
+                    )
+                });
+
+                // the first body of every status a route is known to answer with
+                let mut seen = BTreeSet::new();
+                let responses = route
+                    .responses
+                    .iter()
+                    .filter_map(|res| res.status.map(|status| (status, res)))
+                    .filter(|(status, _)| seen.insert(*status))
+                    .map(|(status, res)| SampleResponse {
+                        status,
+                        content_type: res.content_type.to_owned(),
+                        body: examples.body_example(&res.body, res.content_type.as_deref()),
+                    })
+                    .collect();
+
+                SampleRequest {
+                    name,
+                    docs: route.docs.to_owned(),
+                    method: route.route.method.to_uppercase(),
+                    uri: uri.to_owned(),
+                    path_params,
+                    query,
+                    headers,
+                    body,
+                    responses,
+                }
+            })
+            .collect()
+    }
+
+    /// the path with every `<param>` written the way `placeholder` says
+    pub fn path(&self, placeholder: impl Fn(&str) -> String) -> String {
+        let path = self
+            .uri
+            .path
+            .iter()
+            .map(|segment| match segment {
+                UriSegment::Static { value } => value.to_owned(),
+                UriSegment::Dynamic { name } | UriSegment::Trailing { name } => placeholder(name),
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        format!("/{}", path)
+    }
+
+    /// the path followed by the query params that are turned on
+    pub fn path_and_query(&self, placeholder: impl Fn(&str) -> String) -> String {
+        let query: Vec<String> = self
+            .query
+            .iter()
+            .filter(|param| param.required)
+            .map(|param| format!("{}={}", param.name, param.value))
+            .collect();
+        if query.is_empty() {
+            self.path(placeholder)
+        } else {
+            format!("{}?{}", self.path(placeholder), query.join("&"))
+        }
+    }
+
+    /// the body as it's sent
+    pub fn body_text(&self) -> Option<String> {
+        self.body.as_ref().map(|body| match body {
+            SampleBody::Json(value) => serde_json::to_string_pretty(value).unwrap(),
+            SampleBody::Form(fields) => fields
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join("&"),
+            SampleBody::Text(text) => text.to_owned(),
+        })
+    }
+}
+
+/// the sample requests grouped by the module their handlers are in
+pub fn sample_requests(document: &RocketDocument) -> BTreeMap<String, Vec<SampleRequest>> {
+    let examples = ExampleGenerator::new(document);
+    by_module(&document.routes, |route| &route.module)
+        .into_iter()
+        .map(|(module, routes)| {
+            let requests = routes
+                .into_iter()
+                .flat_map(|route| SampleRequest::from_route(document, &examples, route))
+                .collect();
+            (module, requests)
+        })
+        .collect()
+}
+
+/// a postman v2.1 collection with a folder per module and `{{baseUrl}}` as
+/// a collection variable
+pub fn to_postman(document: &RocketDocument, title: &str) -> String {
+    let folders: Vec<Value> = sample_requests(document)
+        .into_iter()
+        .map(|(module, requests)| {
+            let items: Vec<Value> = requests
+                .iter()
+                .map(|request| {
+                    let path = request.path(|name| format!(":{}", name));
+                    let query: Vec<Value> = request
+                        .query
+                        .iter()
+                        .map(|param| {
+                            let mut query = json!({ "key": param.name, "value": param.value });
+                            if !param.required {
+                                query["disabled"] = json!(true);
+                            }
+                            query
+                        })
+                        .collect();
+                    let raw = format!(
+                        "{{{{baseUrl}}}}{}",
+                        request.path_and_query(|name| format!(":{}", name))
+                    );
+
+                    let mut postman = json!({
+                        "name": request.name,
+                        "request": {
+                            "method": request.method,
+                            "header": request.headers.iter().map(|(key, value)| {
+                                json!({ "key": key, "value": value })
+                            }).collect::<Vec<_>>(),
+                            "url": {
+                                "raw": raw,
+                                "host": ["{{baseUrl}}"],
+                                "path": path.trim_start_matches('/').split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>(),
+                                "query": query,
+                                "variable": request.path_params.iter().map(|param| {
+                                    json!({ "key": param.name, "value": param.value })
+                                }).collect::<Vec<_>>()
+                            }
+                        }
+                    });
+                    if let Some(docs) = &request.docs {
+                        postman["request"]["description"] = json!(docs);
+                    }
+                    postman["response"] = request
+                        .responses
+                        .iter()
+                        .map(|res| {
+                            let mut response = json!({
+                                "name": format!("{} {}", res.status, status_reason(res.status)),
+                                "code": res.status,
+                                "status": status_reason(res.status),
+                                "header": res.content_type.iter().map(|content_type| {
+                                    json!({ "key": "Content-Type", "value": content_type })
+                                }).collect::<Vec<_>>(),
+                                "body": res.body.to_owned().unwrap_or_default()
+                            });
+                            if res.content_type.as_deref().map(|content_type| content_type.contains("json")) == Some(true) {
+                                response["_postman_previewlanguage"] = json!("json");
+                            }
+                            response
+                        })
+                        .collect();
+                    match &request.body {
+                        Some(SampleBody::Form(fields)) => {
+                            postman["request"]["body"] = json!({
+                                "mode": "urlencoded",
+                                "urlencoded": fields.iter().map(|(key, value)| {
+                                    json!({ "key": key, "value": value })
+                                }).collect::<Vec<_>>()
+                            })
+                        }
+                        Some(SampleBody::Json(_)) => {
+                            postman["request"]["body"] = json!({
+                                "mode": "raw",
+                                "raw": request.body_text(),
+                                "options": { "raw": { "language": "json" } }
+                            })
+                        }
+                        Some(SampleBody::Text(_)) => {
+                            postman["request"]["body"] = json!({
+                                "mode": "raw",
+                                "raw": request.body_text()
+                            })
+                        }
+                        None => (),
+                    }
+                    postman
+                })
+                .collect();
+            json!({ "name": module, "item": items })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&json!({
+        "info": {
+            "name": title,
+            "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+        },
+        "item": folders,
+        "variable": [{ "key": "baseUrl", "value": BASE_URL }]
+    }))
+    .unwrap()
+}
+
+/// an insomnia v4 export: a workspace, a base environment with `base_url`
+/// and a request group per module. ids are counters so the file only
+/// changes when the routes do
+pub fn to_insomnia(document: &RocketDocument, title: &str) -> String {
+    let mut resources = vec![
+        json!({
+            "_id": "wrk_1",
+            "_type": "workspace",
+            "name": title,
+            "scope": "collection"
+        }),
+        json!({
+            "_id": "env_1",
+            "_type": "environment",
+            "parentId": "wrk_1",
+            "name": "Base Environment",
+            "data": { "base_url": BASE_URL }
+        }),
+    ];
+
+    let mut request_id = 0;
+    sample_requests(document)
+        .into_iter()
+        .enumerate()
+        .for_each(|(idx, (module, requests))| {
+            let folder = format!("fld_{}", idx + 1);
+            resources.push(json!({
+                "_id": folder,
+                "_type": "request_group",
+                "parentId": "wrk_1",
+                "name": module
+            }));
+            requests.iter().for_each(|request| {
+                request_id += 1;
+                let mut insomnia = json!({
+                    "_id": format!("req_{}", request_id),
+                    "_type": "request",
+                    "parentId": folder,
+                    "name": request.name,
+                    "description": request.docs.to_owned().unwrap_or_default(),
+                    "method": request.method,
+                    "url": format!("{{{{ _.base_url }}}}{}", request.path(|name| format!(":{}", name))),
+                    "pathParameters": request.path_params.iter().map(|param| {
+                        json!({ "name": param.name, "value": param.value })
+                    }).collect::<Vec<_>>(),
+                    "parameters": request.query.iter().map(|param| {
+                        json!({ "name": param.name, "value": param.value, "disabled": !param.required })
+                    }).collect::<Vec<_>>(),
+                    "headers": request.headers.iter().map(|(name, value)| {
+                        json!({ "name": name, "value": value })
+                    }).collect::<Vec<_>>(),
+                    "body": {}
+                });
+                let mime_type = request
+                    .headers
+                    .iter()
+                    .find(|(name, _)| name == "Content-Type")
+                    .map(|(_, value)| value.to_owned());
+                match &request.body {
+                    Some(SampleBody::Form(fields)) => {
+                        insomnia["body"] = json!({
+                            "mimeType": mime_type,
+                            "params": fields.iter().map(|(name, value)| {
+                                json!({ "name": name, "value": value })
+                            }).collect::<Vec<_>>()
+                        })
+                    }
+                    Some(_) => {
+                        insomnia["body"] = json!({
+                            "mimeType": mime_type,
+                            "text": request.body_text()
+                        })
+                    }
+                    None => (),
+                }
+                resources.push(insomnia);
+            });
+        });
+
+    serde_json::to_string_pretty(&json!({
+        "_type": "export",
+        "__export_format": 4,
+        "__export_source": "rocket-doc-gen",
+        "resources": resources
+    }))
+    .unwrap()
+}
+
+/// bruno keeps a collection as a directory: `bruno.json`, an environment
+/// with `baseUrl` and a folder of `.bru` files per module
+pub fn to_bruno(document: &RocketDocument, title: &str) -> BTreeMap<String, String> {
+    let mut files = BTreeMap::new();
+    files.insert(
+        "bruno.json".to_string(),
+        serde_json::to_string_pretty(&json!({
+            "version": "1",
+            "name": title,
+            "type": "collection"
+        }))
+        .unwrap(),
+    );
+    files.insert(
+        "environments/local.bru".to_string(),
+        format!("vars {{\n  baseUrl: {}\n}}\n", BASE_URL),
+    );
+
+    sample_requests(document)
+        .into_iter()
+        .for_each(|(module, requests)| {
+            let folder = module.replace("::", ".");
+            requests.iter().enumerate().for_each(|(idx, request)| {
+                let file = request
+                    .name
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || c == '_' {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>();
+                files.insert(
+                    format!("{}/{}.bru", folder, file.trim_end_matches('_')),
+                    bru_file(request, idx + 1),
+                );
+            });
+        });
+    files
+}
+
+fn bru_block(name: &str, lines: &[String]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n{} {{\n{}}}\n",
+            name,
+            lines
+                .iter()
+                .map(|line| format!("  {}\n", line))
+                .collect::<String>()
+        )
+    }
+}
+
+fn bru_file(request: &SampleRequest, seq: usize) -> String {
+    let body_kind = match &request.body {
+        Some(SampleBody::Json(_)) => "json",
+        Some(SampleBody::Form(_)) => "formUrlEncoded",
+        Some(SampleBody::Text(_)) => "text",
+        None => "none",
+    };
+    let url = format!(
+        "{{{{baseUrl}}}}{}",
+        request.path_and_query(|name| format!(":{}", name))
+    );
+
+    let mut out = String::new();
+    write!(
+        out,
+        "meta {{\n  name: {}\n  type: http\n  seq: {}\n}}\n\n{} {{\n  url: {}\n  body: {}\n  auth: none\n}}\n",
+        request.name,
+        seq,
+        request.method.to_lowercase(),
+        url,
+        body_kind
+    )
+    .unwrap();
+
+    // disabled entries start with ~
+    let params = |params: &[SampleParam]| {
+        params
+            .iter()
+            .map(|param| {
+                format!(
+                    "{}{}: {}",
+                    if param.required { "" } else { "~" },
+                    param.name,
+                    param.value
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    out.push_str(&bru_block("params:query", &params(&request.query)));
+    out.push_str(&bru_block("params:path", &params(&request.path_params)));
+    out.push_str(&bru_block(
+        "headers",
+        &request
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<_>>(),
+    ));
+    match &request.body {
+        Some(SampleBody::Form(fields)) => out.push_str(&bru_block(
+            "body:form-urlencoded",
+            &fields
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect::<Vec<_>>(),
+        )),
+        Some(body) => {
+            let name = match body {
+                SampleBody::Json(_) => "body:json",
+                _ => "body:text",
+            };
+            let text = request.body_text().unwrap_or_default();
+            out.push_str(&bru_block(
+                name,
+                &text
+                    .lines()
+                    .map(|line| line.to_string())
+                    .collect::<Vec<_>>(),
+            ));
+        }
+        None => (),
+    }
+    if let Some(docs) = &request.docs {
+        out.push_str(&bru_block(
+            "docs",
+            &docs
+                .lines()
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>(),
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document() -> RocketDocument {
+        let ast = syn::parse_str(
+            "
+            mod api {
+                #[derive(Deserialize)]
+                struct NewUser { name: String, age: Option<u8> }
+                #[derive(FromForm)]
+                struct Filter { q: String, limit: Option<u32> }
+
+                /// Changes a user.
+                #[put(\"/users/<id>?<notify>\", format = \"json\", data = \"<user>\")]
+                fn update_user(id: u64, notify: Option<bool>, user: Json<NewUser>) {}
+
+                #[get(\"/users?<filter..>&sort=asc\")]
+                fn list_users(filter: Filter) {}
+            }
+            ",
+        )
+        .unwrap();
+        RocketDocument::parse_file(&ast)
+    }
+
+    #[test]
+    fn builds_sample_requests() {
+        let requests = sample_requests(&document());
+        let requests = &requests["crate::api"];
+
+        assert_eq!(requests[0].name, "update_user");
+        assert_eq!(requests[0].path(|name| format!(":{}", name)), "/users/:id");
+        assert_eq!(
+            requests[0].path_params,
+            vec![SampleParam {
+                name: "id".to_string(),
+                value: "0".to_string(),
+                required: true
+            }]
+        );
+        assert_eq!(
+            requests[0].query,
+            vec![SampleParam {
+                name: "notify".to_string(),
+                value: "true".to_string(),
+                required: false
+            }]
+        );
+        assert_eq!(
+            requests[0].headers,
+            vec![("Content-Type".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(
+            requests[0].body,
+            Some(SampleBody::Json(json!({ "name": "string", "age": 0 })))
+        );
+        assert_eq!(
+            requests[1]
+                .query
+                .iter()
+                .map(|param| (param.name.as_str(), param.required))
+                .collect::<Vec<_>>(),
+            vec![("q", true), ("limit", false), ("sort", true)],
+            "FromForm fields are query params of their own"
+        );
+    }
+
+    #[test]
+    fn writes_collections() {
+        let postman: Value = serde_json::from_str(&to_postman(&document(), "API")).unwrap();
+        let request = &postman["item"][0]["item"][0];
+        assert_eq!(postman["item"][0]["name"], "crate::api");
+        assert_eq!(request["request"]["url"]["raw"], "{{baseUrl}}/users/:id");
+        assert_eq!(request["request"]["url"]["path"], json!(["users", ":id"]));
+        assert_eq!(request["request"]["description"], "Changes a user.");
+        assert_eq!(request["request"]["body"]["mode"], "raw");
+
+        let insomnia: Value = serde_json::from_str(&to_insomnia(&document(), "API")).unwrap();
+        assert_eq!(insomnia["resources"][2]["_type"], "request_group");
+        assert_eq!(
+            insomnia["resources"][3]["url"],
+            "{{ _.base_url }}/users/:id"
+        );
+
+        let bruno = to_bruno(&document(), "API");
+        assert_eq!(
+            bruno["crate.api/list_users.bru"],
+            "meta {\n  name: list_users\n  type: http\n  seq: 2\n}\n\n\
+             get {\n  url: {{baseUrl}}/users?q=string&sort=asc\n  body: none\n  auth: none\n}\n\n\
+             params:query {\n  q: string\n  ~limit: 0\n  sort: asc\n}\n"
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! `rocket-doc-gen.toml`, the settings a project keeps next to its code so
+//! every run documents the api the same way. paths in it are relative to
+//! the file and the command line wins over anything set here
+
+use crate::cli::FORMATS;
+use crate::example::ExampleOverrides;
+use crate::guard::{GuardKind, Guards};
+use crate::lint::{Severity, RULES};
+use crate::openapi::Server;
+use crate::rocket_document::RocketDocument;
+use crate::rocket_route::ParamKind;
+use crate::type_mapping::{TypeMapping, TypeMappings};
+use crate::type_ref::TypeRef;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// looked for in the working directory when there's no --config
+pub const CONFIG_FILE: &str = "rocket-doc-gen.toml";
+
+/// everything is optional, an empty file is the same as no file
+#[derive(Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub servers: Vec<Server>,
+    // source files or directories, like the command line's inputs
+    pub inputs: Vec<PathBuf>,
+    // module paths to leave out, `crate::internal` or `internal`
+    pub exclude: Vec<String>,
+    // format to where it's written
+    pub outputs: BTreeMap<String, PathBuf>,
+    pub types_crate: Option<String>,
+    pub type_mappings: TypeMappings,
+    pub examples: ExampleOverrides,
+    pub guards: Guards,
+    // lint rule to its severity, instead of the rule's default
+    pub lints: BTreeMap<String, Severity>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Self::parse(&content, dir).map_err(|err| format!("invalid {}: {}", path.display(), err))
+    }
+
+    /// the file given, or rocket-doc-gen.toml in the working directory if
+    /// there is one
+    pub fn find(path: Option<&Path>) -> Result<Self, String> {
+        match path {
+            Some(path) => Self::load(path),
+            None if Path::new(CONFIG_FILE).exists() => Self::load(Path::new(CONFIG_FILE)),
+            None => Ok(Config::default()),
+        }
+    }
+
+    fn parse(content: &str, dir: &Path) -> Result<Self, String> {
+        let mut config: Config = toml::from_str(content).map_err(|err| err.to_string())?;
+        config.inputs = config.inputs.iter().map(|input| dir.join(input)).collect();
+        config.outputs = config
+            .outputs
+            .into_iter()
+            .map(|(format, output)| (format, dir.join(output)))
+            .collect();
+
+        let errors = config.validate();
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors.join("\n    "))
+        }
+    }
+
+    /// everything that's wrong, each starting with the key it's about
+    fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+
+        if self.title.as_deref() == Some("") {
+            errors.push("title: can't be empty".to_string());
+        }
+        self.servers.iter().enumerate().for_each(|(i, server)| {
+            let url = server.url.as_str();
+            if !(url.starts_with("http://") || url.starts_with("https://") || url.starts_with('/'))
+            {
+                errors.push(format!(
+                    "servers[{}].url: `{}` should start with http://, https:// or /",
+                    i, url
+                ));
+            }
+        });
+        self.inputs
+            .iter()
+            .filter(|input| !input.exists())
+            .for_each(|input| errors.push(format!("inputs: {} does not exist", input.display())));
+        self.exclude
+            .iter()
+            .filter(|module| module_path(module).is_none())
+            .for_each(|module| {
+                errors.push(format!(
+                    "exclude: `{}` is not a module path like `crate::internal`",
+                    module
+                ))
+            });
+        self.outputs
+            .keys()
+            .filter(|format| !FORMATS.contains(&format.as_str()))
+            .for_each(|format| {
+                errors.push(format!(
+                    "outputs.{}: unknown format, expected one of {}",
+                    format,
+                    FORMATS.join(", ")
+                ))
+            });
+        self.type_mappings
+            .0
+            .iter()
+            .for_each(|(ty, mapping)| match mapping {
+                TypeMapping::Schema(schema) if !schema.is_object() && !schema.is_boolean() => {
+                    errors.push(format!(
+                        "type_mappings.\"{}\": should be a type name or a schema table",
+                        ty
+                    ))
+                }
+                TypeMapping::Type(name) if TypeRef::parse(name).ident().is_none() => errors.push(
+                    format!("type_mappings.\"{}\": `{}` is not a rust type", ty, name),
+                ),
+                _ => (),
+            });
+        self.guards.0.iter().for_each(|(ty, kind)| match kind {
+            GuardKind::Header { name } | GuardKind::Cookie { name }
+                if name.is_empty() || !name.chars().all(is_token_char) =>
+            {
+                errors.push(format!(
+                    "guards.\"{}\".name: `{}` is not a valid header or cookie name",
+                    ty, name
+                ))
+            }
+            GuardKind::ApiKey { name, .. }
+                if name.is_empty() || !name.chars().all(is_token_char) =>
+            {
+                errors.push(format!(
+                    "guards.\"{}\".name: `{}` is not a valid header or cookie name",
+                    ty, name
+                ))
+            }
+            GuardKind::ApiKey { location, .. } if location != "header" && location != "cookie" => {
+                errors.push(format!(
+                    "guards.\"{}\".in: `{}` should be `header` or `cookie`",
+                    ty, location
+                ))
+            }
+            GuardKind::Auth { scheme } if scheme.is_empty() => {
+                errors.push(format!("guards.\"{}\".scheme: can't be empty", ty))
+            }
+            _ => (),
+        });
+        self.lints
+            .keys()
+            .filter(|name| !RULES.iter().any(|rule| rule.name == name.as_str()))
+            .for_each(|name| {
+                errors.push(format!(
+                    "lints.{}: unknown lint, expected one of {}",
+                    name,
+                    RULES
+                        .iter()
+                        .map(|rule| rule.name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            });
+
+        errors
+    }
+
+    /// the settings that live on the document, and the routes and types in
+    /// excluded inline modules taken out, the same as if they were in an
+    /// excluded file (those aren't parsed at all). a route or type that's
+    /// kept but uses an excluded type is an error, the docs would point at
+    /// a type that isn't there
+    pub fn apply(&self, document: &mut RocketDocument) -> Result<(), String> {
+        let errors = self.excluded_uses(document);
+        if !errors.is_empty() {
+            return Err(errors.join("\n    "));
+        }
+
+        document
+            .routes
+            .retain(|route| !self.excludes(&route.module));
+        document
+            .structs
+            .retain(|strct| !self.excludes(&strct.module));
+        document
+            .struct_instances
+            .retain(|strct| !self.excludes(&strct.module));
+        document.enums.retain(|enm| !self.excludes(&enm.module));
+        document
+            .enum_instances
+            .retain(|enm| !self.excludes(&enm.module));
+        document
+            .aliases
+            .retain(|alias| !self.excludes(&alias.module));
+        document.examples = self.examples.clone();
+        document.type_mappings = self.type_mappings.clone();
+        document.guards = self.guards.clone();
+        Ok(())
+    }
+
+    /// the kept routes and types that mention a type from an excluded module
+    fn excluded_uses(&self, document: &RocketDocument) -> Vec<String> {
+        let excluded = |ty: &TypeRef| {
+            let mut pending = vec![ty];
+            while let Some(ty) = pending.pop() {
+                pending.extend(ty.children());
+                if let Some((module, ident)) = document.declared(ty) {
+                    if self.excludes(&module) {
+                        return Some(
+                            std::iter::once("crate")
+                                .chain(module.iter().map(|segment| segment.as_str()))
+                                .chain(std::iter::once(ident.as_str()))
+                                .collect::<Vec<_>>()
+                                .join("::"),
+                        );
+                    }
+                }
+            }
+            None
+        };
+        let uses = |user: String, types: Vec<TypeRef>| {
+            let mut found: Vec<String> = types.iter().filter_map(&excluded).collect();
+            found.sort();
+            found.dedup();
+            found
+                .into_iter()
+                .map(move |ty| format!("exclude: {} uses `{}`, which is excluded", user, ty))
+        };
+
+        let routes = document
+            .routes
+            .iter()
+            .filter(|route| !self.excludes(&route.module))
+            .flat_map(|route| {
+                let types = route
+                    .params
+                    .iter()
+                    .filter(|param| param.kind != ParamKind::Guard)
+                    .map(|param| param.ty.to_owned())
+                    .chain(route.body.iter().filter_map(|body| body.schema.to_owned()))
+                    .chain(route.responses.iter().filter_map(|res| res.body.to_owned()))
+                    .collect();
+                uses(
+                    format!(
+                        "`{} {}` ({})",
+                        route.route.method.to_uppercase(),
+                        route.route.path,
+                        route.ident
+                    ),
+                    types,
+                )
+            });
+        let structs = document
+            .structs
+            .iter()
+            .filter(|strct| !self.excludes(&strct.module))
+            .flat_map(|strct| uses(format!("`{}`", strct.ident), strct.field_types()));
+        let enums = document
+            .enums
+            .iter()
+            .filter(|enm| !self.excludes(&enm.module))
+            .flat_map(|enm| uses(format!("`{}`", enm.ident), enm.field_types()));
+        routes.chain(structs).chain(enums).collect()
+    }
+
+    /// whether a module (as a path without `crate`) is excluded, along with
+    /// everything inside it
+    pub fn excludes(&self, module: &[String]) -> bool {
+        self.exclude
+            .iter()
+            .filter_map(|excluded| module_path(excluded))
+            .any(|excluded| module.starts_with(&excluded))
+    }
+}
+
+/// `crate::api::internal` as `["api", "internal"]`, None if it isn't a path
+fn module_path(path: &str) -> Option<Vec<String>> {
+    let path = path.trim();
+    let path = path
+        .strip_prefix("crate::")
+        .unwrap_or(if path == "crate" { "" } else { path });
+    if path.is_empty() {
+        return Some(vec![]);
+    }
+    path.split("::")
+        .map(|segment| {
+            syn::parse_str::<syn::Ident>(segment)
+                .ok()
+                .map(|_| segment.to_string())
+        })
+        .collect()
+}
+
+/// the characters http allows in header names, cookie names are the same
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_a_config_file() {
+        let config = Config::parse(
+            "
+            title = \"Users API\"
+            servers = [{ url = \"https://api.example.com\", description = \"production\" }]
+            inputs = [\"src\"]
+            exclude = [\"crate::internal\"]
+
+            [outputs]
+            openapi = \"docs/openapi.json\"
+
+            [type_mappings]
+            UserId = \"u64\"
+            Email = { type = \"string\", format = \"email\" }
+
+            [examples.fields]
+            \"User.name\" = \"Ada\"
+
+            [guards]
+            ApiKey = { kind = \"header\", name = \"X-Api-Key\" }
+            DbConn = { kind = \"ignore\" }
+
+            [lints]
+            missing_docs = \"deny\"
+            ",
+            Path::new("."),
+        )
+        .unwrap();
+
+        assert_eq!(config.title.as_deref(), Some("Users API"));
+        assert_eq!(config.inputs, vec![Path::new(".").join("src")]);
+        assert_eq!(
+            config.outputs["openapi"],
+            Path::new(".").join("docs/openapi.json")
+        );
+        assert_eq!(
+            config.type_mappings.0["Email"],
+            TypeMapping::Schema(json!({ "type": "string", "format": "email" }))
+        );
+        assert_eq!(config.examples.fields["User.name"], json!("Ada"));
+        assert_eq!(config.guards.0["DbConn"], GuardKind::Ignore);
+        assert_eq!(config.lints["missing_docs"], Severity::Deny);
+        assert!(config.excludes(&["internal".to_string(), "admin".to_string()]));
+        assert!(!config.excludes(&["api".to_string()]));
+    }
+
+    #[test]
+    fn reports_every_problem_with_its_key() {
+        let err = Config::parse(
+            "
+            servers = [{ url = \"api.example.com\" }]
+            inputs = [\"does-not-exist\"]
+            exclude = [\"not a module\"]
+            outputs = { pdf = \"api.pdf\" }
+
+            [guards]
+            ApiKey = { kind = \"header\", name = \"X Api Key\" }
+
+            [lints]
+            missing_doc = \"warn\"
+            ",
+            Path::new("."),
+        )
+        .unwrap_err();
+
+        assert!(err.contains("servers[0].url: `api.example.com` should start with"));
+        assert!(err.contains("inputs: ./does-not-exist does not exist"));
+        assert!(err.contains("exclude: `not a module` is not a module path"));
+        assert!(err.contains("outputs.pdf: unknown format"));
+        assert!(err.contains("guards.\"ApiKey\".name: `X Api Key` is not a valid"));
+        assert!(err.contains("lints.missing_doc: unknown lint"));
+
+        assert!(
+            Config::parse("titel = \"typo\"", Path::new("."))
+                .unwrap_err()
+                .contains("unknown field `titel`"),
+            "Unknown keys are an error instead of being ignored"
+        );
+    }
+
+    #[test]
+    fn excludes_inline_modules() {
+        let config = Config::parse("exclude = [\"crate::internal\"]", Path::new(".")).unwrap();
+        let mut document = RocketDocument::parse_file(
+            &syn::parse_str(
+                "
+                #[derive(Serialize)]
+                struct User { name: String }
+
+                mod internal {
+                    #[derive(Serialize)]
+                    struct Secret { key: String }
+                    #[derive(Serialize)]
+                    enum Level { Low, High }
+                    type Secrets = Vec<Secret>;
+
+                    #[get(\"/secret\")]
+                    fn secret() -> Json<Secret> {}
+                }
+                ",
+            )
+            .unwrap(),
+        );
+        config.apply(&mut document).unwrap();
+
+        assert!(document.routes.is_empty());
+        assert_eq!(
+            document
+                .structs
+                .iter()
+                .map(|strct| strct.ident.as_str())
+                .collect::<Vec<_>>(),
+            vec!["User"],
+            "Types in excluded modules are left out along with the routes"
+        );
+        assert!(document.enums.is_empty());
+        assert!(document.aliases.is_empty());
+    }
+
+    #[test]
+    fn fails_when_kept_routes_use_excluded_types() {
+        let config = Config::parse("exclude = [\"crate::internal\"]", Path::new(".")).unwrap();
+        let mut document = RocketDocument::parse_file(
+            &syn::parse_str(
+                "
+                #[derive(Serialize)]
+                struct User { name: String }
+
+                #[get(\"/users\")]
+                fn list_users() -> Json<Vec<internal::Secret>> {}
+
+                mod internal {
+                    #[derive(Serialize)]
+                    struct Secret { key: String }
+                }
+                ",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            config.apply(&mut document),
+            Err(
+                "exclude: `GET /users` (list_users) uses `crate::internal::Secret`, which is excluded"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! how much of the api is documented by hand rather than made up or left
+//! out: doc comments on routes, types and fields, statuses given with
+//! #[response] and examples from the config. broken down per module so it's
+//! clear where the gaps are
+
+use crate::rocket_attribute::derives_trait;
+use crate::rocket_document::{by_module, RocketDocument};
+use crate::rocket_struct::RocketStruct;
+use crate::type_mapping::lookup_keys;
+use crate::type_ref::TypeRef;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Count {
+    pub covered: usize,
+    pub total: usize,
+}
+
+impl Count {
+    fn add(&mut self, covered: bool) {
+        self.total += 1;
+        if covered {
+            self.covered += 1;
+        }
+    }
+
+    fn merge(&mut self, other: Count) {
+        self.covered += other.covered;
+        self.total += other.total;
+    }
+
+    /// nothing to cover is fully covered
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.covered as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize)]
+pub struct Coverage {
+    // routes with a doc comment
+    pub route_docs: Count,
+    // structs and enums with a doc comment
+    pub type_docs: Count,
+    // named struct fields with a doc comment, skipped ones don't count
+    pub field_docs: Count,
+    // Responder structs and variants with a #[response(status = ..)]
+    pub responses: Count,
+    // request and response bodies with an example in the config, for the
+    // type or for every one of its fields
+    pub examples: Count,
+}
+
+impl Coverage {
+    fn counts(&self) -> [(&'static str, Count); 5] {
+        [
+            ("routes", self.route_docs),
+            ("types", self.type_docs),
+            ("fields", self.field_docs),
+            ("responses", self.responses),
+            ("examples", self.examples),
+        ]
+    }
+
+    fn merge(&mut self, other: &Coverage) {
+        self.route_docs.merge(other.route_docs);
+        self.type_docs.merge(other.type_docs);
+        self.field_docs.merge(other.field_docs);
+        self.responses.merge(other.responses);
+        self.examples.merge(other.examples);
+    }
+
+    /// everything together, what the threshold is checked against
+    pub fn overall(&self) -> Count {
+        let mut overall = Count::default();
+        self.counts()
+            .iter()
+            .for_each(|(_, count)| overall.merge(*count));
+        overall
+    }
+}
+
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct CoverageReport {
+    // keyed by `crate::module::path`
+    pub modules: BTreeMap<String, Coverage>,
+    pub total: Coverage,
+}
+
+impl CoverageReport {
+    pub fn percent(&self) -> f64 {
+        self.total.overall().percent()
+    }
+}
+
+pub fn coverage(document: &RocketDocument) -> CoverageReport {
+    let mut modules: BTreeMap<String, Coverage> = BTreeMap::new();
+
+    by_module(&document.routes, |route| &route.module)
+        .into_iter()
+        .for_each(|(module, routes)| {
+            let coverage = modules.entry(module).or_default();
+            routes
+                .iter()
+                .for_each(|route| coverage.route_docs.add(route.docs.is_some()));
+        });
+
+    by_module(&document.structs, |strct| &strct.module)
+        .into_iter()
+        .for_each(|(module, structs)| {
+            let coverage = modules.entry(module).or_default();
+            structs.iter().for_each(|strct| {
+                coverage.type_docs.add(strct.docs.is_some());
+                named_fields(strct)
+                    .for_each(|name| coverage.field_docs.add(strct.field_docs.contains_key(name)));
+                if derives_trait(&strct.derives, "Responder") {
+                    coverage.responses.add(
+                        strct
+                            .response
+                            .as_ref()
+                            .and_then(|response| response.status)
+                            .is_some(),
+                    );
+                }
+                if strct.direction.is_some() {
+                    coverage.examples.add(
+                        has_type_example(document, &strct.ident)
+                            || has_field_examples(document, strct),
+                    );
+                }
+            });
+        });
+
+    by_module(&document.enums, |enm| &enm.module)
+        .into_iter()
+        .for_each(|(module, enums)| {
+            let coverage = modules.entry(module).or_default();
+            enums.iter().for_each(|enm| {
+                coverage.type_docs.add(enm.docs.is_some());
+                if derives_trait(&enm.derives, "Responder") {
+                    enm.variants.iter().for_each(|variant| {
+                        coverage.responses.add(
+                            variant
+                                .response
+                                .as_ref()
+                                .and_then(|response| response.status)
+                                .is_some(),
+                        )
+                    });
+                }
+                if enm.direction.is_some() {
+                    coverage
+                        .examples
+                        .add(has_type_example(document, &enm.ident));
+                }
+            });
+        });
+
+    let mut total = Coverage::default();
+    modules.values().for_each(|coverage| total.merge(coverage));
+    CoverageReport { modules, total }
+}
+
+/// the fields that end up in the serialized form, tuple structs have none
+fn named_fields(strct: &RocketStruct) -> impl Iterator<Item = &String> {
+    strct
+        .fields
+        .iter()
+        .map(|(name, _)| name)
+        .filter(|name| name.parse::<usize>().is_err())
+        .filter(move |name| {
+            !strct
+                .serde
+                .field(name)
+                .map(|field| field.skip.skipped(strct.direction))
+                .unwrap_or(false)
+        })
+}
+
+fn has_type_example(document: &RocketDocument, ident: &str) -> bool {
+    lookup_keys(&TypeRef::parse(ident))
+        .iter()
+        .any(|key| document.examples.types.contains_key(key))
+}
+
+/// every field has one, by its rust name or its serialized one
+fn has_field_examples(document: &RocketDocument, strct: &RocketStruct) -> bool {
+    let fields = &document.examples.fields;
+    let mut names = named_fields(strct).peekable();
+    names.peek().is_some()
+        && names.all(|name| {
+            fields.contains_key(&format!("{}.{}", strct.ident, name))
+                || fields.contains_key(&format!("{}.{}", strct.ident, strct.serde.field_name(name)))
+        })
+}
+
+fn cell(count: Count) -> String {
+    if count.total == 0 {
+        "-".to_string()
+    } else {
+        format!(
+            "{}/{} {:>3.0}%",
+            count.covered,
+            count.total,
+            count.percent()
+        )
+    }
+}
+
+/// a table with a row per module and a total, and the overall number
+pub fn to_text(report: &CoverageReport) -> String {
+    let width = report
+        .modules
+        .keys()
+        .map(|module| module.len())
+        .max()
+        .unwrap_or(0)
+        .max("module".len());
+    let mut out = String::new();
+
+    write!(out, "{:<width$}", "module", width = width).unwrap();
+    report
+        .total
+        .counts()
+        .iter()
+        .for_each(|(name, _)| write!(out, "  {:>14}", name).unwrap());
+    out.push('\n');
+    report
+        .modules
+        .iter()
+        .chain(std::iter::once((&"total".to_string(), &report.total)))
+        .for_each(|(module, coverage)| {
+            write!(out, "{:<width$}", module, width = width).unwrap();
+            coverage
+                .counts()
+                .iter()
+                .for_each(|(_, count)| write!(out, "  {:>14}", cell(*count)).unwrap());
+            out.push('\n');
+        });
+
+    let overall = report.total.overall();
+    writeln!(
+        out,
+        "\ndocumentation coverage: {:.1}% ({} of {})",
+        overall.percent(),
+        overall.covered,
+        overall.total
+    )
+    .unwrap();
+    out
+}
+
+pub fn to_json(report: &CoverageReport) -> String {
+    let mut value = serde_json::to_value(report).unwrap();
+    value["percent"] = serde_json::json!((report.percent() * 10.0).round() / 10.0);
+    serde_json::to_string_pretty(&value).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counts_what_is_documented_per_module() {
+        let mut document = RocketDocument::parse_file(
+            &syn::parse_str(
+                "
+                /// the users
+                #[get(\"/users\")]
+                fn list_users() -> Json<Vec<User>> {}
+
+                mod admin {
+                    #[delete(\"/users/<id>\")]
+                    fn delete_user(id: u64) -> Answer {}
+
+                    #[derive(Responder)]
+                    enum Answer {
+                        #[response(status = 204)]
+                        Deleted(()),
+                        Missing(String),
+                    }
+                }
+
+                /// someone
+                #[derive(Serialize)]
+                struct User {
+                    /// what they go by
+                    name: String,
+                    email: String,
+                    #[serde(skip)]
+                    password: String,
+                }
+                ",
+            )
+            .unwrap(),
+        );
+        document
+            .examples
+            .fields
+            .insert("User.name".to_string(), serde_json::json!("Ada"));
+
+        let report = coverage(&document);
+        let root = &report.modules["crate"];
+        assert_eq!(
+            root.route_docs,
+            Count {
+                covered: 1,
+                total: 1
+            }
+        );
+        assert_eq!(
+            root.type_docs,
+            Count {
+                covered: 1,
+                total: 1
+            }
+        );
+        assert_eq!(
+            root.field_docs,
+            Count {
+                covered: 1,
+                total: 2
+            }
+        );
+        assert_eq!(
+            root.examples,
+            Count {
+                covered: 0,
+                total: 1
+            }
+        );
+        let admin = &report.modules["crate::admin"];
+        assert_eq!(
+            admin.route_docs,
+            Count {
+                covered: 0,
+                total: 1
+            }
+        );
+        assert_eq!(
+            admin.responses,
+            Count {
+                covered: 1,
+                total: 2
+            }
+        );
+
+        // 4 of 10 across the board
+        assert_eq!(
+            report.total.overall(),
+            Count {
+                covered: 4,
+                total: 10
+            }
+        );
+        assert!(to_text(&report).ends_with("documentation coverage: 40.0% (4 of 10)\n"));
+
+        document.examples.fields.insert(
+            "User.email".to_string(),
+            serde_json::json!("ada@example.com"),
+        );
+        assert_eq!(
+            coverage(&document).modules["crate"].examples,
+            Count {
+                covered: 1,
+                total: 1
+            }
+        );
+    }
+}
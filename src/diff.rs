@@ -0,0 +1,1106 @@
+//! what changed between two versions of the api, from the documents the
+//! `json` format writes. everything is compared the way it looks on the
+//! wire (the schemas, not the rust types) and graded by what it does to a
+//! client written against the old version
+
+use crate::openapi::unwrap_param;
+use crate::rocket_document::RocketDocument;
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_uri::{RocketUri, UriSegment};
+use crate::schema::SchemaGenerator;
+use crate::type_ref::TypeRef;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::Path;
+
+const REF_PREFIX: &str = "#/$defs/";
+
+/// the semver bump a change calls for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    RouteAdded,
+    RouteRemoved,
+    // same handler, different method or path
+    RouteMoved,
+    // same method and path, different handler
+    RouteRenamed,
+    DocsChanged,
+    BodyAdded,
+    BodyRemoved,
+    MediaTypeChanged,
+    StatusAdded,
+    StatusRemoved,
+    FieldAdded,
+    FieldRemoved,
+    FieldRequired,
+    FieldOptional,
+    NullabilityChanged,
+    EnumNarrowed,
+    EnumWidened,
+    TypeChanged,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Change {
+    pub bump: Bump,
+    pub kind: ChangeKind,
+    // `GET /users/{id}`, `PUT /users/{id} query` or a type like `User.email`
+    pub location: String,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct DiffReport {
+    // the biggest bump any of the changes calls for
+    pub bump: Bump,
+    pub changes: Vec<Change>,
+}
+
+impl Change {
+    /// something that was there before and isn't now, whatever it does to
+    /// clients
+    pub fn is_removal(&self) -> bool {
+        matches!(
+            self.kind,
+            ChangeKind::RouteRemoved
+                | ChangeKind::BodyRemoved
+                | ChangeKind::StatusRemoved
+                | ChangeKind::FieldRemoved
+                | ChangeKind::EnumNarrowed
+        )
+    }
+}
+
+impl DiffReport {
+    /// the changes under a title for each kind of bump, empty ones left out.
+    /// minor changes that take something away aren't additions, they get
+    /// their own section
+    pub fn sections(&self) -> Vec<(&'static str, Vec<&Change>)> {
+        [
+            (Bump::Major, None, "breaking changes"),
+            (Bump::Minor, Some(false), "additions"),
+            (Bump::Minor, Some(true), "removals"),
+            (Bump::Patch, None, "other changes"),
+        ]
+        .iter()
+        .map(|(bump, removal, title)| {
+            let changes = self
+                .changes
+                .iter()
+                .filter(|change| change.bump == *bump)
+                .filter(|change| match removal {
+                    Some(removal) => change.is_removal() == *removal,
+                    None => true,
+                })
+                .collect::<Vec<_>>();
+            (*title, changes)
+        })
+        .filter(|(_, changes)| !changes.is_empty())
+        .collect()
+    }
+}
+
+/// which way a schema's data goes. a field the server stops sending breaks
+/// clients, a field it stops reading doesn't
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Direction {
+    Request,
+    Response,
+}
+
+impl Direction {
+    fn name(self) -> &'static str {
+        match self {
+            Direction::Request => "requests",
+            Direction::Response => "responses",
+        }
+    }
+}
+
+/// a document written by the `json` format
+pub fn load_document(path: &Path) -> Result<RocketDocument, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+    serde_json::from_str(&content).map_err(|err| {
+        format!(
+            "could not parse {}, it should be the output of the json format: {}",
+            path.display(),
+            err
+        )
+    })
+}
+
+/// one version of the api with the schemas of everything its routes use
+struct Side<'a> {
+    generator: SchemaGenerator<'a>,
+    definitions: BTreeMap<String, Value>,
+}
+
+impl<'a> Side<'a> {
+    fn new(document: &'a RocketDocument) -> Self {
+        let generator = SchemaGenerator::new(document, REF_PREFIX);
+        let roots = document
+            .routes
+            .iter()
+            .flat_map(|route| {
+                route
+                    .params
+                    .iter()
+                    .filter(|param| param.kind != ParamKind::Guard)
+                    .map(|param| param.ty.to_owned())
+                    .chain(route.body.iter().filter_map(|body| body.schema.to_owned()))
+                    .chain(route.responses.iter().filter_map(|res| res.body.to_owned()))
+            })
+            .collect();
+        let definitions = generator.definitions(roots);
+        Side {
+            generator,
+            definitions,
+        }
+    }
+
+    /// a schema with a `$ref` at the top swapped for the definition (and its
+    /// name) and `allOf` merged into one object
+    fn resolve(&self, schema: &Value) -> (Option<String>, Value) {
+        let name = schema
+            .get("$ref")
+            .and_then(|reference| reference.as_str())
+            .and_then(|reference| reference.strip_prefix(REF_PREFIX));
+        let schema = match name {
+            Some(name) => self.definitions.get(name).cloned().unwrap_or(json!({})),
+            None => schema.to_owned(),
+        };
+        let name = name.map(|name| name.to_string());
+
+        match schema.get("allOf").and_then(|parts| parts.as_array()) {
+            Some(parts) => {
+                let mut properties = Map::new();
+                let mut required = vec![];
+                parts.iter().for_each(|part| {
+                    let (_, part) = self.resolve(part);
+                    if let Some(Value::Object(props)) = part.get("properties") {
+                        properties.extend(props.to_owned());
+                    }
+                    if let Some(Value::Array(names)) = part.get("required") {
+                        required.extend(names.to_owned());
+                    }
+                });
+                (
+                    name,
+                    json!({ "type": "object", "properties": properties, "required": required }),
+                )
+            }
+            None => (name, schema),
+        }
+    }
+
+    /// every query param as a property of one object, FromForm structs put
+    /// their fields in it since those are what the client sends
+    fn query_schema(&self, route: &RocketRoute) -> Value {
+        let mut properties = Map::new();
+        let mut required = vec![];
+        route.uri.query.iter().for_each(|segment| match segment {
+            UriSegment::Static { value } => {
+                let mut kv = value.splitn(2, '=');
+                let name = kv.next().unwrap_or_default().to_string();
+                properties.insert(
+                    name.to_owned(),
+                    json!({ "const": kv.next().unwrap_or_default() }),
+                );
+                required.push(json!(name));
+            }
+            _ => route
+                .params
+                .iter()
+                .filter(|param| Some(param.ident.as_str()) == segment.name())
+                .for_each(|param| {
+                    let optional = param.ty.ident() == Some("Option");
+                    let schema = self.generator.type_schema(unwrap_param(&param.ty));
+                    match (param.kind, self.resolve(&schema).1) {
+                        (ParamKind::QueryForm, form) if form.get("properties").is_some() => {
+                            if let Some(Value::Object(props)) = form.get("properties") {
+                                properties.extend(props.to_owned());
+                            }
+                            if let (false, Some(Value::Array(names))) =
+                                (optional, form.get("required"))
+                            {
+                                required.extend(names.to_owned());
+                            }
+                        }
+                        _ => {
+                            properties.insert(param.ident.to_owned(), schema);
+                            if !optional {
+                                required.push(json!(param.ident));
+                            }
+                        }
+                    }
+                }),
+        });
+        json!({ "type": "object", "properties": properties, "required": required })
+    }
+}
+
+/// `Option<T>` is `anyOf: [T, null]`, T and whether it can be null
+fn nullable(schema: &Value) -> (&Value, bool) {
+    match schema.get("anyOf").and_then(|schemas| schemas.as_array()) {
+        Some(schemas) if schemas.len() == 2 && schemas[1] == json!({ "type": "null" }) => {
+            (&schemas[0], true)
+        }
+        _ => (schema, false),
+    }
+}
+
+/// what kind of thing a schema is, two schemas of a different shape can't
+/// be compared any further
+fn shape(schema: &Value) -> Option<&str> {
+    if schema.get("oneOf").is_some() {
+        Some("oneOf")
+    } else if schema.get("const").is_some() {
+        Some("const")
+    } else {
+        schema.get("type").and_then(|ty| ty.as_str())
+    }
+}
+
+/// how a variant of a oneOf is told apart from the others: its name for
+/// unit variants and externally tagged ones, its tag for internally tagged
+fn variant_key(schema: &Value) -> Option<String> {
+    if let Some(Value::String(name)) = schema.get("const") {
+        return Some(name.to_owned());
+    }
+    let properties = schema.get("properties")?.as_object()?;
+    if let Some(Value::String(tag)) = properties.values().find_map(|prop| prop.get("const")) {
+        return Some(tag.to_owned());
+    }
+    match properties.keys().collect::<Vec<_>>().as_slice() {
+        [name] if schema.get("additionalProperties") == Some(&json!(false)) => {
+            Some(name.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn required(schema: &Value) -> BTreeSet<&str> {
+    schema
+        .get("required")
+        .and_then(|names| names.as_array())
+        .map(|names| names.iter().filter_map(|name| name.as_str()).collect())
+        .unwrap_or_default()
+}
+
+struct Differ<'a> {
+    old: Side<'a>,
+    new: Side<'a>,
+    changes: Vec<Change>,
+    // definitions already compared, each one is only reported once
+    seen: BTreeSet<(String, String, Direction)>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, bump: Bump, kind: ChangeKind, location: &str, message: String) {
+        self.changes.push(Change {
+            bump,
+            kind,
+            location: location.to_string(),
+            message,
+        })
+    }
+
+    /// keyed by method and path, plus the rank when there is one, since
+    /// routes that only differ by rank are different endpoints
+    fn endpoints(document: &RocketDocument) -> BTreeMap<String, (&RocketRoute, RocketUri)> {
+        document
+            .routes
+            .iter()
+            .flat_map(|route| {
+                route.full_uris().into_iter().map(move |uri| {
+                    let mut key = format!(
+                        "{} {}",
+                        route.route.method.to_uppercase(),
+                        uri.templated_path()
+                    );
+                    if let Some(rank) = route.route.rank {
+                        write!(key, " (rank {})", rank).unwrap();
+                    }
+                    (key, (route, uri))
+                })
+            })
+            .collect()
+    }
+
+    fn diff_routes(&mut self, old: &RocketDocument, new: &RocketDocument) {
+        let handler = |route: &RocketRoute| {
+            route
+                .module
+                .iter()
+                .chain(std::iter::once(&route.ident))
+                .map(|segment| segment.as_str())
+                .collect::<Vec<_>>()
+                .join("::")
+        };
+        let old_endpoints = Self::endpoints(old);
+        let new_endpoints = Self::endpoints(new);
+        let mut moved = BTreeSet::new();
+
+        old_endpoints
+            .iter()
+            .for_each(|(key, (old_route, old_uri))| {
+                if let Some((new_route, new_uri)) = new_endpoints.get(key) {
+                    // nothing changes on the wire, only the names generated
+                    // clients give the call
+                    if old_route.ident != new_route.ident {
+                        self.push(
+                            Bump::Minor,
+                            ChangeKind::RouteRenamed,
+                            key,
+                            format!(
+                                "handled by `{}` instead of `{}`",
+                                new_route.ident, old_route.ident
+                            ),
+                        );
+                    }
+                    self.diff_route(key, (old_route, old_uri), (new_route, new_uri));
+                    return;
+                }
+                // the same handler somewhere else is a move, not a removal
+                let target = new_endpoints.iter().find(|(new_key, (new_route, _))| {
+                    !old_endpoints.contains_key(*new_key)
+                        && !moved.contains(*new_key)
+                        && handler(new_route) == handler(old_route)
+                });
+                match target {
+                    Some((new_key, (new_route, new_uri))) => {
+                        moved.insert(new_key.to_owned());
+                        self.push(
+                            Bump::Major,
+                            ChangeKind::RouteMoved,
+                            key,
+                            format!("moved to `{}`", new_key),
+                        );
+                        self.diff_route(new_key, (old_route, old_uri), (new_route, new_uri));
+                    }
+                    None => self.push(
+                        Bump::Major,
+                        ChangeKind::RouteRemoved,
+                        key,
+                        "removed".to_string(),
+                    ),
+                }
+            });
+
+        new_endpoints
+            .keys()
+            .filter(|key| !old_endpoints.contains_key(*key) && !moved.contains(*key))
+            .for_each(|key| {
+                self.push(
+                    Bump::Minor,
+                    ChangeKind::RouteAdded,
+                    key,
+                    "added".to_string(),
+                )
+            });
+    }
+
+    fn diff_route(
+        &mut self,
+        location: &str,
+        (old, old_uri): (&RocketRoute, &RocketUri),
+        (new, new_uri): (&RocketRoute, &RocketUri),
+    ) {
+        if old.docs != new.docs {
+            self.push(
+                Bump::Patch,
+                ChangeKind::DocsChanged,
+                location,
+                "docs changed".to_string(),
+            );
+        }
+
+        // path params by position, their names aren't sent
+        let path_params = |route: &RocketRoute, uri: &RocketUri, side: &Side| -> Vec<Value> {
+            uri.path
+                .iter()
+                .filter_map(|segment| segment.name())
+                .filter_map(|name| route.params.iter().find(|param| param.ident == name))
+                .map(|param| side.generator.type_schema(unwrap_param(&param.ty)))
+                .collect()
+        };
+        let old_params = path_params(old, old_uri, &self.old);
+        let new_params = path_params(new, new_uri, &self.new);
+        old_params
+            .iter()
+            .zip(new_params.iter())
+            .enumerate()
+            .for_each(|(i, (old_param, new_param))| {
+                self.diff_schema(
+                    old_param,
+                    new_param,
+                    Direction::Request,
+                    &format!("{} path param {}", location, i + 1),
+                )
+            });
+
+        let (old_query, new_query) = (self.old.query_schema(old), self.new.query_schema(new));
+        self.diff_schema(
+            &old_query,
+            &new_query,
+            Direction::Request,
+            &format!("{} query", location),
+        );
+
+        self.diff_body(location, old, new);
+        self.diff_responses(location, old, new);
+    }
+
+    fn diff_body(&mut self, location: &str, old: &RocketRoute, new: &RocketRoute) {
+        let location = format!("{} body", location);
+        match (&old.body, &new.body) {
+            (None, Some(body)) if body.required => self.push(
+                Bump::Major,
+                ChangeKind::BodyAdded,
+                &location,
+                "a body is required now".to_string(),
+            ),
+            (None, Some(_)) => self.push(
+                Bump::Minor,
+                ChangeKind::BodyAdded,
+                &location,
+                "an optional body can be sent".to_string(),
+            ),
+            (Some(_), None) => self.push(
+                Bump::Minor,
+                ChangeKind::BodyRemoved,
+                &location,
+                "the body isn't read anymore".to_string(),
+            ),
+            (Some(old_body), Some(new_body)) => {
+                if old_body.media_type != new_body.media_type {
+                    self.push(
+                        Bump::Major,
+                        ChangeKind::MediaTypeChanged,
+                        &location,
+                        format!(
+                            "sent as {} instead of {}",
+                            new_body.media_type.as_deref().unwrap_or("anything"),
+                            old_body.media_type.as_deref().unwrap_or("anything")
+                        ),
+                    );
+                }
+                if !old_body.required && new_body.required {
+                    self.push(
+                        Bump::Major,
+                        ChangeKind::BodyAdded,
+                        &location,
+                        "the body is required now".to_string(),
+                    );
+                }
+                if let (Some(old_schema), Some(new_schema)) = (&old_body.schema, &new_body.schema) {
+                    let old_schema = self.old.generator.type_schema(old_schema);
+                    let new_schema = self.new.generator.type_schema(new_schema);
+                    self.diff_schema(&old_schema, &new_schema, Direction::Request, &location);
+                }
+            }
+            (None, None) => (),
+        }
+    }
+
+    /// responses by status, bodies by content type. a status the client
+    /// handled going away breaks it, a new one is something to handle
+    fn diff_responses(&mut self, location: &str, old: &RocketRoute, new: &RocketRoute) {
+        fn by_status(route: &RocketRoute) -> BTreeMap<String, BTreeMap<String, Option<TypeRef>>> {
+            let mut out: BTreeMap<String, BTreeMap<String, Option<TypeRef>>> = BTreeMap::new();
+            route.responses.iter().for_each(|res| {
+                let status = res
+                    .status
+                    .map(|status| status.to_string())
+                    .unwrap_or_else(|| "default".to_string());
+                let bodies = out.entry(status).or_default();
+                if res.body.is_some() || res.content_type.is_some() {
+                    bodies
+                        .entry(
+                            res.content_type
+                                .to_owned()
+                                .unwrap_or_else(|| "*/*".to_string()),
+                        )
+                        .or_insert_with(|| res.body.to_owned());
+                }
+            });
+            out
+        }
+        let old_statuses = by_status(old);
+        let new_statuses = by_status(new);
+
+        old_statuses.iter().for_each(|(status, old_bodies)| {
+            let location = format!("{} {}", location, status);
+            let new_bodies = match new_statuses.get(status) {
+                Some(bodies) => bodies,
+                None => {
+                    return self.push(
+                        Bump::Major,
+                        ChangeKind::StatusRemoved,
+                        &location,
+                        "isn't responded with anymore".to_string(),
+                    )
+                }
+            };
+            old_bodies.iter().for_each(|(content_type, old_body)| {
+                match (old_body, new_bodies.get(content_type)) {
+                    (_, None) => self.push(
+                        Bump::Major,
+                        ChangeKind::MediaTypeChanged,
+                        &location,
+                        format!("isn't sent as {} anymore", content_type),
+                    ),
+                    (Some(old_ty), Some(Some(new_ty))) => {
+                        let old_schema = self.old.generator.type_schema(old_ty);
+                        let new_schema = self.new.generator.type_schema(new_ty);
+                        self.diff_schema(&old_schema, &new_schema, Direction::Response, &location)
+                    }
+                    _ => (),
+                }
+            });
+            new_bodies
+                .keys()
+                .filter(|content_type| !old_bodies.contains_key(*content_type))
+                .for_each(|content_type| {
+                    self.push(
+                        Bump::Minor,
+                        ChangeKind::MediaTypeChanged,
+                        &location,
+                        format!("can be sent as {} too", content_type),
+                    )
+                });
+        });
+        new_statuses
+            .keys()
+            .filter(|status| !old_statuses.contains_key(*status))
+            .for_each(|status| {
+                self.push(
+                    Bump::Minor,
+                    ChangeKind::StatusAdded,
+                    &format!("{} {}", location, status),
+                    "is a new response".to_string(),
+                )
+            });
+    }
+
+    /// a change that lets through more than before is fine in requests and
+    /// breaks clients in responses, one that lets through less is the
+    /// other way around
+    fn graded(direction: Direction, widened: bool) -> Bump {
+        match (direction, widened) {
+            (Direction::Request, true) | (Direction::Response, false) => Bump::Minor,
+            _ => Bump::Major,
+        }
+    }
+
+    fn diff_schema(&mut self, old: &Value, new: &Value, direction: Direction, location: &str) {
+        let (old_name, old) = self.old.resolve(old);
+        let (new_name, new) = self.new.resolve(new);
+        // changes inside a named type are reported against the type
+        let location = match (&old_name, &new_name) {
+            (Some(old_name), Some(new_name)) => {
+                if !self
+                    .seen
+                    .insert((old_name.to_owned(), new_name.to_owned(), direction))
+                {
+                    return;
+                }
+                new_name.to_owned()
+            }
+            _ => location.to_string(),
+        };
+
+        let (old, old_null) = nullable(&old);
+        let (new, new_null) = nullable(&new);
+        if old_null || new_null {
+            if old_null != new_null {
+                self.push(
+                    Self::graded(direction, new_null),
+                    ChangeKind::NullabilityChanged,
+                    &location,
+                    format!(
+                        "{} be null in {} now",
+                        if new_null { "can" } else { "can't" },
+                        direction.name()
+                    ),
+                );
+            }
+            let (old, new) = (old.to_owned(), new.to_owned());
+            return self.diff_schema(&old, &new, direction, &location);
+        }
+
+        // types we know nothing about can't be compared
+        if shape(old).is_none() || shape(new).is_none() {
+            return;
+        }
+        if shape(old) != shape(new) || old.get("format") != new.get("format") {
+            return self.push(
+                Bump::Major,
+                ChangeKind::TypeChanged,
+                &location,
+                format!("is {} instead of {}", describe(new), describe(old)),
+            );
+        }
+        if old.get("const") != new.get("const") {
+            return self.push(
+                Bump::Major,
+                ChangeKind::TypeChanged,
+                &location,
+                format!("is {} instead of {}", new["const"], old["const"]),
+            );
+        }
+
+        if let (Some(Value::Array(old_values)), Some(Value::Array(new_values))) =
+            (old.get("enum"), new.get("enum"))
+        {
+            let names = |values: &Vec<Value>| -> Vec<(String, Value)> {
+                values
+                    .iter()
+                    .map(|value| (value.as_str().unwrap_or_default().to_string(), json!({})))
+                    .collect()
+            };
+            self.diff_variants(names(old_values), names(new_values), direction, &location);
+        }
+        if let (Some(Value::Array(old_variants)), Some(Value::Array(new_variants))) =
+            (old.get("oneOf"), new.get("oneOf"))
+        {
+            let keyed = |side: &Side, variants: &Vec<Value>| -> Vec<(String, Value)> {
+                variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, variant)| {
+                        let key = variant_key(&side.resolve(variant).1)
+                            .unwrap_or_else(|| format!("#{}", i));
+                        (key, variant.to_owned())
+                    })
+                    .collect()
+            };
+            let old_variants = keyed(&self.old, old_variants);
+            let new_variants = keyed(&self.new, new_variants);
+            self.diff_variants(old_variants, new_variants, direction, &location);
+        }
+
+        if let (Some(Value::Object(old_props)), Some(Value::Object(new_props))) =
+            (old.get("properties"), new.get("properties"))
+        {
+            self.diff_properties(old, new, old_props, new_props, direction, &location);
+        }
+        if let (Some(old_values), Some(new_values)) = (
+            old.get("additionalProperties")
+                .filter(|values| values.is_object()),
+            new.get("additionalProperties")
+                .filter(|values| values.is_object()),
+        ) {
+            self.diff_schema(
+                old_values,
+                new_values,
+                direction,
+                &format!("{}{{}}", location),
+            );
+        }
+        if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+            self.diff_schema(old_items, new_items, direction, &format!("{}[]", location));
+        }
+        if let (Some(Value::Array(old_items)), Some(Value::Array(new_items))) =
+            (old.get("prefixItems"), new.get("prefixItems"))
+        {
+            if old_items.len() != new_items.len() {
+                return self.push(
+                    Bump::Major,
+                    ChangeKind::TypeChanged,
+                    &location,
+                    format!(
+                        "has {} elements instead of {}",
+                        new_items.len(),
+                        old_items.len()
+                    ),
+                );
+            }
+            old_items.iter().zip(new_items.iter()).enumerate().for_each(
+                |(i, (old_item, new_item))| {
+                    self.diff_schema(
+                        old_item,
+                        new_item,
+                        direction,
+                        &format!("{}.{}", location, i),
+                    )
+                },
+            );
+        }
+    }
+
+    fn diff_variants(
+        &mut self,
+        old: Vec<(String, Value)>,
+        new: Vec<(String, Value)>,
+        direction: Direction,
+        location: &str,
+    ) {
+        old.iter().for_each(|(key, old_variant)| {
+            match new.iter().find(|(new_key, _)| new_key == key) {
+                Some((_, new_variant)) => self.diff_schema(
+                    old_variant,
+                    new_variant,
+                    direction,
+                    &format!("{}::{}", location, key),
+                ),
+                None => self.push(
+                    Self::graded(direction, false),
+                    ChangeKind::EnumNarrowed,
+                    location,
+                    match direction {
+                        Direction::Request => format!("`{}` isn't accepted anymore", key),
+                        Direction::Response => format!("`{}` is no longer returned", key),
+                    },
+                ),
+            }
+        });
+        new.iter()
+            .filter(|(key, _)| !old.iter().any(|(old_key, _)| old_key == key))
+            .for_each(|(key, _)| {
+                self.push(
+                    Self::graded(direction, true),
+                    ChangeKind::EnumWidened,
+                    location,
+                    format!("`{}` was added to {}", key, direction.name()),
+                )
+            });
+    }
+
+    fn diff_properties(
+        &mut self,
+        old: &Value,
+        new: &Value,
+        old_props: &Map<String, Value>,
+        new_props: &Map<String, Value>,
+        direction: Direction,
+        location: &str,
+    ) {
+        let (old_required, new_required) = (required(old), required(new));
+        let closed = new.get("additionalProperties") == Some(&json!(false));
+
+        old_props.iter().for_each(|(name, old_prop)| {
+            let field = format!("{}.{}", location, name);
+            let new_prop = match new_props.get(name) {
+                Some(new_prop) => new_prop,
+                None => {
+                    let bump = match direction {
+                        // the server ignores it, unless it rejects unknown fields
+                        Direction::Request if !closed => Bump::Minor,
+                        _ => Bump::Major,
+                    };
+                    return self.push(
+                        bump,
+                        ChangeKind::FieldRemoved,
+                        &field,
+                        format!("was removed from {}", direction.name()),
+                    );
+                }
+            };
+            let (old_prop, new_prop) = match (
+                old_required.contains(name.as_str()),
+                new_required.contains(name.as_str()),
+            ) {
+                (old_required, new_required) if old_required != new_required => {
+                    let message = match (direction, new_required) {
+                        (Direction::Request, true) => "has to be sent now",
+                        (Direction::Request, false) => "can be left out now",
+                        (Direction::Response, true) => "is always sent now",
+                        (Direction::Response, false) => "can be missing from responses now",
+                    };
+                    self.push(
+                        Self::graded(direction, !new_required),
+                        if new_required {
+                            ChangeKind::FieldRequired
+                        } else {
+                            ChangeKind::FieldOptional
+                        },
+                        &field,
+                        message.to_string(),
+                    );
+                    // an Option field going away or coming back is the same
+                    // change, it doesn't need reporting as a null too
+                    (nullable(old_prop).0, nullable(new_prop).0)
+                }
+                _ => (old_prop, new_prop),
+            };
+            self.diff_schema(old_prop, new_prop, direction, &field);
+        });
+
+        new_props
+            .keys()
+            .filter(|name| !old_props.contains_key(*name))
+            .for_each(|name| {
+                let field = format!("{}.{}", location, name);
+                let required = new_required.contains(name.as_str());
+                let (bump, message) = match direction {
+                    Direction::Request if required => {
+                        (Bump::Major, "was added to requests and is required")
+                    }
+                    Direction::Request => (Bump::Minor, "was added to requests"),
+                    Direction::Response => (Bump::Minor, "was added to responses"),
+                };
+                self.push(bump, ChangeKind::FieldAdded, &field, message.to_string())
+            });
+    }
+}
+
+/// a schema in a few words, for messages
+fn describe(schema: &Value) -> String {
+    match (
+        shape(schema),
+        schema.get("format").and_then(|format| format.as_str()),
+    ) {
+        (Some(shape), Some(format)) => format!("{} ({})", shape, format),
+        (Some("oneOf"), None) => "an enum".to_string(),
+        (Some(shape), None) => shape.to_string(),
+        (None, _) => "unknown".to_string(),
+    }
+}
+
+/// every change from `old` to `new`, breaking ones first
+pub fn diff(old: &RocketDocument, new: &RocketDocument) -> DiffReport {
+    let mut differ = Differ {
+        old: Side::new(old),
+        new: Side::new(new),
+        changes: vec![],
+        seen: BTreeSet::new(),
+    };
+    differ.diff_routes(old, new);
+
+    let mut changes = differ.changes;
+    changes.sort_by_key(|change| std::cmp::Reverse(change.bump));
+    DiffReport {
+        bump: changes
+            .iter()
+            .map(|change| change.bump)
+            .max()
+            .unwrap_or(Bump::None),
+        changes,
+    }
+}
+
+/// the report for people, a list of changes under each kind of bump
+pub fn to_text(report: &DiffReport) -> String {
+    let mut out = String::new();
+    if report.changes.is_empty() {
+        out.push_str("no changes\n");
+        return out;
+    }
+    writeln!(out, "suggested version bump: {}", bump_name(report.bump)).unwrap();
+    report.sections().iter().for_each(|(title, changes)| {
+        write!(out, "\n{}:\n", title).unwrap();
+        changes.iter().for_each(|change| {
+            writeln!(out, "  - {}: {}", change.location, change.message).unwrap()
+        });
+    });
+    out
+}
+
+pub fn to_json(report: &DiffReport) -> String {
+    serde_json::to_string_pretty(report).unwrap()
+}
+
+pub fn bump_name(bump: Bump) -> &'static str {
+    match bump {
+        Bump::None => "none",
+        Bump::Patch => "patch",
+        Bump::Minor => "minor",
+        Bump::Major => "major",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document(content: &str) -> RocketDocument {
+        let document = RocketDocument::parse_file(&syn::parse_str(content).unwrap());
+        // what's diffed is read back from the json format
+        serde_json::from_str(&serde_json::to_string(&document).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn finds_breaking_changes() {
+        let old = document(
+            "
+            #[derive(Deserialize)]
+            struct NewUser { name: String }
+            #[derive(Serialize)]
+            struct User { id: u64, name: String, role: Role }
+            #[derive(Serialize, Deserialize)]
+            enum Role { Admin, Member }
+
+            #[post(\"/users\", data = \"<user>\")]
+            fn create_user(user: Json<NewUser>) -> Json<User> {}
+            #[get(\"/users/<id>\")]
+            fn get_user(id: u64) -> Option<Json<User>> {}
+            #[delete(\"/users/<id>\")]
+            fn delete_user(id: u64) {}
+            ",
+        );
+        let new = document(
+            "
+            #[derive(Deserialize)]
+            struct NewUser { name: String, email: String }
+            #[derive(Serialize)]
+            struct User { id: u64, role: Role }
+            #[derive(Serialize, Deserialize)]
+            enum Role { Admin, Member, Guest }
+
+            #[post(\"/users\", data = \"<user>\")]
+            fn create_user(user: Json<NewUser>) -> Json<User> {}
+            #[get(\"/accounts/<id>\")]
+            fn get_user(id: u64) -> Option<Json<User>> {}
+            ",
+        );
+
+        let report = diff(&old, &new);
+        assert_eq!(report.bump, Bump::Major);
+        assert_eq!(
+            report
+                .changes
+                .iter()
+                .map(|change| (change.kind, change.location.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (ChangeKind::RouteRemoved, "DELETE /users/{id}"),
+                (ChangeKind::RouteMoved, "GET /users/{id}"),
+                (ChangeKind::FieldRemoved, "User.name"),
+                (ChangeKind::EnumWidened, "Role"),
+                (ChangeKind::FieldAdded, "NewUser.email"),
+            ]
+        );
+        assert!(to_text(&report).starts_with(
+            "suggested version bump: major\n\nbreaking changes:\n  \
+             - DELETE /users/{id}: removed\n  - GET /users/{id}: moved to `GET /accounts/{id}`\n"
+        ));
+    }
+
+    #[test]
+    fn additions_are_a_minor_bump() {
+        let old = document(
+            "
+            #[derive(Serialize)]
+            struct User { id: u64 }
+
+            #[get(\"/users/<id>\")]
+            fn get_user(id: u64) -> Json<User> {}
+            ",
+        );
+        let new = document(
+            "
+            #[derive(Serialize)]
+            struct User { id: u64, name: Option<String> }
+
+            /// Gets a user.
+            #[get(\"/users/<id>?<fields>\")]
+            fn get_user(id: u64, fields: Option<String>) -> Json<User> {}
+            #[get(\"/users\")]
+            fn list_users() -> Json<Vec<User>> {}
+            ",
+        );
+
+        let report = diff(&old, &new);
+        assert_eq!(report.bump, Bump::Minor);
+        assert_eq!(
+            report
+                .changes
+                .iter()
+                .map(|change| (change.bump, change.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (Bump::Minor, ChangeKind::FieldAdded),
+                (Bump::Minor, ChangeKind::FieldAdded),
+                (Bump::Minor, ChangeKind::RouteAdded),
+                (Bump::Patch, ChangeKind::DocsChanged),
+            ]
+        );
+        assert_eq!(diff(&old, &old).bump, Bump::None);
+    }
+
+    #[test]
+    fn tells_ranked_routes_apart() {
+        let old = document(
+            "
+            #[get(\"/posts/<id>\")]
+            fn by_id(id: u64) -> String {}
+            #[get(\"/posts/<id>\", rank = 2)]
+            fn by_name(id: String) -> String {}
+            ",
+        );
+        let new = document(
+            "
+            #[get(\"/posts/<id>\")]
+            fn post(id: u64) -> String {}
+            #[get(\"/posts/<id>\", rank = 2)]
+            fn by_name(id: String) -> Json<Post> {}
+            ",
+        );
+
+        let report = diff(&old, &new);
+        assert_eq!(
+            report
+                .changes
+                .iter()
+                .map(|change| (change.bump, change.kind, change.location.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Bump::Major,
+                    ChangeKind::MediaTypeChanged,
+                    "GET /posts/{id} (rank 2) 200"
+                ),
+                (Bump::Minor, ChangeKind::RouteRenamed, "GET /posts/{id}"),
+                (
+                    Bump::Minor,
+                    ChangeKind::MediaTypeChanged,
+                    "GET /posts/{id} (rank 2) 200"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_removed_response_variants_apart_from_additions() {
+        let old = document(
+            "
+            #[derive(Serialize)]
+            enum Status { Active, Banned }
+            #[get(\"/status\")]
+            fn status() -> Json<Status> {}
+            ",
+        );
+        let new = document(
+            "
+            #[derive(Serialize)]
+            enum Status { Active }
+            #[get(\"/status\")]
+            fn status() -> Json<Status> {}
+            #[get(\"/health\")]
+            fn health() {}
+            ",
+        );
+
+        assert_eq!(
+            to_text(&diff(&old, &new)),
+            "suggested version bump: minor\n\n\
+             additions:\n  \
+             - GET /health: added\n\n\
+             removals:\n  \
+             - Status: `Banned` is no longer returned\n"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::rocket_document::RocketDocument;
+use crate::schema::SchemaGenerator;
+use crate::type_mapping::{TypeMapping, TypeMappings};
+use crate::type_ref::TypeRef;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const REF_PREFIX: &str = "#/$defs/";
+
+/// examples to use instead of the made up ones. `types` is keyed the same
+/// way type mappings are (`User`, `Page<User>`, `chrono::DateTime`) and
+/// `fields` by `Type.field`, with the field's rust name or its serialized one
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExampleOverrides {
+    pub types: BTreeMap<String, Value>,
+    pub fields: BTreeMap<String, Value>,
+}
+
+impl ExampleOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.fields.is_empty()
+    }
+
+    /// `{ "types": { .. }, "fields": { .. } }` from a json file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+        serde_json::from_str(&content)
+            .map_err(|err| format!("could not parse {}: {}", path.display(), err))
+    }
+}
+
+/// makes up a value for a type by walking its json schema, so serde's
+/// renames, skips and enum tagging come out the way they would on the wire.
+/// the same type always gets the same value, unless the document's
+/// `examples` say what it should be
+pub struct ExampleGenerator<'a> {
+    document: &'a RocketDocument,
+    generator: SchemaGenerator<'a>,
+}
+
+impl<'a> ExampleGenerator<'a> {
+    /// types with an example given are mapped to a schema that's only that
+    /// value, so they're found the same way type mappings are
+    pub fn new(document: &'a RocketDocument) -> Self {
+        let overrides = document
+            .examples
+            .types
+            .iter()
+            .map(|(ty, value)| {
+                (
+                    ty.to_owned(),
+                    TypeMapping::Schema(json!({ "const": value })),
+                )
+            })
+            .collect();
+        ExampleGenerator {
+            document,
+            generator: SchemaGenerator::new(document, REF_PREFIX)
+                .with_mappings(TypeMappings(overrides)),
+        }
+    }
+
+    pub fn example(&self, ty: &TypeRef) -> Value {
+        let definitions = self.generator.definitions(vec![ty.to_owned()]);
+        self.schema_example(&self.generator.type_schema(ty), &definitions, &mut vec![])
+    }
+
+    /// the example for a body, json bodies as they are and anything else as
+    /// the text that's sent. bytes don't get one
+    pub fn body_example(&self, ty: &Option<TypeRef>, content_type: Option<&str>) -> Option<String> {
+        let example = self.example(ty.as_ref()?);
+        match (content_type, &example) {
+            (Some(content_type), _) if content_type.contains("json") => {
+                Some(serde_json::to_string_pretty(&example).unwrap())
+            }
+            (_, Value::String(text)) => Some(text.to_owned()),
+            (None, _) => Some(serde_json::to_string_pretty(&example).unwrap()),
+            _ => None,
+        }
+    }
+
+    /// an override for a field of a definition, the definition's name is
+    /// turned back into the type's so `Page_User.items` is `Page<User>.items`
+    fn field_override(&self, definition: &str, field: &str) -> Option<&Value> {
+        let fields = &self.document.examples.fields;
+        if fields.is_empty() {
+            return None;
+        }
+        let (ident, serde, rust_fields) = self
+            .document
+            .structs
+            .iter()
+            .chain(self.document.struct_instances.iter())
+            .find(|strct| {
+                SchemaGenerator::definition_name(
+                    &self.document.type_name(&strct.ident, &strct.module),
+                ) == definition
+            })
+            .map(|strct| (&strct.ident, &strct.serde, &strct.fields))?;
+
+        let rust_name = rust_fields
+            .iter()
+            .map(|(name, _)| name)
+            .find(|name| serde.field_name(name) == field);
+        fields
+            .get(&format!("{}.{}", ident, field))
+            .or_else(|| rust_name.and_then(|name| fields.get(&format!("{}.{}", ident, name))))
+    }
+
+    /// `seen` are the definitions we're inside of, a type that contains
+    /// itself stops at null instead of going on forever
+    fn schema_example(
+        &self,
+        schema: &Value,
+        definitions: &BTreeMap<String, Value>,
+        seen: &mut Vec<String>,
+    ) -> Value {
+        if let Some(name) = schema
+            .get("$ref")
+            .and_then(|reference| reference.as_str())
+            .and_then(|reference| reference.strip_prefix(REF_PREFIX))
+        {
+            if seen.iter().any(|seen| seen == name) {
+                return Value::Null;
+            }
+            return match definitions.get(name) {
+                Some(definition) => {
+                    seen.push(name.to_string());
+                    let example = self.schema_example(definition, definitions, seen);
+                    seen.pop();
+                    example
+                }
+                None => Value::Null,
+            };
+        }
+        if let Some(value) = schema.get("const") {
+            return value.to_owned();
+        }
+        if let Some(value) = schema.get("enum").and_then(|values| values.get(0)) {
+            return value.to_owned();
+        }
+        // the first thing that isn't null, `Option<T>` is an example of T
+        if let Some(schemas) = schema
+            .get("oneOf")
+            .or_else(|| schema.get("anyOf"))
+            .and_then(|schemas| schemas.as_array())
+        {
+            return schemas
+                .iter()
+                .find(|schema| schema.get("type") != Some(&json!("null")))
+                .map(|schema| self.schema_example(schema, definitions, seen))
+                .unwrap_or(Value::Null);
+        }
+        if let Some(schemas) = schema.get("allOf").and_then(|schemas| schemas.as_array()) {
+            let mut object = Map::new();
+            schemas.iter().for_each(|schema| {
+                if let Value::Object(fields) = self.schema_example(schema, definitions, seen) {
+                    object.extend(fields);
+                }
+            });
+            return Value::Object(object);
+        }
+
+        match schema.get("type").and_then(|ty| ty.as_str()) {
+            Some("object") => {
+                match (schema.get("properties"), schema.get("additionalProperties")) {
+                    (Some(Value::Object(properties)), _) => Value::Object(
+                        properties
+                            .iter()
+                            .map(|(name, schema)| {
+                                let field = seen
+                                    .last()
+                                    .and_then(|definition| self.field_override(definition, name));
+                                let value = match field {
+                                    Some(value) => value.to_owned(),
+                                    None => self.schema_example(schema, definitions, seen),
+                                };
+                                (name.to_owned(), value)
+                            })
+                            .collect(),
+                    ),
+                    (_, Some(values)) if values.is_object() => {
+                        json!({ "key": self.schema_example(values, definitions, seen) })
+                    }
+                    _ => json!({}),
+                }
+            }
+            Some("array") => match (schema.get("prefixItems"), schema.get("items")) {
+                (Some(Value::Array(items)), _) => Value::Array(
+                    items
+                        .iter()
+                        .map(|schema| self.schema_example(schema, definitions, seen))
+                        .collect(),
+                ),
+                (_, Some(items)) => json!([self.schema_example(items, definitions, seen)]),
+                _ => json!([]),
+            },
+            Some("string") => match schema.get("format").and_then(|format| format.as_str()) {
+                Some("date-time") => json!("2024-01-01T00:00:00Z"),
+                Some("date") => json!("2024-01-01"),
+                Some("time") => json!("00:00:00"),
+                Some("uuid") => json!("00000000-0000-0000-0000-000000000000"),
+                Some("uri") => json!("https://example.com"),
+                Some("email") => json!("user@example.com"),
+                Some("ipv4") => json!("127.0.0.1"),
+                Some("ipv6") => json!("::1"),
+                Some("decimal") => json!("0.00"),
+                _ => json!("string"),
+            },
+            Some("integer") => schema.get("minimum").cloned().unwrap_or(json!(0)),
+            Some("number") => json!(0.0),
+            Some("boolean") => json!(true),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// a value the way it goes in a url or a form, strings without their quotes
+pub fn plain_text(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.to_owned(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn makes_up_values_shaped_like_the_wire_format() {
+        let ast = syn::parse_str(
+            "
+            #[derive(Serialize)]
+            #[serde(rename_all = \"camelCase\")]
+            struct User { user_id: u64, nickname: Option<String>, roles: Vec<Role>, parent: Option<Box<User>> }
+            #[derive(Serialize)]
+            #[serde(tag = \"type\")]
+            enum Role { Admin, Member { since: u32 } }
+
+            #[get(\"/\")]
+            fn index() -> Json<User> {}
+            ",
+        )
+        .unwrap();
+        let document = RocketDocument::parse_file(&ast);
+
+        assert_eq!(
+            ExampleGenerator::new(&document).example(&TypeRef::parse("User")),
+            json!({
+                "userId": 0,
+                "nickname": "string",
+                "roles": [{ "type": "Admin" }],
+                "parent": null
+            }),
+            "Recursive types stop at null"
+        );
+    }
+
+    #[test]
+    fn uses_overrides_for_types_and_fields() {
+        let ast = syn::parse_str(
+            "
+            #[derive(Serialize)]
+            #[serde(rename_all = \"camelCase\")]
+            struct User { user_id: u64, email: String, joined: DateTime<Utc> }
+            ",
+        )
+        .unwrap();
+        let mut document = RocketDocument::parse_file(&ast);
+        document.examples = serde_json::from_value(json!({
+            "types": { "DateTime<Utc>": "2021-05-01T12:00:00Z" },
+            "fields": { "User.userId": 42, "User.email": "ada@example.com" }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            ExampleGenerator::new(&document).example(&TypeRef::parse("User")),
+            json!({
+                "userId": 42,
+                "email": "ada@example.com",
+                "joined": "2021-05-01T12:00:00Z"
+            }),
+            "Fields go by their serialized or rust name"
+        );
+        assert_eq!(
+            ExampleGenerator::new(&document).example(&TypeRef::parse("Vec<DateTime<Utc>>")),
+            json!(["2021-05-01T12:00:00Z"])
+        );
+    }
+}
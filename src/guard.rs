@@ -0,0 +1,112 @@
+//! request guards are plain types to us, what they read from the request
+//! lives in their FromRequest impl. a project says what they are so they
+//! can be documented as the headers, cookies and auth they stand for
+
+use crate::type_mapping::lookup_keys;
+use crate::type_ref::TypeRef;
+use std::collections::BTreeMap;
+
+/// what a guard takes from the request. `ignore` is for guards that don't
+/// take anything from the client (db connections, managed state)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum GuardKind {
+    Ignore,
+    Auth {
+        #[serde(default = "default_scheme")]
+        scheme: String,
+    },
+    // a key sent in a header or a cookie, security rather than a param
+    ApiKey {
+        name: String,
+        #[serde(rename = "in", default = "default_key_location")]
+        location: String,
+    },
+    Header {
+        name: String,
+    },
+    Cookie {
+        name: String,
+    },
+}
+
+fn default_scheme() -> String {
+    "bearer".to_string()
+}
+
+fn default_key_location() -> String {
+    "header".to_string()
+}
+
+impl GuardKind {
+    /// where the guard's input comes from, for the params tables
+    pub fn location(&self) -> String {
+        match self {
+            GuardKind::Ignore => "guard".to_string(),
+            GuardKind::Auth { scheme } => format!("auth ({})", scheme),
+            GuardKind::ApiKey { name, location } => format!("api key ({} {})", location, name),
+            GuardKind::Header { name } => format!("header {}", name),
+            GuardKind::Cookie { name } => format!("cookie {}", name),
+        }
+    }
+}
+
+/// guard classifications keyed by type, found the same way type mappings are
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Guards(pub BTreeMap<String, GuardKind>);
+
+impl Guards {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `Option<ApiKey>` and `Result<ApiKey, _>` are classified as `ApiKey`
+    pub fn find(&self, ty: &TypeRef) -> Option<&GuardKind> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let ty = match (ty.ident(), ty.args()) {
+            (Some("Option"), [inner]) | (Some("Result"), [inner, ..]) => inner,
+            _ => ty,
+        };
+        lookup_keys(ty).iter().find_map(|key| self.0.get(key))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn classifies_guards_by_type() {
+        let guards: Guards = serde_json::from_value(json!({
+            "ApiKey": { "kind": "header", "name": "X-Api-Key" },
+            "auth::User": { "kind": "auth" },
+            "Session": { "kind": "apikey", "name": "sid", "in": "cookie" },
+            "DbConn": { "kind": "ignore" }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            guards.find(&TypeRef::parse("Option<ApiKey>")),
+            Some(&GuardKind::Header {
+                name: "X-Api-Key".to_string()
+            })
+        );
+        assert_eq!(
+            guards
+                .find(&TypeRef::parse("auth::User"))
+                .map(|kind| kind.location()),
+            Some("auth (bearer)".to_string())
+        );
+        assert_eq!(
+            guards
+                .find(&TypeRef::parse("Session"))
+                .map(|kind| kind.location()),
+            Some("api key (cookie sid)".to_string())
+        );
+        assert_eq!(guards.find(&TypeRef::parse("Admin")), None);
+    }
+}
@@ -0,0 +1,674 @@
+use crate::example::ExampleGenerator;
+use crate::guard::GuardKind;
+use crate::html_search::{search_index_js, SEARCH_JS};
+use crate::rocket_attribute::BodyDirection;
+use crate::rocket_document::{by_module, RocketDocument};
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_uri::UriSegment;
+use crate::rocket_wrapper::status_reason;
+use crate::type_ref::TypeRef;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+const STYLE: &str = "\
+body { margin: 0; display: flex; font-family: sans-serif; line-height: 1.5; color: #222; }
+nav { width: 18rem; min-height: 100vh; padding: 1rem; background: #f4f4f4; box-sizing: border-box; }
+nav h2 { font-size: 0.9rem; margin: 1rem 0 0.25rem; }
+nav ul { list-style: none; margin: 0; padding: 0; font-size: 0.9rem; }
+main { flex: 1; padding: 1rem 2rem; max-width: 60rem; }
+a { color: #0550ae; text-decoration: none; }
+a:hover { text-decoration: underline; }
+code { font-family: monospace; }
+pre { background: #f5f5f5; padding: 0.5em; overflow-x: auto; }
+table { border-collapse: collapse; margin: 0.5rem 0 1rem; }
+th, td { border: 1px solid #ddd; padding: 0.25rem 0.5rem; text-align: left; vertical-align: top; }
+tr:target { background: #fff8c5; }
+.method { font-weight: bold; text-transform: uppercase; }
+#search { width: 100%; margin: 0.75rem 0 0.25rem; padding: 0.25rem; box-sizing: border-box; }
+#search-results li { margin-bottom: 0.25rem; }
+#search-results .summary { color: #666; font-size: 0.8rem; }
+";
+
+/// a static site for the document, file name to file content. everything is
+/// in one flat directory and only links to itself, so it can be opened
+/// straight from the filesystem without a server or network access. the nav
+/// lists every page, so it's in `nav.js` once instead of in each of them
+pub fn to_html_site(document: &RocketDocument, title: &str) -> BTreeMap<String, String> {
+    let site = Site::new(document, title);
+    let mut files = BTreeMap::new();
+
+    files.insert("style.css".to_string(), STYLE.to_string());
+    files.insert("index.html".to_string(), site.index());
+    files.insert("search.js".to_string(), SEARCH_JS.to_string());
+    files.insert("nav.js".to_string(), site.nav_js());
+    files.insert("search-index.js".to_string(), search_index_js(document));
+    document.routes.iter().for_each(|route| {
+        files.insert(route_page(route), site.route(route));
+    });
+    document.structs.iter().for_each(|strct| {
+        let mut body = site.type_header(&strct.ident, &strct.module, &strct.docs, strct.direction);
+        body.push_str(&site.fields(&strct.fields));
+        body.push_str(&site.used_by(&strct.module, &strct.ident));
+        files.insert(
+            type_page(&strct.module, &strct.ident),
+            site.page(&strct.ident, &body),
+        );
+    });
+    document.enums.iter().for_each(|enm| {
+        let mut body = site.type_header(&enm.ident, &enm.module, &enm.docs, enm.direction);
+        body.push_str("<h2>Variants</h2>\n<table>\n<tr><th>Variant</th><th>Status</th><th>Fields</th></tr>\n");
+        enm.variants.iter().for_each(|variant| {
+            let id = format!("variant-{}", variant.ident);
+            let fields = variant
+                .fields
+                .iter()
+                .map(|(name, ty)| match name.parse::<usize>() {
+                    Ok(_) => site.type_html(&TypeRef::parse(ty)),
+                    Err(_) => format!("{}: {}", escape(name), site.type_html(&TypeRef::parse(ty))),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let status = variant
+                .response
+                .as_ref()
+                .and_then(|response| response.status)
+                .map(|status| status.to_string())
+                .unwrap_or_default();
+            writeln!(
+                body,
+                "<tr id=\"{id}\"><td><a href=\"#{id}\"><code>{}</code></a></td><td>{}</td><td>{}</td></tr>",
+                escape(&variant.ident),
+                status,
+                fields,
+                id = escape(&id)
+            )
+            .unwrap();
+        });
+        body.push_str("</table>\n");
+        body.push_str(&site.used_by(&enm.module, &enm.ident));
+        files.insert(type_page(&enm.module, &enm.ident), site.page(&enm.ident, &body));
+    });
+
+    files
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// file names only use characters that are safe on every filesystem, and the
+/// module is part of them so `api::users::get` and `api::posts::get` don't
+/// end up on the same page
+fn page_name(prefix: &str, module: &[String], ident: &str) -> String {
+    let name = module
+        .iter()
+        .map(|segment| segment.as_str())
+        .chain(std::iter::once(ident))
+        .flat_map(|segment| segment.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(".");
+    format!("{}-{}.html", prefix, name)
+}
+
+pub fn route_page(route: &RocketRoute) -> String {
+    page_name("route", &route.module, &route.ident)
+}
+
+pub fn type_page(module: &[String], ident: &str) -> String {
+    page_name("type", module, ident)
+}
+
+/// docs are plain text to us, blank lines split paragraphs
+fn paragraphs(docs: &Option<String>) -> String {
+    docs.iter()
+        .flat_map(|docs| docs.split("\n\n"))
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .map(|paragraph| format!("<p>{}</p>\n", escape(paragraph.trim())))
+        .collect()
+}
+
+/// does the type mention the struct or enum declared in `module` anywhere
+/// inside it
+fn mentions(document: &RocketDocument, ty: &TypeRef, module: &[String], ident: &str) -> bool {
+    matches!(document.declared(ty), Some(declared) if declared.0 == module && declared.1 == ident)
+        || ty
+            .children()
+            .into_iter()
+            .any(|child| mentions(document, child, module, ident))
+}
+
+struct Site<'a> {
+    document: &'a RocketDocument,
+    examples: ExampleGenerator<'a>,
+    title: &'a str,
+}
+
+impl<'a> Site<'a> {
+    fn new(document: &'a RocketDocument, title: &'a str) -> Self {
+        Site {
+            document,
+            examples: ExampleGenerator::new(document),
+            title,
+        }
+    }
+
+    /// the type as it's written, with every struct and enum in it linked to
+    /// its page
+    fn type_html(&self, ty: &TypeRef) -> String {
+        format!("<code>{}</code>", self.type_links(ty))
+    }
+
+    fn type_links(&self, ty: &TypeRef) -> String {
+        let join = |types: &[TypeRef]| {
+            types
+                .iter()
+                .map(|ty| self.type_links(ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match ty {
+            TypeRef::Path { path, args } => {
+                let mut name = escape(&path.join("::"));
+                if let Some((module, ident)) = self.document.declared(ty) {
+                    name = format!("<a href=\"{}\">{}</a>", type_page(&module, &ident), name);
+                }
+                if args.is_empty() {
+                    name
+                } else {
+                    format!("{}&lt;{}&gt;", name, join(args))
+                }
+            }
+            TypeRef::Tuple { elems } if elems.len() == 1 => format!("({},)", join(elems)),
+            TypeRef::Tuple { elems } => format!("({})", join(elems)),
+            TypeRef::Array { elem } => format!("[{}]", self.type_links(elem)),
+            TypeRef::Reference { elem } => format!("&amp;{}", self.type_links(elem)),
+            TypeRef::Other { repr } => escape(repr),
+        }
+    }
+
+    /// fills in the `<nav>` every page has while the page is still loading,
+    /// so it's there before anything is shown. without scripts the nav is
+    /// just the link to the index, which lists everything too
+    fn nav_js(&self) -> String {
+        format!(
+            "document.getElementById(\"nav\").innerHTML = {};\n",
+            serde_json::to_string(&self.nav()).unwrap()
+        )
+    }
+
+    fn nav(&self) -> String {
+        let routes = by_module(&self.document.routes, |route| &route.module);
+        let structs = by_module(&self.document.structs, |strct| &strct.module);
+        let enums = by_module(&self.document.enums, |enm| &enm.module);
+        let modules: BTreeSet<&String> = routes
+            .keys()
+            .chain(structs.keys())
+            .chain(enums.keys())
+            .collect();
+
+        let mut nav = format!(
+            "\n<a href=\"index.html\"><strong>{}</strong></a>\n\
+             <input id=\"search\" type=\"search\" placeholder=\"Search\" autocomplete=\"off\">\n\
+             <ul id=\"search-results\"></ul>\n",
+            escape(self.title)
+        );
+        modules.into_iter().for_each(|module| {
+            writeln!(nav, "<h2>{}</h2>\n<ul>", escape(module)).unwrap();
+            routes.get(module).into_iter().flatten().for_each(|route| {
+                route.full_uris().iter().for_each(|uri| {
+                    writeln!(
+                        nav,
+                        "<li><a href=\"{}\"><span class=\"method\">{}</span> {}</a></li>",
+                        route_page(route),
+                        escape(&route.route.method),
+                        escape(&uri.templated_path())
+                    )
+                    .unwrap()
+                })
+            });
+            structs
+                .get(module)
+                .into_iter()
+                .flatten()
+                .map(|strct| (&strct.module, &strct.ident))
+                .chain(
+                    enums
+                        .get(module)
+                        .into_iter()
+                        .flatten()
+                        .map(|enm| (&enm.module, &enm.ident)),
+                )
+                .for_each(|(module, ident)| {
+                    writeln!(
+                        nav,
+                        "<li><a href=\"{}\"><code>{}</code></a></li>",
+                        type_page(module, ident),
+                        escape(ident)
+                    )
+                    .unwrap()
+                });
+            nav.push_str("</ul>\n");
+        });
+        nav
+    }
+
+    fn page(&self, heading: &str, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{0} - {1}</title>\n<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n\
+             <body>\n<nav id=\"nav\"><a href=\"index.html\"><strong>{1}</strong></a></nav>\n\
+             <script src=\"nav.js\"></script>\n<main>\n{2}</main>\n\
+             <script src=\"search-index.js\"></script>\n\
+             <script src=\"search.js\"></script>\n</body>\n</html>\n",
+            escape(heading),
+            escape(self.title),
+            body
+        )
+    }
+
+    fn index(&self) -> String {
+        let mut body = format!("<h1>{}</h1>\n", escape(self.title));
+
+        if !self.document.routes.is_empty() {
+            body.push_str(
+                "<h2>Routes</h2>\n<table>\n<tr><th>Method</th><th>Path</th><th>Handler</th></tr>\n",
+            );
+            self.document.routes.iter().for_each(|route| {
+                route.full_uris().iter().for_each(|uri| {
+                    writeln!(
+                        body,
+                        "<tr><td class=\"method\">{}</td><td><a href=\"{}\"><code>{}</code></a></td><td><code>{}</code></td></tr>",
+                        escape(&route.route.method),
+                        route_page(route),
+                        escape(&uri.templated_path()),
+                        escape(&route.ident)
+                    )
+                    .unwrap()
+                })
+            });
+            body.push_str("</table>\n");
+        }
+
+        // same named types show their module to tell them apart
+        let types: BTreeSet<(String, String)> = self
+            .document
+            .structs
+            .iter()
+            .map(|strct| (&strct.ident, &strct.module))
+            .chain(
+                self.document
+                    .enums
+                    .iter()
+                    .map(|enm| (&enm.ident, &enm.module)),
+            )
+            .map(|(ident, module)| {
+                (
+                    self.document.type_name(ident, module),
+                    type_page(module, ident),
+                )
+            })
+            .collect();
+        if !types.is_empty() {
+            body.push_str("<h2>Types</h2>\n<ul>\n");
+            types.into_iter().for_each(|(name, page)| {
+                writeln!(
+                    body,
+                    "<li><a href=\"{}\"><code>{}</code></a></li>",
+                    page,
+                    escape(&name)
+                )
+                .unwrap()
+            });
+            body.push_str("</ul>\n");
+        }
+
+        self.page(self.title, &body)
+    }
+
+    fn route(&self, route: &RocketRoute) -> String {
+        let mut body = String::new();
+        route.full_uris().iter().for_each(|uri| {
+            writeln!(
+                body,
+                "<h1><span class=\"method\">{}</span> <code>{}</code></h1>",
+                escape(&route.route.method),
+                escape(&uri.templated_path())
+            )
+            .unwrap()
+        });
+        body.push_str(&paragraphs(&route.docs));
+        let handler = route
+            .module
+            .iter()
+            .chain(std::iter::once(&route.ident))
+            .map(|segment| segment.as_str())
+            .collect::<Vec<_>>()
+            .join("::");
+        writeln!(body, "<p>Handler: <code>{}</code></p>", escape(&handler)).unwrap();
+
+        let mut rows = vec![];
+        route.params.iter().for_each(|param| {
+            let location = match param.kind {
+                ParamKind::Path => "path".to_string(),
+                ParamKind::Query | ParamKind::QueryForm => "query".to_string(),
+                ParamKind::Guard => match self.document.guard_inputs(&param.ty).as_slice() {
+                    [GuardKind::Ignore] => return,
+                    [] => "guard".to_string(),
+                    inputs => inputs
+                        .iter()
+                        .map(|kind| escape(&kind.location()))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                },
+                ParamKind::Data => return,
+            };
+            let required = param.kind == ParamKind::Path || param.ty.ident() != Some("Option");
+            rows.push(format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&param.ident),
+                location,
+                self.type_html(&param.ty),
+                if required { "yes" } else { "no" }
+            ));
+        });
+        route.uri.query.iter().for_each(|segment| {
+            if let UriSegment::Static { value } = segment {
+                let mut kv = value.splitn(2, '=');
+                rows.push(format!(
+                    "<tr><td><code>{}</code></td><td>query</td><td><code>= {}</code></td><td>yes</td></tr>",
+                    escape(kv.next().unwrap_or_default()),
+                    escape(kv.next().unwrap_or_default())
+                ));
+            }
+        });
+        if !rows.is_empty() {
+            body.push_str("<h2>Parameters</h2>\n<table>\n<tr><th>Name</th><th>In</th><th>Type</th><th>Required</th></tr>\n");
+            rows.iter()
+                .for_each(|row| writeln!(body, "{}", row).unwrap());
+            body.push_str("</table>\n");
+        }
+
+        if let Some(request) = &route.body {
+            writeln!(
+                body,
+                "<h2>Request body</h2>\n<p>{}{}{}</p>",
+                request
+                    .media_type
+                    .as_ref()
+                    .map(|media_type| format!("<code>{}</code>: ", escape(media_type)))
+                    .unwrap_or_default(),
+                request
+                    .schema
+                    .as_ref()
+                    .map(|schema| self.type_html(schema))
+                    .unwrap_or_else(|| "raw data".to_string()),
+                if request.required { "" } else { " (optional)" }
+            )
+            .unwrap();
+            if let Some(example) = self
+                .examples
+                .body_example(&request.schema, request.media_type.as_deref())
+            {
+                writeln!(body, "<pre><code>{}</code></pre>", escape(&example)).unwrap();
+            }
+        }
+
+        if !route.responses.is_empty() {
+            body.push_str("<h2>Responses</h2>\n<table>\n<tr><th>Status</th><th>Content type</th><th>Body</th></tr>\n");
+            route.responses.iter().for_each(|res| {
+                writeln!(
+                    body,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    res.status
+                        .map(|status| format!("{} {}", status, status_reason(status)))
+                        .unwrap_or_else(|| "decided at runtime".to_string()),
+                    res.content_type
+                        .as_ref()
+                        .map(|content_type| format!("<code>{}</code>", escape(content_type)))
+                        .unwrap_or_default(),
+                    res.body
+                        .as_ref()
+                        .map(|ty| self.type_html(ty))
+                        .unwrap_or_default()
+                )
+                .unwrap()
+            });
+            body.push_str("</table>\n");
+
+            // an example for the first body of every status
+            let mut seen = HashSet::new();
+            route
+                .responses
+                .iter()
+                .filter(|res| res.body.is_some() && seen.insert(res.status))
+                .for_each(|res| {
+                    if let Some(example) = self
+                        .examples
+                        .body_example(&res.body, res.content_type.as_deref())
+                    {
+                        writeln!(
+                            body,
+                            "<h3>Example {} response</h3>\n<pre><code>{}</code></pre>",
+                            res.status
+                                .map(|status| format!("{} {}", status, status_reason(status)))
+                                .unwrap_or_else(|| "runtime status".to_string()),
+                            escape(&example)
+                        )
+                        .unwrap()
+                    }
+                });
+        }
+
+        self.page(&route.ident, &body)
+    }
+
+    fn type_header(
+        &self,
+        ident: &str,
+        module: &[String],
+        docs: &Option<String>,
+        direction: Option<BodyDirection>,
+    ) -> String {
+        let path = std::iter::once("crate")
+            .chain(module.iter().map(|segment| segment.as_str()))
+            .collect::<Vec<_>>()
+            .join("::");
+        let mut header = format!(
+            "<h1><code>{}</code></h1>\n<p>Module: <code>{}</code></p>\n{}",
+            escape(ident),
+            escape(&path),
+            paragraphs(docs)
+        );
+        if let Some(direction) = direction {
+            let used_as = match direction {
+                BodyDirection::Request => "request body",
+                BodyDirection::Response => "response body",
+                BodyDirection::Both => "request and response body",
+            };
+            writeln!(header, "<p>Used as: {}</p>", used_as).unwrap();
+        }
+        header
+    }
+
+    /// every field row has an id so it can be linked to directly
+    fn fields(&self, fields: &[(String, String)]) -> String {
+        if fields.is_empty() {
+            return String::new();
+        }
+        let mut html =
+            "<h2>Fields</h2>\n<table>\n<tr><th>Field</th><th>Type</th><th>Required</th></tr>\n"
+                .to_string();
+        fields.iter().for_each(|(name, ty)| {
+            let ty = TypeRef::parse(ty);
+            let id = escape(&format!("field-{}", name));
+            writeln!(
+                html,
+                "<tr id=\"{id}\"><td><a href=\"#{id}\"><code>{}</code></a></td><td>{}</td><td>{}</td></tr>",
+                escape(name),
+                self.type_html(&ty),
+                if ty.ident() == Some("Option") { "no" } else { "yes" },
+                id = id
+            )
+            .unwrap();
+        });
+        html.push_str("</table>\n");
+        html
+    }
+
+    /// the routes that take or return the type, the other half of the links
+    /// from route pages
+    fn used_by(&self, module: &[String], ident: &str) -> String {
+        let routes: Vec<&RocketRoute> = self
+            .document
+            .routes
+            .iter()
+            .filter(|route| {
+                route
+                    .params
+                    .iter()
+                    .map(|param| &param.ty)
+                    .chain(route.body.iter().filter_map(|body| body.schema.as_ref()))
+                    .chain(route.responses.iter().filter_map(|res| res.body.as_ref()))
+                    .any(|ty| mentions(self.document, ty, module, ident))
+            })
+            .collect();
+        if routes.is_empty() {
+            return String::new();
+        }
+
+        let mut html = "<h2>Used by</h2>\n<ul>\n".to_string();
+        routes.into_iter().for_each(|route| {
+            route.full_uris().iter().for_each(|uri| {
+                writeln!(
+                    html,
+                    "<li><a href=\"{}\"><span class=\"method\">{}</span> <code>{}</code></a></li>",
+                    route_page(route),
+                    escape(&route.route.method),
+                    escape(&uri.templated_path())
+                )
+                .unwrap()
+            })
+        });
+        html.push_str("</ul>\n");
+        html
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn builds_linked_pages_for_routes_and_types() {
+        let ast = syn::parse_str(
+            "
+            mod api {
+                #[get(\"/users/<id>\")]
+                fn get(id: u64) -> Option<Json<User>> {}
+            }
+            mod models {
+                #[derive(Serialize)]
+                struct User { id: u64, role: Role }
+                #[derive(Serialize)]
+                enum Role { Admin, Member }
+            }
+            fn rocket() {
+                rocket::ignite().mount(\"/api\", routes![api::get]);
+            }
+            ",
+        )
+        .unwrap();
+        let site = to_html_site(&RocketDocument::parse_file(&ast), "Users <API>");
+
+        assert_eq!(
+            site.keys().collect::<Vec<_>>(),
+            vec![
+                "index.html",
+                "nav.js",
+                "route-api.get.html",
+                "search-index.js",
+                "search.js",
+                "style.css",
+                "type-models.Role.html",
+                "type-models.User.html",
+            ]
+        );
+        assert!(
+            site["route-api.get.html"].contains("<a href=\"type-models.User.html\">User</a>"),
+            "Routes link to the types they use"
+        );
+        assert!(
+            site["type-models.User.html"].contains("<a href=\"route-api.get.html\">"),
+            "Types link back to the routes using them"
+        );
+        assert!(site["type-models.User.html"].contains(
+            "<tr id=\"field-role\"><td><a href=\"#field-role\"><code>role</code></a></td><td><code><a href=\"type-models.Role.html\">Role</a></code></td>"
+        ));
+        assert!(site["index.html"].contains("<title>Users &lt;API&gt; - Users &lt;API&gt;</title>"));
+        assert!(
+            site["nav.js"].contains("/api/users/{id}</a></li>"),
+            "The nav shows the paths routes are mounted at"
+        );
+        assert!(
+            !site["route-api.get.html"].contains("type-models.Role.html"),
+            "Pages share the nav instead of each having a copy"
+        );
+        assert!(
+            site.values()
+                .all(|file| !file.contains("http://") && !file.contains("https://")),
+            "Nothing is loaded from the network"
+        );
+    }
+
+    #[test]
+    fn tells_same_named_types_apart() {
+        let ast = syn::parse_str(
+            "
+            #[derive(Serialize)]
+            struct User { name: String }
+
+            mod api {
+                mod items {
+                    #[derive(Serialize)]
+                    struct User { id: u64 }
+
+                    #[get(\"/items/<id>\")]
+                    fn item(id: u64) -> Json<User> {}
+                }
+            }
+
+            #[get(\"/me\")]
+            fn me() -> Json<User> {}
+
+            fn rocket() {
+                rocket::ignite().mount(\"/\", routes![me]).mount(\"/v2\", routes![api::items::item]);
+            }
+            ",
+        )
+        .unwrap();
+        let site = to_html_site(&RocketDocument::parse_file(&ast), "Users");
+
+        assert!(
+            site["route-me.html"].contains("<a href=\"type-User.html\">"),
+            "{}",
+            site["route-me.html"]
+        );
+        assert!(site["index.html"].contains("<code>crate::User</code>"));
+        assert!(site["index.html"].contains("<code>api::items::User</code>"));
+        assert!(site["type-User.html"].contains("<code>/me</code>"));
+        assert!(
+            !site["type-User.html"].contains("/items/"),
+            "Routes using the other User aren't listed"
+        );
+        assert!(
+            site["type-api.items.User.html"].contains("<code>/v2/items/{id}</code>"),
+            "{}",
+            site["type-api.items.User.html"]
+        );
+    }
+}
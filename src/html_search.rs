@@ -0,0 +1,232 @@
+use crate::html::{route_page, type_page};
+use crate::rocket_document::RocketDocument;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// runs in the browser, searches the index from `search-index.js`. a term
+/// matches any indexed word it's a prefix of and every term has to match
+pub const SEARCH_JS: &str = r#"(function () {
+  var input = document.getElementById("search");
+  var results = document.getElementById("search-results");
+  var index = window.SEARCH_INDEX;
+  if (!input || !results || !index) return;
+
+  function matching(term) {
+    var found = {};
+    Object.keys(index.terms).forEach(function (word) {
+      if (word.lastIndexOf(term, 0) === 0) {
+        index.terms[word].forEach(function (id) { found[id] = true; });
+      }
+    });
+    return found;
+  }
+
+  input.addEventListener("input", function () {
+    var terms = input.value.toLowerCase().split(/[^a-z0-9]+/).filter(Boolean);
+    results.innerHTML = "";
+    if (!terms.length) return;
+
+    var ids = null;
+    terms.forEach(function (term) {
+      var found = matching(term);
+      ids = ids === null ? found : Object.keys(ids).reduce(function (both, id) {
+        if (found[id]) both[id] = true;
+        return both;
+      }, {});
+    });
+
+    Object.keys(ids).map(Number).sort(function (a, b) { return a - b; }).slice(0, 50)
+      .forEach(function (id) {
+        var entry = index.entries[id];
+        var item = document.createElement("li");
+        var link = document.createElement("a");
+        link.href = entry.page;
+        link.textContent = entry.title;
+        item.appendChild(link);
+        if (entry.summary) {
+          var summary = document.createElement("div");
+          summary.className = "summary";
+          summary.textContent = entry.summary;
+          item.appendChild(summary);
+        }
+        results.appendChild(item);
+      });
+  });
+})();
+"#;
+
+/// lowercase words of the text. `LoginData` and `login_data` are indexed as
+/// `login` and `data` as well as the whole thing, so either half finds it
+fn words(text: &str) -> BTreeSet<String> {
+    let mut words = BTreeSet::new();
+    text.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .for_each(|word| {
+            let mut part = String::new();
+            let mut previous_lower = false;
+            word.chars().for_each(|c| {
+                let boundary = c == '_' || (c.is_ascii_uppercase() && previous_lower);
+                if boundary && !part.is_empty() {
+                    words.insert(std::mem::take(&mut part));
+                }
+                if c != '_' {
+                    part.push(c.to_ascii_lowercase());
+                }
+                previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            });
+            if !part.is_empty() {
+                words.insert(part);
+            }
+            words.insert(word.replace('_', "").to_ascii_lowercase());
+        });
+    words
+}
+
+/// the first paragraph of the docs, that's what shows up under a result
+fn summary(docs: &Option<String>) -> Option<String> {
+    docs.as_ref()
+        .and_then(|docs| docs.split("\n\n").next())
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// every route, type and field with the page it lives on, plus a map from
+/// word to the entries containing it so the browser doesn't have to
+/// tokenize anything but the query
+pub fn search_index(document: &RocketDocument) -> Value {
+    let mut entries = vec![];
+    let mut terms: BTreeMap<String, BTreeSet<usize>> = BTreeMap::new();
+    let mut add = |title: String, page: String, summary: Option<String>, text: String| {
+        words(&text).into_iter().for_each(|word| {
+            terms.entry(word).or_default().insert(entries.len());
+        });
+        entries.push(json!({ "title": title, "page": page, "summary": summary }));
+    };
+
+    document.routes.iter().for_each(|route| {
+        route.full_uris().iter().for_each(|uri| {
+            let path = uri.templated_path();
+            add(
+                format!("{} {}", route.route.method.to_uppercase(), path),
+                route_page(route),
+                summary(&route.docs),
+                format!(
+                    "{} {} {} {}",
+                    route.route.method,
+                    path,
+                    route.ident,
+                    route.docs.as_deref().unwrap_or_default()
+                ),
+            )
+        })
+    });
+
+    let types = document
+        .structs
+        .iter()
+        .map(|strct| {
+            (
+                &strct.ident,
+                &strct.module,
+                &strct.docs,
+                strct.fields.as_slice(),
+            )
+        })
+        .chain(
+            document
+                .enums
+                .iter()
+                .map(|enm| (&enm.ident, &enm.module, &enm.docs, &[][..])),
+        );
+    types.for_each(|(ident, module, docs, fields)| {
+        let page = type_page(module, ident);
+        add(
+            ident.to_owned(),
+            page.to_owned(),
+            summary(docs),
+            format!("{} {}", ident, docs.as_deref().unwrap_or_default()),
+        );
+        fields
+            .iter()
+            .filter(|(name, _)| name.parse::<usize>().is_err())
+            .for_each(|(name, _)| {
+                add(
+                    format!("{}.{}", ident, name),
+                    format!("{}#field-{}", page, name),
+                    None,
+                    name.to_owned(),
+                )
+            });
+    });
+    document.enums.iter().for_each(|enm| {
+        let page = type_page(&enm.module, &enm.ident);
+        enm.variants.iter().for_each(|variant| {
+            add(
+                format!("{}::{}", enm.ident, variant.ident),
+                format!("{}#variant-{}", page, variant.ident),
+                None,
+                variant.ident.to_owned(),
+            )
+        })
+    });
+
+    json!({ "entries": entries, "terms": terms })
+}
+
+/// the index as a script that sets a global. browsers won't let a page
+/// opened from the filesystem fetch a json file, but a script tag is fine
+pub fn search_index_js(document: &RocketDocument) -> String {
+    format!("window.SEARCH_INDEX = {};\n", search_index(document))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn splits_identifiers_into_words() {
+        assert_eq!(
+            words("LoginData get_user /api/v2"),
+            [
+                "api",
+                "data",
+                "get",
+                "getuser",
+                "login",
+                "logindata",
+                "user",
+                "v2"
+            ]
+            .iter()
+            .map(|word| word.to_string())
+            .collect()
+        );
+    }
+
+    #[test]
+    fn indexes_routes_types_and_fields() {
+        let ast = syn::parse_str(
+            "
+            /// Someone with an account.
+            ///
+            /// More detail.
+            struct User { nickname: String }
+
+            #[get(\"/users/<id>\")]
+            fn get_user(id: u64) -> Json<User> {}
+            ",
+        )
+        .unwrap();
+        let index = search_index(&RocketDocument::parse_file(&ast));
+
+        assert_eq!(
+            index["entries"],
+            json!([
+                { "title": "GET /users/{id}", "page": "route-get_user.html", "summary": null },
+                { "title": "User", "page": "type-User.html", "summary": "Someone with an account." },
+                { "title": "User.nickname", "page": "type-User.html#field-nickname", "summary": null }
+            ])
+        );
+        assert_eq!(index["terms"]["user"], json!([0, 1]));
+        assert_eq!(index["terms"]["detail"], json!([1]));
+    }
+}
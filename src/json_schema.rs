@@ -0,0 +1,122 @@
+use crate::example::ExampleGenerator;
+use crate::rocket_document::RocketDocument;
+use crate::schema::SchemaGenerator;
+use crate::type_ref::TypeRef;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+const REF_PREFIX: &str = "#/$defs/";
+
+/// a standalone draft 2020-12 schema for the type. the type itself and
+/// everything it references are in `$defs` so the file doesn't point to any
+/// other file. `examples` has a made up value (or the one it was given)
+pub fn type_json_schema(document: &RocketDocument, ty: &TypeRef) -> Value {
+    let generator = SchemaGenerator::new(document, REF_PREFIX);
+    let name = SchemaGenerator::definition_name(&ty.to_string());
+
+    json!({
+        "$schema": DRAFT,
+        "$id": format!("{}.schema.json", name),
+        "title": ty.to_string(),
+        "$ref": format!("{}{}", REF_PREFIX, name),
+        "$defs": generator.definitions(vec![ty.to_owned()]),
+        "examples": [ExampleGenerator::new(document).example(ty)]
+    })
+}
+
+/// a `<Name>.schema.json` file for every struct and enum. generic ones are
+/// only there in the forms routes use them in, `Page<User>` is
+/// `Page_User.schema.json`, since a schema can't leave `T` open
+pub fn to_json_schemas(document: &RocketDocument) -> BTreeMap<String, String> {
+    let types = document
+        .structs
+        .iter()
+        .filter(|strct| strct.generics.is_empty())
+        .map(|strct| document.type_name(&strct.ident, &strct.module))
+        .chain(
+            document
+                .enums
+                .iter()
+                .filter(|enm| enm.generics.is_empty())
+                .map(|enm| document.type_name(&enm.ident, &enm.module)),
+        )
+        .chain(
+            document
+                .struct_instances
+                .iter()
+                .map(|strct| strct.ident.to_owned()),
+        )
+        .chain(
+            document
+                .enum_instances
+                .iter()
+                .map(|enm| enm.ident.to_owned()),
+        );
+
+    // types with the same name in different modules go by their path
+    types
+        .map(|name| {
+            let ty = TypeRef::parse(&name);
+            (
+                format!("{}.schema.json", SchemaGenerator::definition_name(&name)),
+                serde_json::to_string_pretty(&type_json_schema(document, &ty)).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn writes_a_schema_per_type() {
+        let ast = syn::parse_str(
+            "
+            /// A page of results.
+            struct Page<T> { items: Vec<T> }
+            struct User { id: u64 }
+
+            #[get(\"/\")]
+            fn index() -> Json<Page<User>> {}
+            ",
+        )
+        .unwrap();
+        let document = RocketDocument::parse_file(&ast);
+        let schemas = to_json_schemas(&document);
+
+        assert_eq!(
+            schemas.keys().collect::<Vec<_>>(),
+            vec!["Page_User.schema.json", "User.schema.json"],
+            "Generic types are only written out for the arguments routes use"
+        );
+        assert_eq!(
+            type_json_schema(&document, &TypeRef::parse("Page<User>")),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "Page_User.schema.json",
+                "title": "Page<User>",
+                "$ref": "#/$defs/Page_User",
+                "examples": [{ "items": [{ "id": 0 }] }],
+                "$defs": {
+                    "Page_User": {
+                        "type": "object",
+                        "properties": {
+                            "items": { "type": "array", "items": { "$ref": "#/$defs/User" } }
+                        },
+                        "required": ["items"],
+                        "description": "A page of results."
+                    },
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "integer", "format": "int64", "minimum": 0 }
+                        },
+                        "required": ["id"]
+                    }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,812 @@
+//! checks for routes and responders that compile fine but end up badly
+//! documented (or badly designed). every rule has a default severity that
+//! the config can change, and a finding can be allowed right in the code
+//! with a `// rocket-doc-gen: allow(rule)` comment above the item
+
+use crate::guard::GuardKind;
+use crate::rocket_attribute::derives_trait;
+use crate::rocket_document::RocketDocument;
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_uri::{RocketUri, UriSegment};
+use crate::type_ref::TypeRef;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const MARKER: &str = "rocket-doc-gen: allow(";
+
+/// the rule files that don't parse are reported under. it isn't in RULES
+/// since it can't be allowed, nothing in the file would be documented
+pub const PARSE_ERROR: &str = "parse_error";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Allow,
+    Warn,
+    Deny,
+}
+
+pub struct Rule {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: Severity,
+    check: fn(&RocketDocument) -> Vec<Finding>,
+}
+
+/// what a rule found, the severity and the file are worked out afterwards
+struct Finding {
+    module: Vec<String>,
+    line: usize,
+    message: String,
+}
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "missing_docs",
+        description: "a route without a doc comment",
+        default: Severity::Warn,
+        check: missing_docs,
+    },
+    Rule {
+        name: "mutating_route_without_auth",
+        description: "a POST, PUT, PATCH or DELETE route without an auth guard",
+        default: Severity::Warn,
+        check: mutating_route_without_auth,
+    },
+    Rule {
+        name: "data_on_get",
+        description: "a GET or HEAD route that takes a body, which clients and proxies drop",
+        default: Severity::Deny,
+        check: data_on_get,
+    },
+    Rule {
+        name: "binding_mismatch",
+        description: "a <param> in the path, query or data without a handler argument of that name",
+        default: Severity::Deny,
+        check: binding_mismatch,
+    },
+    Rule {
+        name: "route_collision",
+        description: "routes with the same method, path shape, rank and format",
+        default: Severity::Deny,
+        check: route_collision,
+    },
+    Rule {
+        name: "responder_variant_without_response",
+        description: "a Responder enum variant without #[response], its status isn't known",
+        default: Severity::Warn,
+        check: responder_variant_without_response,
+    },
+    Rule {
+        name: "responder_without_status",
+        description: "a Responder struct without #[response(status = ..)]",
+        default: Severity::Warn,
+        check: responder_without_status,
+    },
+    Rule {
+        name: "path_casing",
+        description: "a path segment cased differently from most of the api's paths",
+        default: Severity::Warn,
+        check: path_casing,
+    },
+];
+
+/// a source file the document was parsed from, findings point into it
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub module: Vec<String>,
+    pub content: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub line: Option<usize>,
+}
+
+impl Diagnostic {
+    /// syn puts errors from the tokenizer (an unclosed delimiter, say) at
+    /// the start of the file, proc-macro2 knows where they are
+    pub fn parse_error(path: &Path, content: &str, err: &syn::Error) -> Self {
+        let (message, line) = match content.parse::<proc_macro2::TokenStream>() {
+            Err(lex) => (
+                "can't be split into tokens, there's an unclosed delimiter or a malformed literal"
+                    .to_string(),
+                lex.span().start().line,
+            ),
+            Ok(_) => (err.to_string(), err.span().start().line),
+        };
+        Diagnostic {
+            rule: PARSE_ERROR.to_string(),
+            severity: Severity::Deny,
+            message,
+            file: Some(path.to_owned()),
+            line: Some(line),
+        }
+    }
+}
+
+/// every finding of every rule that isn't allowed, in the order of RULES.
+/// `levels` are the severities from the config
+pub fn lint(
+    document: &RocketDocument,
+    sources: &[SourceFile],
+    levels: &BTreeMap<String, Severity>,
+) -> Vec<Diagnostic> {
+    RULES
+        .iter()
+        .flat_map(|rule| {
+            let severity = levels.get(rule.name).copied().unwrap_or(rule.default);
+            if severity == Severity::Allow {
+                return vec![];
+            }
+            (rule.check)(document)
+                .into_iter()
+                .filter_map(|finding| {
+                    let source = source_of(sources, &finding.module);
+                    if source
+                        .map(|source| is_allowed(&source.content, finding.line, rule.name))
+                        .unwrap_or(false)
+                    {
+                        return None;
+                    }
+                    Some(Diagnostic {
+                        rule: rule.name.to_string(),
+                        severity,
+                        file: source.map(|source| source.path.to_owned()),
+                        line: source.map(|_| finding.line),
+                        message: finding.message,
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// the file an item in `module` is in. items in inline modules are in the
+/// file of the closest module that has one
+fn source_of<'a>(sources: &'a [SourceFile], module: &[String]) -> Option<&'a SourceFile> {
+    sources
+        .iter()
+        .filter(|source| module.starts_with(&source.module))
+        .max_by_key(|source| source.module.len())
+}
+
+/// the rules a `// rocket-doc-gen: allow(a, b)` comment on this line allows
+pub fn allowed_rules(line: &str) -> Vec<&str> {
+    line.find(MARKER)
+        .map(|idx| &line[idx + MARKER.len()..])
+        .and_then(|rest| rest.find(')').map(|end| &rest[..end]))
+        .map(|rules| {
+            rules
+                .split(',')
+                .map(|rule| rule.trim())
+                .filter(|rule| !rule.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// whether the comments right above `line` (or a `//!` one anywhere in the
+/// file) allow `rule`. lines count from 1
+fn is_allowed(content: &str, line: usize, rule: &str) -> bool {
+    let lines: Vec<&str> = content.lines().collect();
+    let above = lines[..line.saturating_sub(1).min(lines.len())]
+        .iter()
+        .rev()
+        .take_while(|line| line.trim_start().starts_with("//"));
+    lines
+        .iter()
+        .filter(|line| line.trim_start().starts_with("//!"))
+        .chain(above)
+        .any(|line| allowed_rules(line).contains(&rule))
+}
+
+/// `GET /users/<id>`, how findings about a route name it
+fn route_name(route: &RocketRoute) -> String {
+    format!(
+        "`{} {}`",
+        route.route.method.to_uppercase(),
+        route.route.path
+    )
+}
+
+fn route_finding(route: &RocketRoute, message: String) -> Finding {
+    Finding {
+        module: route.module.to_owned(),
+        line: route.line,
+        message,
+    }
+}
+
+fn missing_docs(document: &RocketDocument) -> Vec<Finding> {
+    document
+        .routes
+        .iter()
+        .filter(|route| route.docs.is_none())
+        .map(|route| {
+            route_finding(
+                route,
+                format!("{} ({}) has no doc comment", route_name(route), route.ident),
+            )
+        })
+        .collect()
+}
+
+fn mutating_route_without_auth(document: &RocketDocument) -> Vec<Finding> {
+    // with no guards classified as auth in the config or reading
+    // Authorization, guess from the names
+    let is_auth_kind =
+        |kind: &GuardKind| matches!(kind, GuardKind::Auth { .. } | GuardKind::ApiKey { .. });
+    let classified = document.guards.0.values().any(is_auth_kind)
+        || document
+            .request_guards
+            .iter()
+            .any(|guard| guard.inputs().iter().any(is_auth_kind));
+    // a guard we have the FromRequest impl of is auth by how it fails, the
+    // headers an api key guard reads are just headers otherwise
+    let is_auth = |ty: &TypeRef| match (document.guards.find(ty), document.request_guard(ty)) {
+        (Some(kind), _) => is_auth_kind(kind),
+        (None, Some(guard)) => {
+            guard.is_auth() || guard.inputs().iter().any(is_auth_kind) || looks_like_auth(ty)
+        }
+        (None, None) => !classified && looks_like_auth(ty),
+    };
+
+    document
+        .routes
+        .iter()
+        .filter(|route| ["post", "put", "patch", "delete"].contains(&route.route.method.as_str()))
+        .filter(|route| {
+            !route
+                .params
+                .iter()
+                .any(|param| param.kind == ParamKind::Guard && is_auth(&param.ty))
+        })
+        .map(|route| {
+            route_finding(
+                route,
+                format!("{} changes data but has no auth guard", route_name(route)),
+            )
+        })
+        .collect()
+}
+
+/// `User`, `Option<AdminSession>`, `ApiKey` and the like
+fn looks_like_auth(ty: &TypeRef) -> bool {
+    let ty = match (ty.ident(), ty.args()) {
+        (Some("Option"), [inner]) | (Some("Result"), [inner, ..]) => inner,
+        _ => ty,
+    };
+    ty.ident()
+        .map(|ident| {
+            [
+                "Auth", "User", "Admin", "Session", "Token", "Claims", "ApiKey",
+            ]
+            .iter()
+            .any(|word| ident.contains(word))
+        })
+        .unwrap_or(false)
+}
+
+fn data_on_get(document: &RocketDocument) -> Vec<Finding> {
+    document
+        .routes
+        .iter()
+        .filter(|route| ["get", "head"].contains(&route.route.method.as_str()))
+        .filter(|route| route.route.data.is_some())
+        .map(|route| {
+            route_finding(
+                route,
+                format!(
+                    "{} takes a body, clients and proxies don't send one with {}",
+                    route_name(route),
+                    route.route.method.to_uppercase()
+                ),
+            )
+        })
+        .collect()
+}
+
+fn binding_mismatch(document: &RocketDocument) -> Vec<Finding> {
+    document
+        .routes
+        .iter()
+        .flat_map(|route| {
+            route
+                .uri
+                .path
+                .iter()
+                .chain(route.uri.query.iter())
+                .filter_map(|segment| segment.name())
+                .chain(route.data_name())
+                .filter(move |name| !route.handler.args.iter().any(|(arg, _)| arg == name))
+                .map(move |name| {
+                    route_finding(
+                        route,
+                        format!(
+                            "{} binds <{}> but {} has no `{}` argument",
+                            route_name(route),
+                            name,
+                            route.ident,
+                            name
+                        ),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// routes rocket can't tell apart. the paths have to match segment for
+/// segment, static ones by value and dynamic ones by position, so
+/// `/users/<id>` and `/users/<name>` collide but `/users/me` doesn't since
+/// rocket ranks static paths first
+/// how rocket ranks a route it isn't given a rank for: fully static paths
+/// before partly dynamic ones before fully dynamic ones, and the same again
+/// for the query. None is an explicit rank
+fn default_rank(uri: &RocketUri) -> (u8, u8) {
+    let color = |segments: &[UriSegment]| {
+        let dynamic = segments
+            .iter()
+            .filter(|segment| !matches!(segment, UriSegment::Static { .. }))
+            .count();
+        match dynamic {
+            0 => 0,
+            _ if dynamic == segments.len() => 2,
+            _ => 1,
+        }
+    };
+    (
+        color(&uri.path),
+        if uri.query.is_empty() {
+            3
+        } else {
+            color(&uri.query)
+        },
+    )
+}
+
+/// whether some request path matches both. `<p..>` takes whatever's left,
+/// nothing included
+fn paths_overlap(a: &[UriSegment], b: &[UriSegment]) -> bool {
+    match (a.split_first(), b.split_first()) {
+        (Some((UriSegment::Trailing { .. }, _)), _)
+        | (_, Some((UriSegment::Trailing { .. }, _))) => true,
+        (None, None) => true,
+        (None, _) | (_, None) => false,
+        (Some((a_first, a_rest)), Some((b_first, b_rest))) => {
+            let matches = match (a_first, b_first) {
+                (UriSegment::Static { value: a }, UriSegment::Static { value: b }) => a == b,
+                _ => true,
+            };
+            matches && paths_overlap(a_rest, b_rest)
+        }
+    }
+}
+
+fn route_collision(document: &RocketDocument) -> Vec<Finding> {
+    let collides = |a: &RocketRoute, b: &RocketRoute| {
+        a.route.method == b.route.method
+            && a.route.format == b.route.format
+            && a.full_uris().iter().any(|a_uri| {
+                b.full_uris().iter().any(|b_uri| {
+                    let same_rank = match (a.route.rank, b.route.rank) {
+                        (None, None) => default_rank(a_uri) == default_rank(b_uri),
+                        (a_rank, b_rank) => a_rank == b_rank,
+                    };
+                    same_rank && paths_overlap(&a_uri.path, &b_uri.path)
+                })
+            })
+    };
+
+    let routes = &document.routes;
+    routes
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, route)| {
+            routes[..idx]
+                .iter()
+                .find(|earlier| collides(earlier, route))
+                .map(|earlier| {
+                    route_finding(
+                        route,
+                        format!(
+                            "{} ({}) collides with {} ({}), give one of them a rank",
+                            route_name(route),
+                            route.ident,
+                            route_name(earlier),
+                            earlier.ident
+                        ),
+                    )
+                })
+        })
+        .collect()
+}
+
+fn responder_variant_without_response(document: &RocketDocument) -> Vec<Finding> {
+    document
+        .enums
+        .iter()
+        .filter(|enm| derives_trait(&enm.derives, "Responder"))
+        .flat_map(|enm| {
+            enm.variants
+                .iter()
+                .filter(|variant| variant.response.is_none())
+                .map(move |variant| Finding {
+                    module: enm.module.to_owned(),
+                    line: variant.line,
+                    message: format!(
+                        "`{}::{}` has no #[response], its status comes from what it wraps",
+                        enm.ident, variant.ident
+                    ),
+                })
+        })
+        .collect()
+}
+
+fn responder_without_status(document: &RocketDocument) -> Vec<Finding> {
+    document
+        .structs
+        .iter()
+        .filter(|strct| derives_trait(&strct.derives, "Responder"))
+        .filter(|strct| {
+            strct
+                .response
+                .as_ref()
+                .and_then(|response| response.status)
+                .is_none()
+        })
+        .map(|strct| Finding {
+            module: strct.module.to_owned(),
+            line: strct.line,
+            message: format!(
+                "`{}` derives Responder without #[response(status = ..)]",
+                strct.ident
+            ),
+        })
+        .collect()
+}
+
+fn path_casing(document: &RocketDocument) -> Vec<Finding> {
+    let casing = |route: &RocketRoute| -> Vec<&'static str> {
+        route
+            .uri
+            .path
+            .iter()
+            .filter_map(|segment| match segment {
+                UriSegment::Static { value } => casing_of(value),
+                _ => None,
+            })
+            .collect()
+    };
+
+    // most common first, ties go to the one seen first
+    let mut counts: Vec<(&'static str, usize)> = vec![];
+    document.routes.iter().flat_map(casing).for_each(|style| {
+        match counts.iter_mut().find(|(seen, _)| *seen == style) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((style, 1)),
+        }
+    });
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let expected = match counts.first() {
+        Some((expected, _)) if counts.len() > 1 => *expected,
+        _ => return vec![],
+    };
+
+    document
+        .routes
+        .iter()
+        .filter_map(|route| {
+            casing(route)
+                .into_iter()
+                .find(|style| *style != expected)
+                .map(|style| {
+                    route_finding(
+                        route,
+                        format!(
+                            "{} uses {} but most paths use {}",
+                            route_name(route),
+                            style,
+                            expected
+                        ),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// None for segments that look the same in every style, like `users`
+fn casing_of(segment: &str) -> Option<&'static str> {
+    if segment.contains('_') {
+        Some("snake_case")
+    } else if segment.contains('-') {
+        Some("kebab-case")
+    } else if segment.chars().any(|c| c.is_ascii_uppercase()) {
+        Some("camelCase")
+    } else {
+        None
+    }
+}
+
+pub fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Allow => "allow",
+        Severity::Warn => "warning",
+        Severity::Deny => "error",
+    }
+}
+
+/// the rules with their default severity, for `lint --rules`
+pub fn rules_text() -> String {
+    let mut out = String::new();
+    RULES.iter().for_each(|rule| {
+        writeln!(
+            out,
+            "{:<36} {:<8} {}",
+            rule.name,
+            // spelled the way the config spells it
+            format!("{:?}", rule.default).to_lowercase(),
+            rule.description
+        )
+        .unwrap()
+    });
+    out
+}
+
+/// like rustc, a line per finding and where it is under it
+pub fn to_text(diagnostics: &[Diagnostic]) -> String {
+    let mut out = String::new();
+    diagnostics.iter().for_each(|diagnostic| {
+        writeln!(
+            out,
+            "{}[{}]: {}",
+            severity_name(diagnostic.severity),
+            diagnostic.rule,
+            diagnostic.message
+        )
+        .unwrap();
+        if let (Some(file), Some(line)) = (&diagnostic.file, diagnostic.line) {
+            writeln!(out, "  --> {}:{}", file.display(), line).unwrap();
+        }
+    });
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = "
+        #[derive(Responder)]
+        enum Answer {
+            #[response(status = 200)]
+            Found(String),
+            Gone(String),
+        }
+
+        /// the users
+        #[get(\"/users\")]
+        fn list_users() {}
+
+        #[get(\"/user_search\", data = \"<query>\")]
+        fn search(query: Json<Query>) {}
+
+        // rocket-doc-gen: allow(missing_docs)
+        #[delete(\"/users/<id>\")]
+        fn delete_user(id: u64, admin: AdminUser) {}
+
+        /// makes a user
+        #[post(\"/users\", data = \"<user>\")]
+        fn create_user(user: Json<NewUser>) {}
+
+        /// makes a user too
+        #[post(\"/new-users\", data = \"<user>\")]
+        fn create_user_again(user: Json<NewUser>) {}
+
+        /// and another one
+        #[post(\"/more-users\", data = \"<user>\")]
+        fn create_more_users(user: Json<NewUser>) {}
+    ";
+
+    fn findings(levels: &[(&str, Severity)]) -> Vec<(String, Severity, String, usize)> {
+        let document = RocketDocument::parse_file(&syn::parse_str(SOURCE).unwrap());
+        let sources = vec![SourceFile {
+            path: PathBuf::from("src/main.rs"),
+            module: vec![],
+            content: SOURCE.to_string(),
+        }];
+        let levels = levels
+            .iter()
+            .map(|(rule, severity)| (rule.to_string(), *severity))
+            .collect();
+        lint(&document, &sources, &levels)
+            .into_iter()
+            .map(|diagnostic| {
+                assert_eq!(diagnostic.file, Some(PathBuf::from("src/main.rs")));
+                (
+                    diagnostic.rule,
+                    diagnostic.severity,
+                    diagnostic.message,
+                    diagnostic.line.unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_problems_with_their_severity_and_line() {
+        assert_eq!(
+            findings(&[("mutating_route_without_auth", Severity::Deny)]),
+            vec![
+                (
+                    "missing_docs".to_string(),
+                    Severity::Warn,
+                    "`GET /user_search` (search) has no doc comment".to_string(),
+                    13
+                ),
+                (
+                    "mutating_route_without_auth".to_string(),
+                    Severity::Deny,
+                    "`POST /users` changes data but has no auth guard".to_string(),
+                    20
+                ),
+                (
+                    "mutating_route_without_auth".to_string(),
+                    Severity::Deny,
+                    "`POST /new-users` changes data but has no auth guard".to_string(),
+                    24
+                ),
+                (
+                    "mutating_route_without_auth".to_string(),
+                    Severity::Deny,
+                    "`POST /more-users` changes data but has no auth guard".to_string(),
+                    28
+                ),
+                (
+                    "data_on_get".to_string(),
+                    Severity::Deny,
+                    "`GET /user_search` takes a body, clients and proxies don't send one with GET"
+                        .to_string(),
+                    13
+                ),
+                (
+                    "responder_variant_without_response".to_string(),
+                    Severity::Warn,
+                    "`Answer::Gone` has no #[response], its status comes from what it wraps"
+                        .to_string(),
+                    6
+                ),
+                (
+                    "path_casing".to_string(),
+                    Severity::Warn,
+                    "`GET /user_search` uses snake_case but most paths use kebab-case".to_string(),
+                    13
+                ),
+            ]
+        );
+
+        let allowed = findings(&[
+            ("missing_docs", Severity::Allow),
+            ("mutating_route_without_auth", Severity::Allow),
+            ("path_casing", Severity::Allow),
+        ]);
+        assert_eq!(allowed.len(), 2);
+    }
+
+    #[test]
+    fn counts_api_key_guards_as_auth() {
+        let document = RocketDocument::parse_file(
+            &syn::parse_str(
+                "
+                impl<'r> FromRequest<'r> for ApiKey {
+                    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+                        match req.headers().get_one(\"X-Api-Key\") {
+                            Some(key) => Outcome::Success(ApiKey(key.to_string())),
+                            None => Outcome::Error((Status::Forbidden, ())),
+                        }
+                    }
+                }
+
+                impl<'r> FromRequest<'r> for RequestId {
+                    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+                        req.headers().get_one(\"X-Request-Id\").map(RequestId).or_error((Status::BadRequest, ()))
+                    }
+                }
+
+                #[post(\"/users\")]
+                fn create_user(key: ApiKey) {}
+
+                #[post(\"/items\")]
+                fn create_item(id: RequestId) {}
+                ",
+            )
+            .unwrap(),
+        );
+        let levels = RULES
+            .iter()
+            .filter(|rule| rule.name != "mutating_route_without_auth")
+            .map(|rule| (rule.name.to_string(), Severity::Allow))
+            .collect();
+
+        assert_eq!(
+            lint(&document, &[], &levels)
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect::<Vec<_>>(),
+            vec!["`POST /items` changes data but has no auth guard"],
+            "A guard that answers 403 is auth even though it reads a plain header"
+        );
+    }
+
+    #[test]
+    fn finds_unbound_params_and_collisions() {
+        let document = RocketDocument::parse_file(
+            &syn::parse_str(
+                "
+                #[get(\"/users/<id>\")]
+                fn get_user(user_id: u64) {}
+
+                #[get(\"/users/<name>\")]
+                fn find_user(name: String) {}
+
+                #[get(\"/users/me\")]
+                fn me() {}
+
+                #[get(\"/users/<name>\", rank = 2)]
+                fn find_user_again(name: String) {}
+
+                #[get(\"/files/<id>\")]
+                fn file(id: u64) {}
+
+                #[get(\"/files/<path..>\")]
+                fn files(path: PathBuf) {}
+
+                #[get(\"/files/<id>/raw\")]
+                fn raw_file(id: u64) {}
+                ",
+            )
+            .unwrap(),
+        );
+        let levels = RULES
+            .iter()
+            .filter(|rule| !["binding_mismatch", "route_collision"].contains(&rule.name))
+            .map(|rule| (rule.name.to_string(), Severity::Allow))
+            .collect();
+
+        assert_eq!(
+            lint(&document, &[], &levels)
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect::<Vec<_>>(),
+            vec![
+                "`GET /users/<id>` binds <id> but get_user has no `id` argument",
+                "`GET /users/<name>` (find_user) collides with `GET /users/<id>` (get_user), \
+                 give one of them a rank",
+                "`GET /files/<path..>` (files) collides with `GET /files/<id>` (file), \
+                 give one of them a rank",
+                "`GET /files/<id>/raw` (raw_file) collides with `GET /files/<path..>` (files), \
+                 give one of them a rank",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_allow_markers() {
+        assert_eq!(
+            allowed_rules("    // rocket-doc-gen: allow(missing_docs, path_casing)"),
+            vec!["missing_docs", "path_casing"]
+        );
+        assert!(allowed_rules("// allow(missing_docs)").is_empty());
+
+        let content = "//! rocket-doc-gen: allow(path_casing)\n\n// rocket-doc-gen: allow(data_on_get)\n// more about it\n#[get(\"/\")]\nfn index() {}\n";
+        assert!(is_allowed(content, 5, "data_on_get"));
+        assert!(is_allowed(content, 5, "path_casing"));
+        assert!(!is_allowed(content, 5, "missing_docs"));
+        assert!(!is_allowed(content, 6, "data_on_get"));
+    }
+}
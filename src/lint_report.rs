@@ -0,0 +1,254 @@
+//! lint findings in the formats code scanning and CI dashboards read, so
+//! they show up on pull requests next to the lines they're about. SARIF for
+//! github/gitlab code scanning and JUnit XML for test result dashboards
+
+use crate::lint::{Diagnostic, Severity, PARSE_ERROR, RULES};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::path::{Component, Path};
+
+/// every rule with its description and default level, parse errors
+/// included so results always point at a rule that's listed
+fn rules() -> Vec<(&'static str, &'static str, Severity)> {
+    RULES
+        .iter()
+        .map(|rule| (rule.name, rule.description, rule.default))
+        .chain(std::iter::once((
+            PARSE_ERROR,
+            "a source file that doesn't parse",
+            Severity::Deny,
+        )))
+        .collect()
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Allow => "none",
+        Severity::Warn => "warning",
+        Severity::Deny => "error",
+    }
+}
+
+/// relative to where it was run with `/` between the parts, which is what
+/// both formats expect
+fn uri(path: &Path) -> String {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// a SARIF 2.1.0 log with a single run
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> String {
+    let rules = rules();
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let mut result = json!({
+                "ruleId": diagnostic.rule,
+                "level": level(diagnostic.severity),
+                "message": { "text": diagnostic.message },
+            });
+            if let Some(idx) = rules.iter().position(|(name, ..)| *name == diagnostic.rule) {
+                result["ruleIndex"] = json!(idx);
+            }
+            if let Some(file) = &diagnostic.file {
+                let mut location = json!({ "artifactLocation": { "uri": uri(file) } });
+                if let Some(line) = diagnostic.line {
+                    location["region"] = json!({ "startLine": line });
+                }
+                result["locations"] = json!([{ "physicalLocation": location }]);
+            }
+            result
+        })
+        .collect();
+
+    let mut out = serde_json::to_string_pretty(&json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rocket-doc-gen",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|(name, description, default)| json!({
+                        "id": name,
+                        "shortDescription": { "text": description },
+                        "defaultConfiguration": { "level": level(*default) },
+                    })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    }))
+    .unwrap();
+    out.push('\n');
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// a test suite per rule with a failing test case per finding. a rule
+/// without findings gets a passing one, so dashboards show what was checked
+pub fn to_junit(diagnostics: &[Diagnostic]) -> String {
+    let mut out = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_string();
+    let rules = rules();
+    let total = rules
+        .iter()
+        .map(|(name, ..)| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.rule == *name)
+                .count()
+                .max(1)
+        })
+        .sum::<usize>();
+    writeln!(
+        out,
+        "<testsuites name=\"rocket-doc-gen lint\" tests=\"{}\" failures=\"{}\">",
+        total,
+        diagnostics.len()
+    )
+    .unwrap();
+
+    rules.iter().for_each(|(name, description, _)| {
+        let found: Vec<&Diagnostic> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.rule == *name)
+            .collect();
+        writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            name,
+            found.len().max(1),
+            found.len()
+        )
+        .unwrap();
+        if found.is_empty() {
+            writeln!(
+                out,
+                "    <testcase name=\"{}\" classname=\"{}\"/>",
+                escape(description),
+                name
+            )
+            .unwrap();
+        }
+        found.iter().for_each(|diagnostic| {
+            let location = match (&diagnostic.file, diagnostic.line) {
+                (Some(file), Some(line)) => format!("{}:{}", uri(file), line),
+                (Some(file), None) => uri(file),
+                _ => String::new(),
+            };
+            let mut attrs = String::new();
+            if let Some(file) = &diagnostic.file {
+                write!(attrs, " file=\"{}\"", escape(&uri(file))).unwrap();
+            }
+            if let Some(line) = diagnostic.line {
+                write!(attrs, " line=\"{}\"", line).unwrap();
+            }
+            writeln!(
+                out,
+                "    <testcase name=\"{}\" classname=\"{}\"{}>",
+                escape(&diagnostic.message),
+                name,
+                attrs
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "      <failure type=\"{}\" message=\"{}\">{}</failure>",
+                level(diagnostic.severity),
+                escape(&diagnostic.message),
+                escape(&location)
+            )
+            .unwrap();
+            out.push_str("    </testcase>\n");
+        });
+        out.push_str("  </testsuite>\n");
+    });
+    out.push_str("</testsuites>\n");
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    const BROKEN: &str = "fn ok() {}\n\nfn broken( {}\n";
+
+    fn diagnostics() -> Vec<Diagnostic> {
+        vec![
+            Diagnostic {
+                rule: "missing_docs".to_string(),
+                severity: Severity::Warn,
+                message: "`GET /users` (list_users) has no doc comment".to_string(),
+                file: Some(PathBuf::from("./src/api.rs")),
+                line: Some(12),
+            },
+            Diagnostic::parse_error(
+                Path::new("src/broken.rs"),
+                BROKEN,
+                &syn::parse_str::<syn::File>(BROKEN).unwrap_err(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn writes_sarif_results_with_locations() {
+        let sarif: Value = serde_json::from_str(&to_sarif(&diagnostics())).unwrap();
+        let run = &sarif["runs"][0];
+
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "ruleId": "missing_docs",
+                "ruleIndex": 0,
+                "level": "warning",
+                "message": { "text": "`GET /users` (list_users) has no doc comment" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/api.rs" },
+                        "region": { "startLine": 12 }
+                    }
+                }]
+            })
+        );
+        assert_eq!(run["results"][1]["ruleId"], "parse_error");
+        assert_eq!(run["results"][1]["level"], "error");
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            3
+        );
+        let index = run["results"][1]["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(run["tool"]["driver"]["rules"][index]["id"], "parse_error");
+    }
+
+    #[test]
+    fn writes_a_junit_suite_per_rule() {
+        let junit = to_junit(&diagnostics());
+
+        assert!(junit.contains(&format!(
+            "<testsuites name=\"rocket-doc-gen lint\" tests=\"{}\" failures=\"2\">",
+            RULES.len() + 1
+        )));
+        assert!(junit.contains(
+            "  <testsuite name=\"missing_docs\" tests=\"1\" failures=\"1\">\n    \
+             <testcase name=\"`GET /users` (list_users) has no doc comment\" \
+             classname=\"missing_docs\" file=\"src/api.rs\" line=\"12\">\n      \
+             <failure type=\"warning\" message=\"`GET /users` (list_users) has no doc comment\">\
+             src/api.rs:12</failure>\n"
+        ));
+        assert!(junit.contains(
+            "  <testsuite name=\"data_on_get\" tests=\"1\" failures=\"0\">\n    \
+             <testcase name=\"a GET or HEAD route that takes a body, which clients and proxies drop\" \
+             classname=\"data_on_get\"/>\n"
+        ));
+    }
+}
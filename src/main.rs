@@ -1,16 +1,49 @@
-#![feature(box_patterns, bindings_after_at, never_type)]
+#![feature(box_patterns)]
 
 #[macro_use]
 extern crate quote;
 
+use std::path::PathBuf;
+
 mod ast_formatting;
+mod changelog;
+mod cli;
+mod collection;
+mod config;
+mod coverage;
+mod diff;
+mod example;
+mod guard;
+mod html;
+mod html_search;
+mod json_schema;
+mod lint;
+mod lint_report;
+mod markdown;
+mod openapi;
+mod output;
+mod rocket_alias;
 mod rocket_attribute;
+mod rocket_document;
 mod rocket_enum;
+mod rocket_generic;
+mod rocket_guard;
+mod rocket_mount;
 mod rocket_route;
+mod rocket_serde;
 mod rocket_struct;
+mod rocket_uri;
+mod rocket_wrapper;
+mod rust_client;
+mod schema;
+mod snippets;
+mod template;
+mod type_mapping;
+mod type_ref;
+mod typescript;
 
-fn main() {
-    let content = "
+// documented when no inputs are given
+const EXAMPLE: &str = "
     #![feature(proc_macro_hygiene, decl_macro)]
     #[macro_use] extern crate rocket;
 
@@ -27,17 +60,20 @@ fn main() {
         Failure(String),
     }
 
+    type LoginResult = Result<LoginResponse, Status>;
+
+    #[derive(Deserialize)]
     struct LoginData {
         username: String,
         password: String,
     }
 
     #[post(\"/login\", format=\"application/json\", data = \"<data>\")]
-    fn login(user: User, data: LoginData) -> LoginResponse {
+    fn login(user: User, data: LoginData) -> LoginResult {
         if username == \"a\" && password == \"b\" {
-            LoginResponse::Success(user.id)
+            Ok(LoginResponse::Success(user.id))
         } else {
-            LoginResponse::Failure(\"Bad login\")
+            Ok(LoginResponse::Failure(\"Bad login\"))
         }
     }
 
@@ -57,69 +93,382 @@ fn main() {
     fn main() {
         rocket::ignite().mount(\"/\", routes![hello]).launch();
     }
-    "
-    .to_string();
+    ";
 
-    let ast = syn::parse_str(&content).unwrap();
-    traverse_file(ast);
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(err) = run(&args) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
 }
 
-fn traverse_file(ast: syn::File) {
-    traverse_items(&ast.items, 0);
+fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(|arg| arg.as_str()) {
+        Some("diff") => return run_diff(&args[1..]),
+        Some("changelog") => return run_changelog(&args[1..]),
+        Some("lint") => return run_lint(&args[1..]),
+        Some("coverage") => return run_coverage(&args[1..]),
+        _ => (),
+    }
+    let options = cli::Options::parse_args(args)?;
+    if options.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    let config = config::Config::find(options.config.as_deref())?;
+
+    let inputs = if options.inputs.is_empty() {
+        &config.inputs
+    } else {
+        &options.inputs
+    };
+    let mut document = if inputs.is_empty() {
+        rocket_document::RocketDocument::parse_file(&syn::parse_file(EXAMPLE).unwrap())
+    } else {
+        document_for(inputs, &config)?
+    };
+    config.apply(&mut document)?;
+
+    // the files given on the command line add to what's in the config
+    if let Some(path) = &options.examples {
+        let examples = example::ExampleOverrides::load(path)?;
+        document.examples.types.extend(examples.types);
+        document.examples.fields.extend(examples.fields);
+    }
+    if let Some(path) = &options.type_mappings {
+        let mappings = type_mapping::TypeMappings::load(path)?;
+        document.type_mappings.0.extend(mappings.0);
+    }
+
+    let default = openapi::OpenApiInfo::default();
+    let info = openapi::OpenApiInfo {
+        title: options
+            .title
+            .or_else(|| config.title.clone())
+            .unwrap_or(default.title),
+        version: options
+            .version
+            .or_else(|| config.version.clone())
+            .unwrap_or(default.version),
+        servers: if options.servers.is_empty() {
+            config.servers.clone()
+        } else {
+            options
+                .servers
+                .iter()
+                .map(|url| openapi::Server {
+                    url: url.to_owned(),
+                    description: None,
+                })
+                .collect()
+        },
+    };
+    let types_crate = options.types_crate.or_else(|| config.types_crate.clone());
+
+    // a format on the command line is the only thing written, otherwise it's
+    // everything the config lists
+    let outputs = match (&options.templates, options.format) {
+        (Some(dir), _) => vec![output::Output::dir(
+            options.output,
+            template::render_template_dir(&document, &info, dir)?,
+            "--templates",
+        )?],
+        (None, Some(format)) => vec![render(
+            &document,
+            &info,
+            &format,
+            options.output,
+            &types_crate,
+        )?],
+        (None, None) if options.output.is_none() && !config.outputs.is_empty() => config
+            .outputs
+            .iter()
+            .map(|(format, path)| {
+                render(
+                    &document,
+                    &info,
+                    format,
+                    Some(path.to_owned()),
+                    &types_crate,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        (None, None) => vec![render(
+            &document,
+            &info,
+            "json",
+            options.output,
+            &types_crate,
+        )?],
+    };
+
+    if !options.check {
+        return outputs.iter().try_for_each(|output| output.write());
+    }
+    let mut drift = vec![];
+    for output in &outputs {
+        drift.extend(output.check()?);
+    }
+    if drift.is_empty() {
+        return Ok(());
+    }
+    drift.iter().for_each(|diff| print!("{}", diff));
+    Err(format!(
+        "{} generated file{} out of date, run without --check to update",
+        drift.len(),
+        if drift.len() == 1 { " is" } else { "s are" }
+    ))
 }
 
-fn traverse_items(items: &Vec<syn::Item>, depth: u32) {
-    items.iter().for_each(|item| traverse_item(item, depth));
+fn run_diff(args: &[String]) -> Result<(), String> {
+    let options = cli::DiffOptions::parse_args(args)?;
+    let report = diff::diff(
+        &diff::load_document(&options.old)?,
+        &diff::load_document(&options.new)?,
+    );
+    let content = match options.format.as_str() {
+        "json" => diff::to_json(&report),
+        _ => diff::to_text(&report),
+    };
+    match options.output {
+        Some(path) => std::fs::write(&path, content)
+            .map_err(|err| format!("could not write {}: {}", path.display(), err)),
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
 }
 
-fn print_json<'a>(str: impl serde::Serialize) {
-    println!("{}", serde_json::to_string(&str).unwrap());
+fn run_changelog(args: &[String]) -> Result<(), String> {
+    let options = cli::ChangelogOptions::parse_args(args)?;
+    let config = config::Config::find(options.config.as_deref())?;
+    let inputs = if options.inputs.is_empty() {
+        &config.inputs
+    } else {
+        &options.inputs
+    };
+    if inputs.is_empty() {
+        return Err("changelog needs inputs, on the command line or in the config".to_string());
+    }
+
+    let releases = changelog::releases(
+        inputs,
+        &config,
+        options.from.as_deref(),
+        options.to.as_deref(),
+    )?;
+    let content = changelog::to_markdown(&releases);
+    match options.output {
+        Some(path) => std::fs::write(&path, content)
+            .map_err(|err| format!("could not write {}: {}", path.display(), err)),
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
 }
 
-fn traverse_item(item: &syn::Item, depth: u32) {
-    match item {
-        // fn x { }
-        syn::Item::Fn(
-            function
-            @
-            syn::ItemFn {
-                block: box syn::Block { stmts, .. },
-                ..
-            },
-        ) => {
-            let x = rocket_route::RocketRoute::parse_fn(function);
-            if x.is_some() {
-                print_json(x.unwrap());
-            }
+fn run_lint(args: &[String]) -> Result<(), String> {
+    let options = cli::LintOptions::parse_args(args)?;
+    if options.rules {
+        print!("{}", lint::rules_text());
+        return Ok(());
+    }
+    let config = config::Config::find(options.config.as_deref())?;
+    let inputs = if options.inputs.is_empty() {
+        &config.inputs
+    } else {
+        &options.inputs
+    };
+    if inputs.is_empty() {
+        return Err("lint needs inputs, on the command line or in the config".to_string());
+    }
 
-            stmts.iter().for_each(|item| {
-                if let syn::Stmt::Item(item) = item {
-                    traverse_item(item, depth + 1)
-                }
-            })
+    let mut parsed = parse_inputs(inputs, &config)?;
+    config.apply(&mut parsed.document)?;
+    let mut diagnostics = parsed.errors;
+    diagnostics.extend(lint::lint(&parsed.document, &parsed.files, &config.lints));
+    let content = match options.format.as_str() {
+        "sarif" => lint_report::to_sarif(&diagnostics),
+        "junit" => lint_report::to_junit(&diagnostics),
+        _ => lint::to_text(&diagnostics),
+    };
+    match options.output {
+        Some(path) => output::Output::File(path, content).write()?,
+        None => print!("{}", content),
+    }
+
+    let count = |severity| {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    };
+    let plural = |count: usize, what: &str| {
+        format!("{} {}{}", count, what, if count == 1 { "" } else { "s" })
+    };
+    match (count(lint::Severity::Deny), count(lint::Severity::Warn)) {
+        (0, 0) => Ok(()),
+        (0, warnings) => {
+            eprintln!("{}", plural(warnings, "warning"));
+            Ok(())
         }
+        (errors, warnings) => Err(format!(
+            "{} and {}",
+            plural(errors, "error"),
+            plural(warnings, "warning")
+        )),
+    }
+}
+
+fn run_coverage(args: &[String]) -> Result<(), String> {
+    let options = cli::CoverageOptions::parse_args(args)?;
+    let config = config::Config::find(options.config.as_deref())?;
+    let inputs = if options.inputs.is_empty() {
+        &config.inputs
+    } else {
+        &options.inputs
+    };
+    if inputs.is_empty() {
+        return Err("coverage needs inputs, on the command line or in the config".to_string());
+    }
+
+    let mut document = document_for(inputs, &config)?;
+    config.apply(&mut document)?;
+    let report = coverage::coverage(&document);
+    let content = match options.format.as_str() {
+        "json" => coverage::to_json(&report),
+        _ => coverage::to_text(&report),
+    };
+    match options.output {
+        Some(path) => output::Output::File(path, content).write()?,
+        None => print!("{}", content),
+    }
 
-        // mod x or mod x { }
-        syn::Item::Mod(item_mod) => {
-            if let syn::ItemMod {
-                content: Some((_, items)),
-                ..
-            } = item_mod
-            {
-                traverse_items(items, depth + 1)
+    match options.fail_under {
+        Some(threshold) if report.percent() < threshold => Err(format!(
+            "documentation coverage is {:.1}%, under the {}% required",
+            report.percent(),
+            threshold
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// the document for the inputs, failing on the first file that doesn't
+/// parse. the config isn't applied yet
+fn document_for(
+    inputs: &[PathBuf],
+    config: &config::Config,
+) -> Result<rocket_document::RocketDocument, String> {
+    let parsed = parse_inputs(inputs, config)?;
+    match parsed.errors.first() {
+        Some(error) => Err(format!(
+            "could not parse {}:{}: {}",
+            error.file.as_ref().unwrap().display(),
+            error.line.unwrap(),
+            error.message
+        )),
+        None => Ok(parsed.document),
+    }
+}
+
+/// what the inputs parse to
+struct Parsed {
+    document: rocket_document::RocketDocument,
+    // the files it was parsed from, so findings can point into them
+    files: Vec<lint::SourceFile>,
+    // the ones that didn't parse
+    errors: Vec<lint::Diagnostic>,
+}
+
+/// every source under the inputs that isn't excluded. a file that doesn't
+/// parse is left out of the document and reported with the rest
+fn parse_inputs(inputs: &[PathBuf], config: &config::Config) -> Result<Parsed, String> {
+    let mut files = vec![];
+    let mut asts = vec![];
+    let mut errors = vec![];
+    for source in cli::collect_sources(inputs)? {
+        if config.excludes(&source.module) {
+            continue;
+        }
+        let content = cli::read_source(&source.path)?;
+        match syn::parse_file(&content) {
+            Ok(ast) => {
+                asts.push((source.module.to_owned(), ast));
+                files.push(lint::SourceFile {
+                    path: source.path,
+                    module: source.module,
+                    content,
+                });
             }
+            Err(err) => errors.push(lint::Diagnostic::parse_error(&source.path, &content, &err)),
         }
+    }
 
-        // struct x { }
-        syn::Item::Struct(strct) => {
-            let x = rocket_struct::RocketStruct::parse_struct(strct);
-            print_json(x);
-        }
+    let document = rocket_document::RocketDocument::parse_files(
+        asts.iter().map(|(module, ast)| (module.to_owned(), ast)),
+    );
+    Ok(Parsed {
+        document,
+        files,
+        errors,
+    })
+}
 
-        syn::Item::Enum(enm) => {
-            let x = rocket_enum::RocketEnum::parse_enum(enm);
-            print_json(x.unwrap());
+/// a format rendered in memory, ready to be written or checked
+fn render(
+    document: &rocket_document::RocketDocument,
+    info: &openapi::OpenApiInfo,
+    format: &str,
+    path: Option<PathBuf>,
+    types_crate: &Option<String>,
+) -> Result<output::Output, String> {
+    match format {
+        "json-schema" => {
+            return output::Output::dir(
+                path,
+                json_schema::to_json_schemas(document),
+                "the json-schema format",
+            )
+        }
+        "bruno" => {
+            return output::Output::dir(
+                path,
+                collection::to_bruno(document, &info.title),
+                "the bruno format",
+            )
+        }
+        "html" => {
+            return output::Output::dir(
+                path,
+                html::to_html_site(document, &info.title),
+                "the html format",
+            )
         }
         _ => (),
+    }
+
+    let content = match format {
+        "json" => serde_json::to_string(document).unwrap(),
+        "openapi" | "openapi-json" => openapi::to_openapi_json(document, info),
+        "markdown" | "md" => markdown::to_markdown(document),
+        "typescript" | "ts" => typescript::to_typescript(document),
+        "rust-client" | "rust" => rust_client::to_rust_client(
+            document,
+            &rust_client::RustClientOptions {
+                types_crate: types_crate.to_owned(),
+            },
+        ),
+        "postman" => collection::to_postman(document, &info.title),
+        "insomnia" => collection::to_insomnia(document, &info.title),
+        "http" => snippets::to_http_file(document),
+        "curl" => snippets::to_curl(document),
+        "openapi-yaml" => openapi::to_openapi_yaml(document, info),
+        format => return Err(format!("unknown format {}\n\n{}", format, cli::USAGE)),
     };
+    Ok(output::Output::file(path, content))
 }
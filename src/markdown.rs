@@ -0,0 +1,434 @@
+use crate::example::ExampleGenerator;
+use crate::guard::GuardKind;
+use crate::rocket_attribute::BodyDirection;
+use crate::rocket_document::{by_module, RocketDocument};
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_struct::RocketStruct;
+use crate::rocket_uri::UriSegment;
+use crate::rocket_wrapper::status_reason;
+use crate::type_ref::TypeRef;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// a markdown reference for the document, routes then types, both grouped by
+/// the module they are declared in. the output only depends on the code so it
+/// can be committed and diffed in review
+pub fn to_markdown(document: &RocketDocument) -> String {
+    let mut out = String::new();
+    writeln!(out, "# API reference").unwrap();
+
+    if !document.routes.is_empty() {
+        let examples = ExampleGenerator::new(document);
+        writeln!(out, "\n## Routes").unwrap();
+        by_module(&document.routes, |route| &route.module)
+            .into_iter()
+            .for_each(|(module, routes)| {
+                writeln!(out, "\n### `{}`", module).unwrap();
+                routes
+                    .iter()
+                    .for_each(|route| write_route(&mut out, document, &examples, route));
+            });
+    }
+
+    if !document.structs.is_empty() || !document.enums.is_empty() {
+        writeln!(out, "\n## Types").unwrap();
+        let structs = by_module(&document.structs, |strct| &strct.module);
+        let enums = by_module(&document.enums, |enm| &enm.module);
+        let modules: BTreeSet<&String> = structs.keys().chain(enums.keys()).collect();
+
+        modules.into_iter().for_each(|module| {
+            writeln!(out, "\n### `{}`", module).unwrap();
+            structs.get(module).into_iter().flatten().for_each(|strct| {
+                write_type_heading(
+                    &mut out,
+                    &document.type_name(&strct.ident, &strct.module),
+                    &strct.ident,
+                    &strct.docs,
+                    strct.direction,
+                );
+                write_fields(&mut out, document, strct);
+            });
+            enums.get(module).into_iter().flatten().for_each(|enm| {
+                write_type_heading(
+                    &mut out,
+                    &document.type_name(&enm.ident, &enm.module),
+                    &enm.ident,
+                    &enm.docs,
+                    enm.direction,
+                );
+                writeln!(out, "\n| Variant | Status | Fields |").unwrap();
+                writeln!(out, "| --- | --- | --- |").unwrap();
+                enm.variants.iter().for_each(|variant| {
+                    let fields = variant
+                        .fields
+                        .iter()
+                        .map(|(name, ty)| match name.parse::<usize>() {
+                            Ok(_) => type_cell(document, &TypeRef::parse(ty)),
+                            Err(_) => {
+                                format!("{}: {}", name, type_cell(document, &TypeRef::parse(ty)))
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let status = variant
+                        .response
+                        .as_ref()
+                        .and_then(|response| response.status)
+                        .map(|status| status.to_string())
+                        .unwrap_or_default();
+                    writeln!(out, "| `{}` | {} | {} |", variant.ident, status, fields).unwrap();
+                });
+            });
+        });
+    }
+
+    out
+}
+
+/// anchors are lowercase and only letters, digits and dashes so they work
+/// the same on github, gitlab and most static site generators. `name` is
+/// the document's type_name, so same named types get their module in it
+fn anchor(name: &str) -> String {
+    let name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    format!("type-{}", name.to_lowercase())
+}
+
+/// the type in code style, followed by links to every struct or enum in the
+/// document it mentions
+fn type_cell(document: &RocketDocument, ty: &TypeRef) -> String {
+    let mut links = vec![];
+    let mut pending = vec![ty];
+    while let Some(ty) = pending.pop() {
+        if let Some((module, ident)) = document.declared(ty) {
+            let name = document.type_name(&ident, &module);
+            let link = format!("[{}](#{})", name, anchor(&name));
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+        // reversed so links come out in the order they are written
+        pending.extend(ty.children().into_iter().rev());
+    }
+
+    let code = format!("`{}`", ty.to_string().replace('|', "\\|"));
+    if links.is_empty() {
+        code
+    } else {
+        format!("{} ({})", code, links.join(", "))
+    }
+}
+
+fn write_route(
+    out: &mut String,
+    document: &RocketDocument,
+    examples: &ExampleGenerator,
+    route: &RocketRoute,
+) {
+    route.full_uris().iter().for_each(|uri| {
+        writeln!(
+            out,
+            "\n#### `{} {}`",
+            route.route.method.to_uppercase(),
+            uri.templated_path()
+        )
+        .unwrap()
+    });
+    if let Some(docs) = &route.docs {
+        writeln!(out, "\n{}", docs).unwrap();
+    }
+    writeln!(out, "\nHandler: `{}`", route.ident).unwrap();
+
+    let mut rows = vec![];
+    route.params.iter().for_each(|param| {
+        let location = match param.kind {
+            ParamKind::Path => "path".to_string(),
+            ParamKind::Query | ParamKind::QueryForm => "query".to_string(),
+            ParamKind::Guard => match document.guard_inputs(&param.ty).as_slice() {
+                // nothing the client has to send
+                [GuardKind::Ignore] => return,
+                [] => "guard".to_string(),
+                inputs => inputs
+                    .iter()
+                    .map(|kind| kind.location())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            },
+            // documented as the request body
+            ParamKind::Data => return,
+        };
+        let required = match param.kind {
+            ParamKind::Path => "yes",
+            _ if param.ty.ident() == Some("Option") => "no",
+            _ => "yes",
+        };
+        rows.push(format!(
+            "| `{}` | {} | {} | {} |",
+            param.ident,
+            location,
+            type_cell(document, &param.ty),
+            required
+        ));
+    });
+    route.uri.query.iter().for_each(|segment| {
+        if let UriSegment::Static { value } = segment {
+            let mut kv = value.splitn(2, '=');
+            rows.push(format!(
+                "| `{}` | query | `= {}` | yes |",
+                kv.next().unwrap_or_default(),
+                kv.next().unwrap_or_default()
+            ));
+        }
+    });
+    if !rows.is_empty() {
+        writeln!(out, "\n**Parameters**\n").unwrap();
+        writeln!(out, "| Name | In | Type | Required |").unwrap();
+        writeln!(out, "| --- | --- | --- | --- |").unwrap();
+        rows.iter()
+            .for_each(|row| writeln!(out, "{}", row).unwrap());
+    }
+
+    if let Some(body) = &route.body {
+        writeln!(out, "\n**Request body**\n").unwrap();
+        let schema = body
+            .schema
+            .as_ref()
+            .map(|schema| type_cell(document, schema))
+            .unwrap_or_else(|| "raw data".to_string());
+        writeln!(
+            out,
+            "{}{}{}",
+            body.media_type
+                .as_ref()
+                .map(|media_type| format!("`{}`: ", media_type))
+                .unwrap_or_default(),
+            schema,
+            if body.required { "" } else { " (optional)" }
+        )
+        .unwrap();
+        if let Some(example) = examples.body_example(&body.schema, body.media_type.as_deref()) {
+            write_example(out, body.media_type.as_deref(), &example);
+        }
+    }
+
+    if !route.responses.is_empty() {
+        writeln!(out, "\n**Responses**\n").unwrap();
+        writeln!(out, "| Status | Content type | Body |").unwrap();
+        writeln!(out, "| --- | --- | --- |").unwrap();
+        route.responses.iter().for_each(|res| {
+            let status = res
+                .status
+                .map(|status| format!("{} {}", status, status_reason(status)))
+                .unwrap_or_else(|| "decided at runtime".to_string());
+            let content_type = res
+                .content_type
+                .as_ref()
+                .map(|content_type| format!("`{}`", content_type))
+                .unwrap_or_default();
+            let body = res
+                .body
+                .as_ref()
+                .map(|body| type_cell(document, body))
+                .unwrap_or_default();
+            writeln!(out, "| {} | {} | {} |", status, content_type, body).unwrap();
+        });
+
+        // an example for the first body of every status
+        let mut seen = BTreeSet::new();
+        route
+            .responses
+            .iter()
+            .filter(|res| res.body.is_some() && seen.insert(res.status))
+            .for_each(|res| {
+                if let Some(example) = examples.body_example(&res.body, res.content_type.as_deref())
+                {
+                    let status = res
+                        .status
+                        .map(|status| format!("{} {}", status, status_reason(status)))
+                        .unwrap_or_else(|| "runtime status".to_string());
+                    writeln!(
+                        out,
+                        "
+Example {} response:",
+                        status
+                    )
+                    .unwrap();
+                    write_example(out, res.content_type.as_deref(), &example);
+                }
+            });
+    }
+}
+
+fn write_example(out: &mut String, content_type: Option<&str>, example: &str) {
+    let language = match content_type {
+        Some(content_type) if !content_type.contains("json") => "text",
+        _ => "json",
+    };
+    writeln!(out, "\n```{}\n{}\n```", language, example).unwrap();
+}
+
+fn write_type_heading(
+    out: &mut String,
+    name: &str,
+    ident: &str,
+    docs: &Option<String>,
+    direction: Option<BodyDirection>,
+) {
+    writeln!(out, "\n#### <a id=\"{}\"></a>`{}`", anchor(name), ident).unwrap();
+    if let Some(docs) = docs {
+        writeln!(out, "\n{}", docs).unwrap();
+    }
+    if let Some(direction) = direction {
+        let used_as = match direction {
+            BodyDirection::Request => "request body",
+            BodyDirection::Response => "response body",
+            BodyDirection::Both => "request and response body",
+        };
+        writeln!(out, "\nUsed as: {}", used_as).unwrap();
+    }
+}
+
+/// fields by the name they have on the wire, required the same way as in
+/// the json schema
+fn write_fields(out: &mut String, document: &RocketDocument, strct: &RocketStruct) {
+    let fields: Vec<&(String, String)> = strct
+        .fields
+        .iter()
+        .filter(|(name, _)| {
+            !strct
+                .serde
+                .field(name)
+                .map(|field| field.skip.skipped(strct.direction))
+                .unwrap_or(false)
+        })
+        .collect();
+    if fields.is_empty() {
+        return;
+    }
+    writeln!(out, "\n| Field | Type | Required |").unwrap();
+    writeln!(out, "| --- | --- | --- |").unwrap();
+    fields.into_iter().for_each(|(name, ty)| {
+        let ty = TypeRef::parse(ty);
+        let required = match strct.serde.field(name) {
+            Some(field) if field.flatten => "flattened",
+            _ if strct.serde.required(name, &ty, strct.direction) => "yes",
+            _ => "no",
+        };
+        writeln!(
+            out,
+            "| `{}` | {} | {} |",
+            strct.serde.field_name(name),
+            type_cell(document, &ty),
+            required
+        )
+        .unwrap();
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_routes_and_types_by_module() {
+        let ast = syn::parse_str(
+            "
+            mod api {
+                /// Updates a user.
+                #[put(\"/users/<id>?<notify>\", format = \"json\", data = \"<user>\")]
+                fn update(id: u64, notify: Option<bool>, user: Json<User>) -> Option<Json<User>> {}
+            }
+            mod models {
+                /// Someone with an account.
+                #[derive(Serialize, Deserialize)]
+                struct User { id: u64, nickname: Option<String> }
+            }
+            fn main() {
+                rocket::ignite().mount(\"/api\", routes![api::update]);
+            }
+            ",
+        )
+        .unwrap();
+        let result = to_markdown(&RocketDocument::parse_file(&ast));
+
+        assert!(
+            result.contains("### `crate::api`\n\n#### `PUT /api/users/{id}`\n\nUpdates a user.\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "| `id` | path | `u64` | yes |\n| `notify` | query | `Option<bool>` | no |\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("`application/json`: `User` ([User](#type-user))"),
+            "{}",
+            result
+        );
+        assert!(result.contains("| 404 Not Found |  |  |"), "{}", result);
+        assert!(
+            result.contains(
+                "### `crate::models`\n\n#### <a id=\"type-user\"></a>`User`\n\nSomeone with an account.\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("| `nickname` | `Option<String>` | no |"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn links_same_named_types_by_module_and_uses_serde_names() {
+        let ast = syn::parse_str(
+            "
+            #[derive(Serialize)]
+            #[serde(rename_all = \"camelCase\")]
+            struct User { user_name: String, #[serde(default)] tags: Vec<String> }
+
+            mod api {
+                mod items {
+                    #[derive(Serialize)]
+                    struct User { id: u64 }
+
+                    #[get(\"/items/<id>\")]
+                    fn item(id: u64) -> Json<User> {}
+                }
+            }
+            ",
+        )
+        .unwrap();
+        let result = to_markdown(&RocketDocument::parse_file(&ast));
+
+        assert!(
+            result.contains(
+                "| `crate::api::items::User` ([api::items::User](#type-api-items-user)) |"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("#### <a id=\"type-api-items-user\"></a>`User`"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("#### <a id=\"type-crate-user\"></a>`User`"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("| `userName` | `String` | yes |\n| `tags` | `Vec<String>` | no |\n"),
+            "Fields go by their serde name and defaults aren't required\n{}",
+            result
+        );
+    }
+}
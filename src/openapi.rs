@@ -0,0 +1,509 @@
+use crate::example::ExampleGenerator;
+use crate::guard::GuardKind;
+use crate::rocket_document::RocketDocument;
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_uri::UriSegment;
+use crate::rocket_wrapper::RocketResponse;
+use crate::schema::SchemaGenerator;
+use crate::type_ref::TypeRef;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+const REF_PREFIX: &str = "#/components/schemas/";
+
+/// the bits of an openapi document that don't come from the code
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenApiInfo {
+    pub title: String,
+    pub version: String,
+    pub servers: Vec<Server>,
+}
+
+/// somewhere the api is deployed
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Server {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl Default for OpenApiInfo {
+    fn default() -> Self {
+        OpenApiInfo {
+            title: "API".to_string(),
+            version: "0.1.0".to_string(),
+            servers: vec![],
+        }
+    }
+}
+
+/// an openapi 3.1 document for every route in the document, with a schema in
+/// components for every struct/enum that any of them reference
+pub fn to_openapi(document: &RocketDocument, info: &OpenApiInfo) -> Value {
+    let generator = SchemaGenerator::new(document, REF_PREFIX);
+    let examples = ExampleGenerator::new(document);
+
+    // routes told apart only by rank share a method and path. the one rocket
+    // tries first is the operation and the rest are kept next to it, since
+    // openapi has room for one operation per method
+    type Ranked = Vec<(Option<i32>, Value)>;
+    let mut ranked: BTreeMap<(String, String), Ranked> = BTreeMap::new();
+    document.routes.iter().for_each(|route| {
+        let uris = route.full_uris();
+        uris.iter()
+            .zip(&route.mounts_or_root())
+            .for_each(|(uri, base)| {
+                let mut operation = operation(document, route, &generator, &examples);
+                operation["operationId"] = json!(operation_id(route, base, uris.len() > 1));
+                ranked
+                    .entry((uri.templated_path(), route.route.method.to_lowercase()))
+                    .or_default()
+                    .push((route.route.rank, operation));
+            })
+    });
+    let mut paths = Map::new();
+    ranked
+        .into_iter()
+        .for_each(|((path, method), mut operations)| {
+            // unranked routes get a negative rank from rocket, so they go first
+            operations.sort_by_key(|(rank, _)| rank.unwrap_or(i32::MIN));
+            let mut operations = operations.into_iter().map(|(rank, mut operation)| {
+                if let Some(rank) = rank {
+                    operation["x-rocket-rank"] = json!(rank);
+                }
+                operation
+            });
+            let mut operation = operations.next().unwrap();
+            let rest: Vec<Value> = operations.collect();
+            if !rest.is_empty() {
+                operation["x-rocket-ranked"] = Value::Array(rest);
+            }
+            paths.entry(path).or_insert_with(|| json!({}))[method] = operation;
+        });
+
+    let roots = document
+        .routes
+        .iter()
+        .flat_map(|route| {
+            route
+                .params
+                .iter()
+                .filter(|param| param.kind != ParamKind::Guard)
+                .map(|param| param.ty.to_owned())
+                .chain(route.body.iter().filter_map(|body| body.schema.to_owned()))
+                .chain(route.responses.iter().filter_map(|res| res.body.to_owned()))
+        })
+        .collect();
+
+    let mut openapi = json!({
+        "openapi": "3.1.0",
+        "info": { "title": info.title, "version": info.version },
+        "paths": paths,
+        "components": { "schemas": generator.definitions(roots) }
+    });
+    if !info.servers.is_empty() {
+        openapi["servers"] = json!(info.servers);
+    }
+    let schemes: Map<String, Value> = document
+        .routes
+        .iter()
+        .flat_map(|route| route.params_of_kind(ParamKind::Guard))
+        .flat_map(|param| {
+            document
+                .guard_inputs(&param.ty)
+                .into_iter()
+                .filter_map(move |kind| match kind {
+                    GuardKind::Auth { scheme } => Some((
+                        security_scheme_name(&param.ty),
+                        json!({ "type": "http", "scheme": scheme }),
+                    )),
+                    GuardKind::ApiKey { name, location } => Some((
+                        security_scheme_name(&param.ty),
+                        json!({ "type": "apiKey", "in": location, "name": name }),
+                    )),
+                    _ => None,
+                })
+        })
+        .collect();
+    if !schemes.is_empty() {
+        openapi["components"]["securitySchemes"] = Value::Object(schemes);
+    }
+    openapi
+}
+
+/// the handler's module path and name, unique as long as handlers are. a
+/// route mounted more than once gets the base it's mounted at on the end
+fn operation_id(route: &RocketRoute, base: &str, several: bool) -> String {
+    let mut id = route
+        .module
+        .iter()
+        .chain(std::iter::once(&route.ident))
+        .map(|segment| segment.as_str())
+        .collect::<Vec<_>>()
+        .join("_");
+    if several {
+        let base = base
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("_");
+        id.push('_');
+        id.push_str(if base.is_empty() { "root" } else { &base });
+    }
+    id
+}
+
+/// auth guards are security schemes named after the guard's type
+fn security_scheme_name(ty: &TypeRef) -> String {
+    unwrap_param(ty).ident().unwrap_or_default().to_string()
+}
+
+pub fn to_openapi_json(document: &RocketDocument, info: &OpenApiInfo) -> String {
+    serde_json::to_string_pretty(&to_openapi(document, info)).unwrap()
+}
+
+pub fn to_openapi_yaml(document: &RocketDocument, info: &OpenApiInfo) -> String {
+    serde_yaml::to_string(&to_openapi(document, info)).unwrap()
+}
+
+/// params can be wrapped in Option (not required) or Result (validated), the
+/// schema is for what's inside
+pub(crate) fn unwrap_param(ty: &TypeRef) -> &TypeRef {
+    match (ty.ident(), ty.args()) {
+        (Some("Option"), [inner])
+        | (Some("Result"), [inner, ..])
+        | (Some("Form"), [inner])
+        | (Some("LenientForm"), [inner]) => unwrap_param(inner),
+        _ => ty,
+    }
+}
+
+fn operation(
+    document: &RocketDocument,
+    route: &RocketRoute,
+    generator: &SchemaGenerator,
+    examples: &ExampleGenerator,
+) -> Value {
+    let mut parameters: Vec<Value> = vec![];
+
+    route.params_of_kind(ParamKind::Path).for_each(|param| {
+        parameters.push(json!({
+            "name": param.ident,
+            "in": "path",
+            "required": true,
+            "schema": generator.type_schema(unwrap_param(&param.ty))
+        }))
+    });
+    route.uri.query.iter().for_each(|segment| match segment {
+        // lang=en, has to be there with exactly that value
+        UriSegment::Static { value } => {
+            let mut kv = value.splitn(2, '=');
+            parameters.push(json!({
+                "name": kv.next().unwrap_or_default(),
+                "in": "query",
+                "required": true,
+                "schema": { "const": kv.next().unwrap_or_default() }
+            }))
+        }
+        _ => route
+            .params
+            .iter()
+            .filter(|param| Some(param.ident.as_str()) == segment.name())
+            .for_each(|param| {
+                let mut parameter = json!({
+                    "name": param.ident,
+                    "in": "query",
+                    "required": param.ty.ident() != Some("Option"),
+                    "schema": generator.type_schema(unwrap_param(&param.ty))
+                });
+                // a FromForm struct, every field is its own query param
+                if param.kind == ParamKind::QueryForm {
+                    parameter["style"] = json!("form");
+                    parameter["explode"] = json!(true);
+                }
+                parameters.push(parameter)
+            }),
+    });
+
+    // guards that read a header or a cookie are params too, auth guards are
+    // the operation's security
+    let mut security = vec![];
+    route.params_of_kind(ParamKind::Guard).for_each(|param| {
+        let required = param.ty.ident() != Some("Option");
+        document
+            .guard_inputs(&param.ty)
+            .into_iter()
+            .for_each(|kind| {
+                let (location, name) = match kind {
+                    GuardKind::Header { name } => ("header", name),
+                    GuardKind::Cookie { name } => ("cookie", name),
+                    GuardKind::Auth { .. } | GuardKind::ApiKey { .. } => {
+                        security.push(json!({ security_scheme_name(&param.ty): [] }));
+                        // an optional guard means the route works without it too
+                        if !required {
+                            security.push(json!({}));
+                        }
+                        return;
+                    }
+                    GuardKind::Ignore => return,
+                };
+                parameters.push(json!({
+                    "name": name,
+                    "in": location,
+                    "required": required,
+                    "schema": { "type": "string" }
+                }))
+            })
+    });
+
+    let mut operation = json!({
+        "parameters": parameters,
+        "responses": responses(&route.responses, generator, examples)
+    });
+    if !security.is_empty() {
+        operation["security"] = Value::Array(security);
+    }
+
+    if let Some(body) = &route.body {
+        let schema = body
+            .schema
+            .as_ref()
+            .map(|schema| generator.type_schema(schema))
+            .unwrap_or_else(|| json!({ "type": "string", "format": "binary" }));
+        let mut media_type = json!({ "schema": schema });
+        if let Some(schema) = &body.schema {
+            media_type["example"] = examples.example(schema);
+        }
+        operation["requestBody"] = json!({
+            "required": body.required,
+            "content": {
+                body.media_type.as_deref().unwrap_or("application/octet-stream"): media_type
+            }
+        });
+    }
+
+    operation
+}
+
+/// responses with the same status are merged, bodies with the same content
+/// type under a status become a oneOf. the example is the first body's
+fn responses(
+    responses: &[RocketResponse],
+    generator: &SchemaGenerator,
+    examples: &ExampleGenerator,
+) -> Value {
+    let mut out = Map::new();
+
+    responses.iter().for_each(|res| {
+        let (code, description) = match res.status {
+            Some(status) => (
+                status.to_string(),
+                crate::rocket_wrapper::status_reason(status),
+            ),
+            None => ("default".to_string(), "Status decided at runtime"),
+        };
+        let entry = out
+            .entry(code)
+            .or_insert_with(|| json!({ "description": description }));
+
+        if res.body.is_none() && res.content_type.is_none() {
+            return;
+        }
+        let schema = res
+            .body
+            .as_ref()
+            .map(|body| generator.type_schema(body))
+            .unwrap_or_else(|| json!({ "type": "string", "format": "binary" }));
+        let content_type = res.content_type.as_deref().unwrap_or("*/*");
+
+        let content = entry
+            .as_object_mut()
+            .unwrap()
+            .entry("content")
+            .or_insert_with(|| json!({}));
+        match content.get_mut(content_type) {
+            None => {
+                content[content_type] = json!({ "schema": schema });
+                if let Some(body) = &res.body {
+                    content[content_type]["example"] = examples.example(body);
+                }
+            }
+            Some(existing) if existing["schema"] == schema => (),
+            Some(existing) => {
+                let previous = existing["schema"].take();
+                existing["schema"] = match previous {
+                    Value::Object(mut obj) if obj.contains_key("oneOf") => {
+                        obj["oneOf"].as_array_mut().unwrap().push(schema);
+                        Value::Object(obj)
+                    }
+                    previous => json!({ "oneOf": [previous, schema] }),
+                };
+            }
+        }
+    });
+
+    Value::Object(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn openapi(content: &str) -> Value {
+        to_openapi(
+            &RocketDocument::parse_file(&syn::parse_str(content).unwrap()),
+            &OpenApiInfo::default(),
+        )
+    }
+
+    #[test]
+    fn documents_routes_with_params_and_bodies() {
+        let result = openapi(
+            "
+            #[derive(Deserialize)]
+            struct NewUser { name: String }
+            #[derive(Serialize)]
+            struct User { id: u64, name: String }
+
+            #[put(\"/users/<id>?<notify>&v=2\", format = \"json\", data = \"<user>\")]
+            fn update(id: u64, notify: Option<bool>, user: Json<NewUser>, admin: Admin) -> Option<Json<User>> {}
+            ",
+        );
+
+        assert_eq!(result["openapi"], "3.1.0");
+        assert_eq!(
+            result["paths"]["/users/{id}"]["put"],
+            json!({
+                "operationId": "update",
+                "parameters": [
+                    {
+                        "name": "id",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "integer", "format": "int64", "minimum": 0 }
+                    },
+                    {
+                        "name": "notify",
+                        "in": "query",
+                        "required": false,
+                        "schema": { "type": "boolean" }
+                    },
+                    {
+                        "name": "v",
+                        "in": "query",
+                        "required": true,
+                        "schema": { "const": "2" }
+                    }
+                ],
+                "requestBody": {
+                    "required": true,
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/NewUser" },
+                            "example": { "name": "string" }
+                        }
+                    }
+                },
+                "responses": {
+                    "200": {
+                        "description": "OK",
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/User" },
+                                "example": { "id": 0, "name": "string" }
+                            }
+                        }
+                    },
+                    "404": { "description": "Not Found" }
+                }
+            })
+        );
+        assert_eq!(
+            result["components"]["schemas"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["NewUser", "User"],
+            "Every referenced struct gets a schema, request guards don't"
+        );
+    }
+
+    #[test]
+    fn merges_responses_with_the_same_status() {
+        let result = openapi(
+            "
+            #[derive(Responder)]
+            enum Reply {
+                #[response(status = 200, content_type = \"json\")]
+                One(Json<A>),
+                #[response(status = 200, content_type = \"json\")]
+                Other(Json<B>),
+                #[response(status = 500)]
+                Broken(String),
+            }
+
+            #[get(\"/\")]
+            fn index() -> Reply {}
+            ",
+        );
+        assert_eq!(
+            result["paths"]["/"]["get"]["responses"]["200"]["content"]["application/json"]
+                ["schema"],
+            json!({ "oneOf": [{ "description": "A" }, { "description": "B" }] })
+        );
+        assert_eq!(
+            result["paths"]["/"]["get"]["responses"]["500"]["content"]["text/plain"]["schema"],
+            json!({ "type": "string" })
+        );
+    }
+
+    #[test]
+    fn keeps_ranked_routes_and_ids_unique() {
+        let result = openapi(
+            "
+            mod posts {
+                #[get(\"/<id>\", rank = 2)]
+                fn by_name(id: String) {}
+
+                #[get(\"/<id>\")]
+                fn by_id(id: u64) {}
+            }
+
+            mod users {
+                #[get(\"/<id>\")]
+                fn by_id(id: u64) {}
+            }
+
+            fn main() {
+                rocket::ignite()
+                    .mount(\"/posts\", routes![posts::by_id, posts::by_name])
+                    .mount(\"/users\", routes![users::by_id])
+                    .mount(\"/v1/users\", routes![users::by_id]);
+            }
+            ",
+        );
+        let posts = &result["paths"]["/posts/{id}"]["get"];
+        assert_eq!(posts["operationId"], "posts_by_id");
+        assert_eq!(
+            posts["x-rocket-ranked"][0]["operationId"], "posts_by_name",
+            "Routes with a higher rank are kept next to the one tried first"
+        );
+        assert_eq!(posts["x-rocket-ranked"][0]["x-rocket-rank"], 2);
+        assert_eq!(
+            result["paths"]["/users/{id}"]["get"]["operationId"],
+            "users_by_id_users"
+        );
+        assert_eq!(
+            result["paths"]["/v1/users/{id}"]["get"]["operationId"],
+            "users_by_id_v1_users"
+        );
+    }
+
+    #[test]
+    fn renders_yaml() {
+        let yaml = to_openapi_yaml(&RocketDocument::default(), &OpenApiInfo::default());
+        assert!(yaml.contains("openapi: 3.1.0"), "{}", yaml);
+    }
+}
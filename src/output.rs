@@ -0,0 +1,229 @@
+//! where the generated docs go. everything is rendered in memory first, so
+//! the same outputs can either be written or (with --check) compared with
+//! what's committed
+
+use similar::TextDiff;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// what was written into an output directory last time, so files that aren't
+// generated anymore can be told apart from ones someone else put there
+const MANIFEST: &str = ".rocket-doc-gen-files";
+
+pub enum Output {
+    Stdout(String),
+    File(PathBuf, String),
+    // formats that produce more than one file, relative path to content
+    Dir(PathBuf, BTreeMap<String, String>),
+}
+
+impl Output {
+    pub fn file(path: Option<PathBuf>, content: String) -> Self {
+        match path {
+            Some(path) => Output::File(path, content),
+            None => Output::Stdout(content),
+        }
+    }
+
+    /// formats that produce more than one file write them into a directory
+    pub fn dir(
+        path: Option<PathBuf>,
+        files: BTreeMap<String, String>,
+        what: &str,
+    ) -> Result<Self, String> {
+        let dir = path.ok_or_else(|| format!("{} needs --output <DIR>", what))?;
+        Ok(Output::Dir(dir, files))
+    }
+
+    pub fn write(&self) -> Result<(), String> {
+        match self {
+            Output::Stdout(content) => {
+                println!("{}", content);
+                Ok(())
+            }
+            Output::File(path, content) => write_file(path, content),
+            Output::Dir(dir, files) => {
+                stale(dir, files).iter().try_for_each(|name| {
+                    let path = dir.join(name);
+                    std::fs::remove_file(&path)
+                        .map_err(|err| format!("could not remove {}: {}", path.display(), err))?;
+                    // and the directories that leaves empty, remove_dir
+                    // refuses the ones that aren't
+                    path.ancestors()
+                        .skip(1)
+                        .take_while(|parent| *parent != dir.as_path())
+                        .try_for_each(|parent| std::fs::remove_dir(parent).ok());
+                    Ok::<_, String>(())
+                })?;
+                files
+                    .iter()
+                    .try_for_each(|(name, content)| write_file(&dir.join(name), content))?;
+                let manifest = files
+                    .keys()
+                    .map(|name| format!("{}\n", name))
+                    .collect::<String>();
+                write_file(&dir.join(MANIFEST), &manifest)
+            }
+        }
+    }
+
+    /// how the files on disk differ from what would be written, a unified
+    /// diff per file that doesn't match. files an earlier run wrote into an
+    /// output directory that wouldn't be written anymore count as well,
+    /// writing removes them
+    pub fn check(&self) -> Result<Vec<String>, String> {
+        match self {
+            Output::Stdout(_) => Err(
+                "--check compares with files, it needs --output or [outputs] in the config"
+                    .to_string(),
+            ),
+            Output::File(path, content) => Ok(compare(path, content).into_iter().collect()),
+            Output::Dir(dir, files) => {
+                let mut drift: Vec<String> = files
+                    .iter()
+                    .filter_map(|(name, content)| compare(&dir.join(name), content))
+                    .collect();
+                stale(dir, files).iter().for_each(|name| {
+                    drift.push(format!(
+                        "{} isn't generated anymore\n",
+                        dir.join(name).display()
+                    ))
+                });
+                Ok(drift)
+            }
+        }
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("could not create {}: {}", parent.display(), err))?;
+    }
+    std::fs::write(path, content)
+        .map_err(|err| format!("could not write {}: {}", path.display(), err))
+}
+
+/// a unified diff from what's on disk to what should be there, None if
+/// they're the same
+fn compare(path: &Path, expected: &str) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(actual) if actual == expected => None,
+        Ok(actual) => Some(
+            TextDiff::from_lines(actual.as_str(), expected)
+                .unified_diff()
+                .header(
+                    &format!("a/{}", path.display()),
+                    &format!("b/{}", path.display()),
+                )
+                .to_string(),
+        ),
+        Err(_) => Some(format!("{} is missing\n", path.display())),
+    }
+}
+
+/// files the last run wrote into `dir` that are still there but aren't in
+/// `files`. anything not in the manifest isn't ours to report or remove
+fn stale(dir: &Path, files: &BTreeMap<String, String>) -> Vec<String> {
+    let written = std::fs::read_to_string(dir.join(MANIFEST)).unwrap_or_default();
+    let mut existing = vec![];
+    list_files(dir, dir, &mut existing);
+    existing
+        .into_iter()
+        .filter(|name| !files.contains_key(name))
+        .filter(|name| written.lines().any(|line| line == name))
+        .collect()
+}
+
+/// every file under `dir` relative to `root`, with `/` between the parts
+/// like the names formats give their files. dotfiles are left alone
+fn list_files(root: &Path, dir: &Path, out: &mut Vec<String>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    entries.filter_map(|entry| entry.ok()).for_each(|entry| {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            return;
+        }
+        if path.is_dir() {
+            list_files(root, &path, out);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            out.push(
+                relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reports_drift_as_a_unified_diff() {
+        let dir = std::env::temp_dir().join(format!("rocket-doc-gen-check-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let files: BTreeMap<String, String> = vec![
+            ("a.md".to_string(), "one\ntwo\n".to_string()),
+            ("b/c.md".to_string(), "three\n".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let output = Output::Dir(dir.to_owned(), files);
+        output.write().unwrap();
+        assert_eq!(output.check(), Ok(vec![]));
+
+        std::fs::write(dir.join("a.md"), "one\n2\n").unwrap();
+        std::fs::write(dir.join("notes.md"), "by hand\n").unwrap();
+        let renamed = Output::Dir(
+            dir.to_owned(),
+            vec![
+                ("a.md".to_string(), "one\ntwo\n".to_string()),
+                ("d.md".to_string(), "three\n".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let drift = renamed.check().unwrap();
+
+        assert_eq!(drift.len(), 3);
+        assert_eq!(
+            drift[0],
+            format!(
+                "--- a/{0}\n+++ b/{0}\n@@ -1,2 +1,2 @@\n one\n-2\n+two\n",
+                dir.join("a.md").display()
+            )
+        );
+        assert_eq!(
+            drift[1],
+            format!("{} is missing\n", dir.join("d.md").display())
+        );
+        assert_eq!(
+            drift[2],
+            format!("{} isn't generated anymore\n", dir.join("b/c.md").display()),
+            "Files it didn't write aren't reported"
+        );
+
+        renamed.write().unwrap();
+        let fixed = renamed.check();
+        let left = dir.join("b").exists();
+        let kept = dir.join("notes.md").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            fixed,
+            Ok(vec![]),
+            "Writing removes what it stopped generating"
+        );
+        assert!(!left);
+        assert!(kept);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::type_ref::TypeRef;
+use std::collections::BTreeMap;
+
+/// `type ApiResult<T> = Result<Json<T>, ApiError>;` is pretty common in rocket
+/// projects and the handlers will just return `ApiResult<User>`, so we need to
+/// know what the alias expands to before we can say anything about responses
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketAlias {
+    pub ident: String,
+    pub generics: Vec<String>,
+    // `type Result<T, E = ApiError>` fills in E when it's left out
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub defaults: BTreeMap<String, TypeRef>,
+    pub ty: TypeRef,
+    // where it's declared, a path to it has to lead there
+    #[serde(default)]
+    pub module: Vec<String>,
+}
+
+/// `pub use models::User as Account;` lets a type show up under a different
+/// name (and path) than the one it was declared with
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketReexport {
+    pub ident: String,
+    pub path: Vec<String>,
+}
+
+impl RocketAlias {
+    pub fn parse_type(ty: &syn::ItemType) -> Self {
+        RocketAlias {
+            ident: crate::ast_formatting::format_idnt(&ty.ident),
+            generics: ty
+                .generics
+                .type_params()
+                .map(|param| crate::ast_formatting::format_idnt(&param.ident))
+                .collect(),
+            defaults: ty
+                .generics
+                .type_params()
+                .filter_map(|param| {
+                    param.default.as_ref().map(|default| {
+                        (
+                            crate::ast_formatting::format_idnt(&param.ident),
+                            TypeRef::from_type(default),
+                        )
+                    })
+                })
+                .collect(),
+            ty: TypeRef::from_type(&ty.ty),
+            module: vec![],
+        }
+    }
+
+    /// `Result` anywhere, or a path to the module it's in. `std::result::Result`
+    /// is some other type with the same name
+    pub fn matches(&self, path: &[String]) -> bool {
+        match path.split_last() {
+            Some((ident, [])) => *ident == self.ident,
+            Some((ident, prefix)) => {
+                let prefix = match prefix.split_first() {
+                    Some((first, rest)) if first == "crate" => rest,
+                    _ => prefix,
+                };
+                *ident == self.ident && prefix == self.module.as_slice()
+            }
+            None => false,
+        }
+    }
+}
+
+impl RocketReexport {
+    /// only `pub use` items are re-exports. a single use can bring in a bunch
+    /// of names with `{..}` groups so we flatten the tree out
+    pub fn parse_use(item: &syn::ItemUse) -> Vec<Self> {
+        fn flatten(tree: &syn::UseTree, prefix: &mut Vec<String>, out: &mut Vec<RocketReexport>) {
+            match tree {
+                syn::UseTree::Path(path) => {
+                    prefix.push(crate::ast_formatting::format_idnt(&path.ident));
+                    flatten(&path.tree, prefix, out);
+                    prefix.pop();
+                }
+                syn::UseTree::Name(name) => {
+                    let mut path = prefix.to_owned();
+                    path.push(crate::ast_formatting::format_idnt(&name.ident));
+                    out.push(RocketReexport {
+                        ident: crate::ast_formatting::format_idnt(&name.ident),
+                        path,
+                    });
+                }
+                syn::UseTree::Rename(rename) => {
+                    let mut path = prefix.to_owned();
+                    path.push(crate::ast_formatting::format_idnt(&rename.ident));
+                    out.push(RocketReexport {
+                        ident: crate::ast_formatting::format_idnt(&rename.rename),
+                        path,
+                    });
+                }
+                syn::UseTree::Group(group) => group
+                    .items
+                    .iter()
+                    .for_each(|tree| flatten(tree, prefix, out)),
+                // globs don't tell us any names
+                syn::UseTree::Glob(_) => (),
+            }
+        }
+
+        let mut reexports = vec![];
+        if let syn::Visibility::Public(_) = item.vis {
+            flatten(&item.tree, &mut vec![], &mut reexports);
+        }
+        reexports
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_generic_type_alias() {
+        let result = RocketAlias::parse_type(
+            &syn::parse_str("type ApiResult<T, E = ApiError> = Result<Json<T>, E>;").unwrap(),
+        );
+        assert_eq!(
+            result,
+            RocketAlias {
+                ident: "ApiResult".to_string(),
+                generics: vec!["T".to_string(), "E".to_string()],
+                defaults: vec![("E".to_string(), TypeRef::parse("ApiError"))]
+                    .into_iter()
+                    .collect(),
+                ty: TypeRef::parse("Result<Json<T>, E>"),
+                module: vec![],
+            },
+            "Parses a type alias with generics and their defaults"
+        );
+    }
+
+    #[test]
+    fn parses_public_reexports() {
+        let result = RocketReexport::parse_use(
+            &syn::parse_str("pub use crate::models::{User as Account, Session, auth::*};").unwrap(),
+        );
+        assert_eq!(
+            result,
+            vec![
+                RocketReexport {
+                    ident: "Account".to_string(),
+                    path: vec![
+                        "crate".to_string(),
+                        "models".to_string(),
+                        "User".to_string()
+                    ],
+                },
+                RocketReexport {
+                    ident: "Session".to_string(),
+                    path: vec![
+                        "crate".to_string(),
+                        "models".to_string(),
+                        "Session".to_string()
+                    ],
+                },
+            ],
+            "Flattens use groups and keeps renames"
+        );
+    }
+
+    #[test]
+    fn ignores_private_uses() {
+        let result =
+            RocketReexport::parse_use(&syn::parse_str("use crate::models::User;").unwrap());
+        assert!(result.is_empty(), "Private uses are not re-exports");
+    }
+}
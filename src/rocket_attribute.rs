@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-#[derive(Debug, PartialEq, serde::Serialize)]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum RocketAttribute {
-    DeriveResponder,
+    Derive(Vec<String>),
     Response(ResponseAttribute),
     Route(RouteAttribute),
 }
 
-#[derive(Debug, PartialEq, serde::Serialize)]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RouteAttribute {
     pub method: String,
     pub path: String,
@@ -16,14 +16,65 @@ pub struct RouteAttribute {
     pub data: Option<String>,
 }
 
-#[derive(Debug, PartialEq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ResponseAttribute {
-    pub status: u32,
+    // None when it's left to the inner responder, `#[response(content_type = "json")]`
+    pub status: Option<u32>,
     pub content_type: Option<String>,
 }
 
+/// which way a type travels over the wire, based on what it derives. a type
+/// that can be read out of a request is a request body, one that can be
+/// written into a response is a response body
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyDirection {
+    Request,
+    Response,
+    Both,
+}
+
+impl BodyDirection {
+    pub fn from_derives(derives: &[String]) -> Option<Self> {
+        let derives_any = |names: &[&str]| names.iter().any(|name| derives_trait(derives, name));
+
+        match (
+            derives_any(&["Deserialize", "FromForm"]),
+            derives_any(&["Serialize", "Responder"]),
+        ) {
+            (true, true) => Some(BodyDirection::Both),
+            (true, false) => Some(BodyDirection::Request),
+            (false, true) => Some(BodyDirection::Response),
+            (false, false) => None,
+        }
+    }
+}
+
+/// whether `name` is in the list of derives, with or without a path in front
+/// of it since serde::Serialize and Serialize are the same thing to us
+pub fn derives_trait(derives: &[String], name: &str) -> bool {
+    derives
+        .iter()
+        .any(|derive| derive.rsplit("::").next() == Some(name))
+}
+
 impl RocketAttribute {
-    pub fn from_attributes(attrs: &Vec<syn::Attribute>) -> Vec<Self> {
+    /// all the derives across every #[derive(...)] attribute in the list
+    pub fn derives(attrs: &[Self]) -> Vec<String> {
+        attrs
+            .iter()
+            .filter_map(|attr| {
+                if let RocketAttribute::Derive(derives) = attr {
+                    Some(derives.to_owned())
+                } else {
+                    None
+                }
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn from_attributes(attrs: &[syn::Attribute]) -> Vec<Self> {
         fn nested_kv_to_hashmap(
             nested: &syn::punctuated::Punctuated<syn::NestedMeta, syn::token::Comma>,
         ) -> HashMap<String, String> {
@@ -50,35 +101,31 @@ impl RocketAttribute {
                 attr.parse_meta().ok().and_then(|meta| match meta {
                     syn::Meta::List(l) => {
                         match l.path.get_ident().map(|ident| ident.to_string()) {
-                            // #[derive(.., Responder, ..)]
-                            Some(ref id)
-                                if id == "derive"
-                                    && l.nested.iter().any(|meta| {
-                                        // ugly
+                            // #[derive(Serialize, Responder, ..)]
+                            Some(ref id) if id == "derive" => Some(RocketAttribute::Derive(
+                                l.nested
+                                    .iter()
+                                    .filter_map(|meta| {
                                         if let syn::NestedMeta::Meta(syn::Meta::Path(path)) = meta {
-                                            if path.segments.pairs().any(|segment| {
-                                                segment.value().ident.to_string() == "Responder"
-                                            }) {
-                                                true
-                                            } else {
-                                                false
-                                            }
+                                            Some(
+                                                path.segments
+                                                    .iter()
+                                                    .map(|segment| segment.ident.to_string())
+                                                    .collect::<Vec<_>>()
+                                                    .join("::"),
+                                            )
                                         } else {
-                                            false
+                                            None
                                         }
-                                    }) =>
-                            {
-                                Some(RocketAttribute::DeriveResponder)
-                            }
+                                    })
+                                    .collect(),
+                            )),
                             // #[response(status = num, content_type = "idk")
                             Some(ref id) if id == "response" => {
                                 let map = nested_kv_to_hashmap(&l.nested);
 
                                 Some(RocketAttribute::Response(ResponseAttribute {
-                                    status: map
-                                        .get("status")
-                                        .and_then(|val| val.parse().ok())
-                                        .unwrap(),
+                                    status: map.get("status").and_then(|val| val.parse().ok()),
                                     content_type: map
                                         .get("content_type")
                                         .map(|val| val.to_string()),
@@ -189,13 +236,67 @@ mod test {
         assert_eq!(
             x,
             vec![
-                RocketAttribute::DeriveResponder,
+                RocketAttribute::Derive(vec!["Responder".to_string()]),
                 RocketAttribute::Response(ResponseAttribute {
-                    status: 400,
+                    status: Some(400),
                     content_type: Some("application/json".to_string())
                 })
             ],
             "Parses attributes on a struct properly"
         )
     }
+
+    #[test]
+    fn parses_every_derive() {
+        let x = RocketAttribute::from_struct(
+            &syn::parse_str(
+                "
+                #[derive(Debug, serde::Deserialize)]
+                #[derive(FromForm)]
+                struct LoginData {
+                    username: String,
+                }
+                ",
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            RocketAttribute::derives(&x),
+            vec![
+                "Debug".to_string(),
+                "serde::Deserialize".to_string(),
+                "FromForm".to_string()
+            ],
+            "Collects derives across multiple derive attributes"
+        )
+    }
+
+    #[test]
+    fn classifies_body_direction() {
+        let derives = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            BodyDirection::from_derives(&derives(&["Debug", "serde::Deserialize"])),
+            Some(BodyDirection::Request)
+        );
+        assert_eq!(
+            BodyDirection::from_derives(&derives(&["Responder"])),
+            Some(BodyDirection::Response)
+        );
+        assert_eq!(
+            BodyDirection::from_derives(&derives(&["Serialize", "FromForm"])),
+            Some(BodyDirection::Both)
+        );
+        assert_eq!(
+            BodyDirection::from_derives(&derives(&["Clone", "FromParam"])),
+            None,
+            "Derives that say nothing about bodies are not classified"
+        );
+    }
 }
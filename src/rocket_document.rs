@@ -0,0 +1,943 @@
+use crate::example::ExampleOverrides;
+use crate::guard::{GuardKind, Guards};
+use crate::rocket_alias::{RocketAlias, RocketReexport};
+use crate::rocket_attribute::{derives_trait, ResponseAttribute};
+use crate::rocket_enum::RocketEnum;
+use crate::rocket_guard::RocketGuard;
+use crate::rocket_mount::RocketMount;
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_struct::RocketStruct;
+use crate::rocket_wrapper::RocketResponse;
+use crate::type_mapping::TypeMappings;
+use crate::type_ref::TypeRef;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// everything we found in a project. this is the intermediate blob that gets
+/// turned into documentation. types are collected first and then the routes
+/// are resolved against them, since a route can use a type that is declared
+/// after it (or in some other module)
+#[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketDocument {
+    pub routes: Vec<RocketRoute>,
+    pub structs: Vec<RocketStruct>,
+    pub enums: Vec<RocketEnum>,
+    pub aliases: Vec<RocketAlias>,
+    pub reexports: Vec<RocketReexport>,
+    pub mounts: Vec<RocketMount>,
+    // FromRequest impls, what each guard was seen reading and failing with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub request_guards: Vec<RocketGuard>,
+    // concrete copies of generic structs/enums for every way a route uses them
+    pub struct_instances: Vec<RocketStruct>,
+    pub enum_instances: Vec<RocketEnum>,
+    // not from the code, examples for types and fields given on the command
+    // line. every output that shows examples takes them from here
+    #[serde(default, skip_serializing_if = "ExampleOverrides::is_empty")]
+    pub examples: ExampleOverrides,
+    // also not from the code, how types without a schema of their own are
+    // documented. checked wherever a type is turned into a schema
+    #[serde(default, skip_serializing_if = "TypeMappings::is_empty")]
+    pub type_mappings: TypeMappings,
+    // what the project's request guards read from the request, from the
+    // config file
+    #[serde(default, skip_serializing_if = "Guards::is_empty")]
+    pub guards: Guards,
+}
+
+// aliases can refer to other aliases, but if they end up referring to
+// themselves we'd loop forever. same goes for responders wrapping responders
+const MAX_ALIAS_DEPTH: u32 = 16;
+const MAX_RESPONDER_DEPTH: u32 = 16;
+
+impl RocketDocument {
+    pub fn parse_file(ast: &syn::File) -> Self {
+        Self::parse_files(vec![(vec![], ast)])
+    }
+
+    /// each file comes with the module path it is at (`src/api/users.rs` is
+    /// `api::users`), items in inline `mod x { }` blocks get `x` added on top
+    pub fn parse_files<'a>(files: impl IntoIterator<Item = (Vec<String>, &'a syn::File)>) -> Self {
+        let mut document = RocketDocument::default();
+        files
+            .into_iter()
+            .for_each(|(module, ast)| document.traverse_items(&ast.items, &module));
+        document.resolve();
+        document
+    }
+
+    fn traverse_items(&mut self, items: &[syn::Item], module: &[String]) {
+        items
+            .iter()
+            .for_each(|item| self.traverse_item(item, module));
+    }
+
+    fn traverse_item(&mut self, item: &syn::Item, module: &[String]) {
+        match item {
+            // fn x { }
+            syn::Item::Fn(
+                function @ syn::ItemFn {
+                    block: box syn::Block { stmts, .. },
+                    ..
+                },
+            ) => {
+                if let Some(mut x) = RocketRoute::parse_fn(function) {
+                    x.module = module.to_vec();
+                    self.routes.push(x);
+                }
+                self.mounts
+                    .extend(RocketMount::parse_fn(function).into_iter().map(|mut x| {
+                        x.module = module.to_vec();
+                        x
+                    }));
+
+                stmts.iter().for_each(|item| {
+                    if let syn::Stmt::Item(item) = item {
+                        self.traverse_item(item, module)
+                    }
+                })
+            }
+
+            // mod x or mod x { }
+            syn::Item::Mod(syn::ItemMod {
+                ident,
+                content: Some((_, items)),
+                ..
+            }) => {
+                let mut module = module.to_vec();
+                module.push(crate::ast_formatting::format_idnt(ident));
+                self.traverse_items(items, &module)
+            }
+
+            // struct x { }
+            syn::Item::Struct(strct) => {
+                let mut x = RocketStruct::parse_struct(strct);
+                x.module = module.to_vec();
+                self.structs.push(x)
+            }
+
+            // enum x { }
+            syn::Item::Enum(enm) => {
+                let mut x = RocketEnum::parse_enum(enm);
+                x.module = module.to_vec();
+                self.enums.push(x)
+            }
+
+            // type x = y;
+            syn::Item::Type(ty) => {
+                let mut x = RocketAlias::parse_type(ty);
+                x.module = module.to_vec();
+                self.aliases.push(x)
+            }
+
+            // pub use x::y;
+            syn::Item::Use(item_use) => self.reexports.extend(RocketReexport::parse_use(item_use)),
+
+            // impl FromRequest for x { }
+            syn::Item::Impl(item_impl) => self
+                .request_guards
+                .extend(RocketGuard::parse_impl(item_impl)),
+            _ => (),
+        };
+    }
+
+    fn resolve(&mut self) {
+        self.qualify_types();
+        // instances first, the responses of a route can depend on them
+        self.instantiate_generics();
+
+        let mut routes = std::mem::take(&mut self.routes);
+        self.mounts.iter().for_each(|mount| {
+            mount
+                .mounted(&routes)
+                .into_iter()
+                .for_each(|idx| routes[idx].mounts.push(mount.base.to_owned()))
+        });
+        routes
+            .iter_mut()
+            .for_each(|route| route.resolve_types(self));
+        self.routes = routes;
+    }
+
+    /// `User` in `mod users` and `User` in `mod admin` are two types. a bare
+    /// name that could be either is taken to be the one in the module it's
+    /// used from, and gets that module's path so lookups find the right one
+    fn qualify_types(&mut self) {
+        let declared: BTreeSet<(Vec<String>, String)> = self
+            .structs
+            .iter()
+            .map(|strct| (strct.module.to_owned(), strct.ident.to_owned()))
+            .chain(
+                self.enums
+                    .iter()
+                    .map(|enm| (enm.module.to_owned(), enm.ident.to_owned())),
+            )
+            .collect();
+        let mut seen = BTreeSet::new();
+        let ambiguous: BTreeSet<&String> = declared
+            .iter()
+            .map(|(_, ident)| ident)
+            .filter(|ident| !seen.insert(*ident))
+            .collect();
+        if ambiguous.is_empty() {
+            return;
+        }
+
+        let qualify_str = |ty: &mut String, module: &[String]| {
+            let parsed = TypeRef::parse(ty);
+            let qualified = qualify(&parsed, module, &ambiguous, &declared);
+            if qualified != parsed {
+                *ty = qualified.to_string();
+            }
+        };
+        self.routes.iter_mut().for_each(|route| {
+            let module = route.module.to_owned();
+            route
+                .handler
+                .args
+                .iter_mut()
+                .for_each(|(_, ty)| qualify_str(ty, &module));
+            route.handler.ret_type =
+                qualify(&route.handler.ret_type, &module, &ambiguous, &declared);
+        });
+        self.structs.iter_mut().for_each(|strct| {
+            let module = strct.module.to_owned();
+            strct
+                .fields
+                .iter_mut()
+                .for_each(|(_, ty)| qualify_str(ty, &module));
+        });
+        self.enums.iter_mut().for_each(|enm| {
+            let module = enm.module.to_owned();
+            enm.variants.iter_mut().for_each(|variant| {
+                variant
+                    .fields
+                    .iter_mut()
+                    .for_each(|(_, ty)| qualify_str(ty, &module))
+            });
+        });
+    }
+
+    /// what a declared type goes by: its name, or its path when some other
+    /// module has a type with the same name (`admin::User`, `crate::User`)
+    pub fn type_name(&self, ident: &str, module: &[String]) -> String {
+        let other = self
+            .structs
+            .iter()
+            .map(|strct| (&strct.ident, &strct.module))
+            .chain(self.enums.iter().map(|enm| (&enm.ident, &enm.module)))
+            .any(|(other, other_module)| other == ident && other_module.as_slice() != module);
+        if !other {
+            ident.to_string()
+        } else if module.is_empty() {
+            format!("crate::{}", ident)
+        } else {
+            format!("{}::{}", module.join("::"), ident)
+        }
+    }
+
+    /// walk every type the routes use (and the types inside those) and make
+    /// an instance for each generic struct/enum that is used with arguments
+    fn instantiate_generics(&mut self) {
+        let mut pending: Vec<TypeRef> = self
+            .routes
+            .iter()
+            .flat_map(|route| route.used_types())
+            .map(|ty| self.expand_aliases(&ty))
+            .collect();
+
+        while let Some(ty) = pending.pop() {
+            pending.extend(ty.children().into_iter().cloned());
+
+            let (ident, args) = match (ty.ident(), ty.args()) {
+                (Some(ident), args) if !args.is_empty() => (ident, args),
+                _ => continue,
+            };
+            // instances are named the same way instantiate names them
+            let name = TypeRef::Path {
+                path: vec![ident.to_string()],
+                args: args.to_vec(),
+            }
+            .to_string();
+
+            if self.struct_instances.iter().any(|s| s.ident == name)
+                || self.enum_instances.iter().any(|e| e.ident == name)
+            {
+                continue;
+            }
+
+            if let Some(strct) = self
+                .structs
+                .iter()
+                .find(|s| s.ident == ident && !s.generics.is_empty())
+            {
+                let instance = strct.instantiate(args);
+                pending.extend(instance.field_types());
+                self.struct_instances.push(instance);
+            } else if let Some(enm) = self
+                .enums
+                .iter()
+                .find(|e| e.ident == ident && !e.generics.is_empty())
+            {
+                let instance = enm.instantiate(args);
+                pending.extend(instance.field_types());
+                self.enum_instances.push(instance);
+            }
+        }
+    }
+
+    /// every struct/enum reachable from the routes, plus the ones that are
+    /// bodies by their derives, as (module, ident). these are the types a
+    /// client needs a definition of. sorted so generated code is stable
+    pub fn body_types(&self) -> BTreeSet<(Vec<String>, String)> {
+        let path = |module: &[String], ident: &str| TypeRef::Path {
+            path: std::iter::once("crate")
+                .chain(module.iter().map(|segment| segment.as_str()))
+                .chain(std::iter::once(ident))
+                .map(|segment| segment.to_string())
+                .collect(),
+            args: vec![],
+        };
+
+        let mut pending: Vec<TypeRef> = self
+            .routes
+            .iter()
+            .flat_map(|route| {
+                route
+                    .params
+                    .iter()
+                    .filter(|param| param.kind != ParamKind::Guard)
+                    .map(|param| param.ty.to_owned())
+                    .chain(route.body.iter().filter_map(|body| body.schema.to_owned()))
+                    .chain(route.responses.iter().filter_map(|res| res.body.to_owned()))
+            })
+            .chain(
+                self.structs
+                    .iter()
+                    .filter(|strct| is_data(&strct.derives))
+                    .map(|strct| path(&strct.module, &strct.ident)),
+            )
+            .chain(
+                self.enums
+                    .iter()
+                    .filter(|enm| is_data(&enm.derives))
+                    .map(|enm| path(&enm.module, &enm.ident)),
+            )
+            .collect();
+
+        let mut used = BTreeSet::new();
+        while let Some(ty) = pending.pop() {
+            pending.extend(ty.children().into_iter().cloned());
+            let (module, ident) = match self.declared(&ty) {
+                Some(declared) => declared,
+                _ => continue,
+            };
+            if used.insert((module.to_owned(), ident.to_owned())) {
+                self.structs
+                    .iter()
+                    .filter(|strct| strct.module == module && strct.ident == ident)
+                    .for_each(|strct| pending.extend(strct.field_types()));
+                self.enums
+                    .iter()
+                    .filter(|enm| enm.module == module && enm.ident == ident)
+                    .for_each(|enm| pending.extend(enm.field_types()));
+            }
+        }
+        used
+    }
+
+    /// the module and ident of the struct or enum a type names, picked the
+    /// same way as find_struct/find_enum. generic types are their declaration
+    pub fn declared(&self, ty: &TypeRef) -> Option<(Vec<String>, String)> {
+        let declared: Vec<(Vec<String>, String)> = self
+            .structs
+            .iter()
+            .map(|strct| (strct.module.to_owned(), strct.ident.to_owned()))
+            .chain(
+                self.enums
+                    .iter()
+                    .map(|enm| (enm.module.to_owned(), enm.ident.to_owned())),
+            )
+            .collect();
+        find_declared(
+            declared
+                .iter()
+                .map(|item| (item.1.as_str(), item.0.as_slice(), item)),
+            ty,
+        )
+        .cloned()
+    }
+
+    /// the struct a type refers to. generic structs used with arguments are
+    /// looked up in the instances
+    pub fn find_struct(&self, ty: &TypeRef) -> Option<&RocketStruct> {
+        let ident = ty.ident()?;
+        if ty.args().is_empty() {
+            find_declared(
+                self.structs
+                    .iter()
+                    .map(|s| (s.ident.as_str(), s.module.as_slice(), s)),
+                ty,
+            )
+        } else {
+            let name = TypeRef::Path {
+                path: vec![ident.to_string()],
+                args: ty.args().to_vec(),
+            }
+            .to_string();
+            self.struct_instances.iter().find(|s| s.ident == name)
+        }
+    }
+
+    pub fn find_enum(&self, ty: &TypeRef) -> Option<&RocketEnum> {
+        let ident = ty.ident()?;
+        if ty.args().is_empty() {
+            find_declared(
+                self.enums
+                    .iter()
+                    .map(|e| (e.ident.as_str(), e.module.as_slice(), e)),
+                ty,
+            )
+        } else {
+            let name = TypeRef::Path {
+                path: vec![ident.to_string()],
+                args: ty.args().to_vec(),
+            }
+            .to_string();
+            self.enum_instances.iter().find(|e| e.ident == name)
+        }
+    }
+
+    /// the FromRequest impl of a guard, `Option<User>` and `Result<User, _>`
+    /// are `User`'s
+    pub fn request_guard(&self, ty: &TypeRef) -> Option<&RocketGuard> {
+        let ty = match (ty.ident(), ty.args()) {
+            (Some("Option"), [inner]) | (Some("Result"), [inner, ..]) => inner,
+            _ => ty,
+        };
+        let ident = ty.ident()?;
+        self.request_guards
+            .iter()
+            .find(|guard| guard.ident == ident)
+    }
+
+    /// what a guard reads from the request. the config's classification
+    /// wins, otherwise it's what its FromRequest impl was seen reading
+    pub fn guard_inputs(&self, ty: &TypeRef) -> Vec<GuardKind> {
+        match self.guards.find(ty) {
+            Some(kind) => vec![kind.to_owned()],
+            None => self
+                .request_guard(ty)
+                .map(|guard| guard.inputs())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// every response a route returning `ty` can produce. on top of what the
+    /// wrapper types give us, responder structs and enums are walked so each
+    /// variant's #[response] shows up as its own status
+    pub fn responses(&self, ty: &TypeRef) -> Vec<RocketResponse> {
+        self.responses_to_depth(&self.expand_aliases(ty), 0)
+    }
+
+    fn responses_to_depth(&self, ty: &TypeRef, depth: u32) -> Vec<RocketResponse> {
+        crate::rocket_wrapper::responses(ty, &|ty| {
+            if depth < MAX_RESPONDER_DEPTH {
+                self.responder_responses(ty, depth + 1)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn responder_responses(&self, ty: &TypeRef, depth: u32) -> Option<Vec<RocketResponse>> {
+        // #[derive(Responder)] uses the first field as the actual responder
+        // and the #[response] attribute overrides its status/content type
+        let respond = |response: Option<&ResponseAttribute>, fields: &[(String, String)]| {
+            let inner = match fields.first() {
+                Some((_, inner)) => {
+                    self.responses_to_depth(&self.expand_aliases(&TypeRef::parse(inner)), depth)
+                }
+                None => vec![RocketResponse {
+                    status: Some(200),
+                    content_type: None,
+                    body: None,
+                }],
+            };
+            inner
+                .into_iter()
+                .map(|res| RocketResponse {
+                    status: response.and_then(|attr| attr.status).or(res.status),
+                    content_type: response
+                        .and_then(|attr| attr.content_type.as_deref())
+                        .map(crate::rocket_wrapper::media_type)
+                        .or(res.content_type),
+                    body: res.body,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if let Some(strct) = self
+            .find_struct(ty)
+            .filter(|s| derives_trait(&s.derives, "Responder"))
+        {
+            return Some(respond(strct.response.as_ref(), &strct.fields));
+        }
+
+        self.find_enum(ty)
+            .filter(|e| derives_trait(&e.derives, "Responder"))
+            .map(|enm| {
+                enm.variants
+                    .iter()
+                    .flat_map(|variant| respond(variant.response.as_ref(), &variant.fields))
+                    .collect()
+            })
+    }
+
+    /// replace every alias in the type with what it stands for, filling in
+    /// the alias' generics with the arguments it was used with. re-exported
+    /// names are swapped for the path of the type they re-export
+    pub fn expand_aliases(&self, ty: &TypeRef) -> TypeRef {
+        self.expand_aliases_to_depth(ty, 0)
+    }
+
+    fn expand_aliases_to_depth(&self, ty: &TypeRef, depth: u32) -> TypeRef {
+        match ty {
+            TypeRef::Path { path, args } => {
+                let args: Vec<TypeRef> = args
+                    .iter()
+                    .map(|arg| self.expand_aliases_to_depth(arg, depth))
+                    .collect();
+                let ident = ty.ident();
+
+                if depth < MAX_ALIAS_DEPTH {
+                    if let Some(alias) = self.aliases.iter().find(|alias| alias.matches(path)) {
+                        // params left out take their default
+                        let mut args = args.into_iter();
+                        let params: HashMap<String, TypeRef> = alias
+                            .generics
+                            .iter()
+                            .filter_map(|generic| {
+                                args.next()
+                                    .or_else(|| alias.defaults.get(generic).cloned())
+                                    .map(|arg| (generic.to_owned(), arg))
+                            })
+                            .collect();
+                        return self
+                            .expand_aliases_to_depth(&alias.ty.substitute(&params), depth + 1);
+                    }
+                }
+
+                if let Some(reexport) = self.reexports.iter().find(|reexport| {
+                    Some(reexport.ident.as_str()) == ident
+                        && reexport.path.last() != Some(&reexport.ident)
+                }) {
+                    return TypeRef::Path {
+                        path: reexport.path.to_owned(),
+                        args,
+                    };
+                }
+
+                TypeRef::Path {
+                    path: path.to_owned(),
+                    args,
+                }
+            }
+            TypeRef::Tuple { elems } => TypeRef::Tuple {
+                elems: elems
+                    .iter()
+                    .map(|elem| self.expand_aliases_to_depth(elem, depth))
+                    .collect(),
+            },
+            TypeRef::Array { elem } => TypeRef::Array {
+                elem: Box::new(self.expand_aliases_to_depth(elem, depth)),
+            },
+            TypeRef::Reference { elem } => TypeRef::Reference {
+                elem: Box::new(self.expand_aliases_to_depth(elem, depth)),
+            },
+            TypeRef::Other { .. } => ty.to_owned(),
+        }
+    }
+}
+
+/// a bare name in the module it's used from gets the path of the type
+/// declared there, when there's more than one type by that name
+fn qualify(
+    ty: &TypeRef,
+    module: &[String],
+    ambiguous: &BTreeSet<&String>,
+    declared: &BTreeSet<(Vec<String>, String)>,
+) -> TypeRef {
+    let qualify = |ty: &TypeRef| qualify(ty, module, ambiguous, declared);
+    match ty {
+        TypeRef::Path { path, args } => TypeRef::Path {
+            path: match path.as_slice() {
+                [ident]
+                    if ambiguous.contains(ident)
+                        && declared.contains(&(module.to_vec(), ident.to_owned())) =>
+                {
+                    std::iter::once("crate".to_string())
+                        .chain(module.iter().cloned())
+                        .chain(std::iter::once(ident.to_owned()))
+                        .collect()
+                }
+                _ => path.to_owned(),
+            },
+            args: args.iter().map(qualify).collect(),
+        },
+        TypeRef::Tuple { elems } => TypeRef::Tuple {
+            elems: elems.iter().map(qualify).collect(),
+        },
+        TypeRef::Array { elem } => TypeRef::Array {
+            elem: Box::new(qualify(elem)),
+        },
+        TypeRef::Reference { elem } => TypeRef::Reference {
+            elem: Box::new(qualify(elem)),
+        },
+        TypeRef::Other { .. } => ty.to_owned(),
+    }
+}
+
+/// a declared type by name. with a path in front, the one in that module
+/// wins over others with the same name
+fn find_declared<'a, T>(
+    mut items: impl Iterator<Item = (&'a str, &'a [String], &'a T)>,
+    ty: &TypeRef,
+) -> Option<&'a T> {
+    let (ident, prefix) = match ty {
+        TypeRef::Path { path, .. } => path.split_last()?,
+        _ => return None,
+    };
+    let prefix = match prefix.split_first() {
+        Some((first, rest)) if first == "crate" => rest,
+        _ => prefix,
+    };
+    let mut first = None;
+    items
+        .find_map(|(name, module, item)| {
+            if name != ident {
+                return None;
+            }
+            first.get_or_insert(item);
+            if module == prefix {
+                Some(item)
+            } else {
+                None
+            }
+        })
+        .or(first)
+}
+
+/// types that go over the wire as data. a Responder on its own is how a
+/// response is built, not what's in it
+fn is_data(derives: &[String]) -> bool {
+    ["Serialize", "Deserialize", "FromForm"]
+        .iter()
+        .any(|name| derives_trait(derives, name))
+}
+
+/// items keyed by `crate::module::path`, keeping declaration order inside a module
+pub fn by_module<T>(items: &[T], module: impl Fn(&T) -> &Vec<String>) -> BTreeMap<String, Vec<&T>> {
+    let mut modules: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+    items.iter().for_each(|item| {
+        let path = std::iter::once("crate")
+            .chain(module(item).iter().map(|segment| segment.as_str()))
+            .collect::<Vec<_>>()
+            .join("::");
+        modules.entry(path).or_default().push(item);
+    });
+    modules
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(content: &str) -> RocketDocument {
+        RocketDocument::parse_file(&syn::parse_str(content).unwrap())
+    }
+
+    #[test]
+    fn collects_items_from_nested_modules() {
+        let document = parse(
+            "
+            mod api {
+                #[get(\"/\")]
+                fn index() -> Page {}
+
+                struct Page;
+            }
+
+            enum Role { Admin }
+
+            type Id = u64;
+            ",
+        );
+        assert_eq!(document.routes.len(), 1);
+        assert_eq!(document.routes[0].module, vec!["api".to_string()]);
+        assert_eq!(document.structs.len(), 1);
+        assert_eq!(document.structs[0].module, vec!["api".to_string()]);
+        assert_eq!(document.enums.len(), 1);
+        assert_eq!(document.aliases.len(), 1);
+    }
+
+    #[test]
+    fn mounts_routes_under_their_base() {
+        let document = parse(
+            "
+            #[get(\"/<id>\")]
+            fn get(id: u64) {}
+
+            #[get(\"/\")]
+            fn index() {}
+
+            fn main() {
+                rocket::ignite()
+                    .mount(\"/users\", routes![get])
+                    .mount(\"/v1/users\", routes![get]);
+            }
+            ",
+        );
+        assert_eq!(
+            document.routes[0]
+                .full_uris()
+                .iter()
+                .map(|uri| uri.templated_path())
+                .collect::<Vec<_>>(),
+            vec!["/users/{id}", "/v1/users/{id}"],
+            "A route has a full path for every mount"
+        );
+        assert_eq!(
+            document.routes[1]
+                .full_uris()
+                .iter()
+                .map(|uri| uri.templated_path())
+                .collect::<Vec<_>>(),
+            vec!["/"],
+            "Routes that aren't mounted stay where they are"
+        );
+    }
+
+    #[test]
+    fn mounts_same_named_handlers_by_module() {
+        let document = parse(
+            "
+            mod users {
+                #[get(\"/\")]
+                fn index() {}
+            }
+
+            mod posts {
+                #[get(\"/\")]
+                fn index() {}
+            }
+
+            mod api {
+                fn mount(rocket: Rocket) -> Rocket {
+                    rocket.mount(\"/api\", routes![super::posts::index])
+                }
+            }
+
+            fn main() {
+                rocket::ignite()
+                    .mount(\"/users\", routes![users::index])
+                    .mount(\"/posts\", routes![crate::posts::index]);
+            }
+            ",
+        );
+        assert_eq!(document.routes[0].mounts, vec!["/users"]);
+        assert_eq!(
+            document.routes[1].mounts,
+            vec!["/api", "/posts"],
+            "Paths in routes![] are resolved from the mount's module"
+        );
+    }
+
+    #[test]
+    fn expands_generic_aliases() {
+        let document = parse(
+            "
+            type ApiResult<T> = Result<Json<T>, ApiError>;
+            type Users = Vec<User>;
+            ",
+        );
+        assert_eq!(
+            document
+                .expand_aliases(&TypeRef::parse("ApiResult<Users>"))
+                .to_string(),
+            "Result<Json<Vec<User>>, ApiError>",
+            "Expands aliases, including ones used as arguments to other aliases"
+        );
+    }
+
+    #[test]
+    fn fills_in_default_alias_params() {
+        let document = parse(
+            "
+            type Result<T, E = ApiError> = std::result::Result<T, E>;
+            ",
+        );
+        assert_eq!(
+            document
+                .expand_aliases(&TypeRef::parse("Result<Json<User>>"))
+                .to_string(),
+            "std::result::Result<Json<User>, ApiError>",
+            "Defaults fill in left out params and std's Result isn't the alias"
+        );
+    }
+
+    #[test]
+    fn expands_renamed_reexports() {
+        let document = parse("pub use crate::models::User as Account;");
+        assert_eq!(
+            document
+                .expand_aliases(&TypeRef::parse("Json<Account>"))
+                .to_string(),
+            "Json<crate::models::User>",
+            "Swaps re-exported names for the original path"
+        );
+    }
+
+    #[test]
+    fn instantiates_generics_used_by_routes() {
+        let document = parse(
+            "
+            struct Page<T> { items: Vec<T>, total: u64 }
+            struct Wrapper<T> { inner: T }
+            enum Reply<T> { Ok(T), Missing }
+            struct User { id: u64 }
+
+            type Paged<T> = Json<Wrapper<Page<T>>>;
+
+            #[get(\"/users\")]
+            fn users() -> Paged<User> {}
+
+            #[get(\"/me\")]
+            fn me() -> Reply<User> {}
+            ",
+        );
+
+        let mut structs: Vec<&str> = document
+            .struct_instances
+            .iter()
+            .map(|s| s.ident.as_str())
+            .collect();
+        structs.sort();
+        assert_eq!(
+            structs,
+            vec!["Page<User>", "Wrapper<Page<User>>"],
+            "Instantiates generics nested in other generics and aliases"
+        );
+        assert_eq!(
+            document
+                .enum_instances
+                .iter()
+                .map(|e| e.ident.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Reply<User>"]
+        );
+    }
+
+    #[test]
+    fn walks_responder_enums_and_structs() {
+        let document = parse(
+            "
+            #[derive(Responder)]
+            enum LoginResponse {
+                #[response(status = 200, content_type = \"json\")]
+                Success(Json<Session>),
+                #[response(status = 401)]
+                Failure(String),
+                Later(Redirect),
+            }
+
+            #[derive(Responder)]
+            #[response(status = 418)]
+            struct Teapot { body: String, header: ContentType }
+
+            type LoginResult = Result<LoginResponse, Option<Teapot>>;
+            ",
+        );
+        let response =
+            |status: Option<u32>, content_type: Option<&str>, body: Option<&str>| RocketResponse {
+                status,
+                content_type: content_type.map(|c| c.to_string()),
+                body: body.map(TypeRef::parse),
+            };
+
+        assert_eq!(
+            document.responses(&TypeRef::parse("LoginResult")),
+            vec![
+                response(Some(200), Some("application/json"), Some("Session")),
+                response(Some(401), Some("text/plain"), Some("String")),
+                response(Some(303), None, None),
+                response(Some(418), Some("text/plain"), Some("String")),
+                response(Some(404), None, None),
+            ],
+            "Lists every status from the variants and wrappers"
+        );
+    }
+
+    #[test]
+    fn attaches_guard_failures_and_inputs_to_routes() {
+        let mut document = parse(
+            "
+            #[get(\"/me\")]
+            fn me(user: User) -> Json<Profile> {}
+
+            #[get(\"/feed\")]
+            fn feed(user: Option<User>) -> Json<Feed> {}
+
+            impl<'r> FromRequest<'r> for User {
+                async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+                    match req.headers().get_one(\"Authorization\") {
+                        Some(_) => Outcome::Success(User),
+                        None => Outcome::Failure((Status::Unauthorized, ())),
+                    }
+                }
+            }
+            ",
+        );
+
+        assert_eq!(
+            document.routes[0].responses.last(),
+            Some(&RocketResponse {
+                status: Some(401),
+                content_type: None,
+                body: None,
+            })
+        );
+        assert!(
+            document.routes[1]
+                .responses
+                .iter()
+                .all(|res| res.status != Some(401)),
+            "Optional guards don't fail the request"
+        );
+        assert_eq!(
+            document.guard_inputs(&TypeRef::parse("Option<User>")),
+            vec![GuardKind::Auth {
+                scheme: "bearer".to_string()
+            }]
+        );
+
+        document
+            .guards
+            .0
+            .insert("User".to_string(), GuardKind::Ignore);
+        assert_eq!(
+            document.guard_inputs(&TypeRef::parse("User")),
+            vec![GuardKind::Ignore],
+            "The config wins over what the impl reads"
+        );
+    }
+
+    #[test]
+    fn stops_expanding_recursive_aliases() {
+        let document = parse("type Loop = Vec<Loop>;");
+        assert_eq!(
+            document.expand_aliases(&TypeRef::parse("Loop")).to_string(),
+            format!("{}Loop{}", "Vec<".repeat(16), ">".repeat(16)),
+            "Gives up after a fixed depth"
+        );
+    }
+}
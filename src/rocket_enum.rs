@@ -1,91 +1,166 @@
-use crate::rocket_attribute::{ResponseAttribute, RocketAttribute};
+use crate::rocket_attribute::{BodyDirection, ResponseAttribute, RocketAttribute};
+use crate::rocket_generic::RocketGeneric;
+use crate::rocket_serde::SerdeAttributes;
+use crate::type_ref::TypeRef;
+use std::collections::HashMap;
 
-/// we want enums that derive Responder because those are ones that can be used
-/// in responses. the variants will have response attributes with status and
-/// content_type information
-
-#[derive(Debug, PartialEq, serde::Serialize)]
+/// enums that derive Responder can be used in responses. the variants will have
+/// response attributes with status and content_type information. other enums
+/// show up as request/response bodies (serde) or path parameters (FromParam),
+/// so we keep all of them along with their derives
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RocketEnum {
-    ident: String,
-    variants: Vec<RocketVariant>,
+    pub ident: String,
+    pub generics: Vec<RocketGeneric>,
+    pub variants: Vec<RocketVariant>,
+    pub derives: Vec<String>,
+    pub direction: Option<BodyDirection>,
+    pub docs: Option<String>,
+    pub serde: SerdeAttributes,
+    // filled in by the document, the parser doesn't know where it is
+    pub module: Vec<String>,
+    // the line it starts on, kept out of the json like RocketRoute::line
+    #[serde(skip)]
+    pub line: usize,
 }
 
-#[derive(Debug, PartialEq, serde::Serialize)]
-struct RocketVariant {
-    ident: String,
-    response: ResponseAttribute,
-    fields: Vec<(String, String)>,
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketVariant {
+    pub ident: String,
+    // like RocketEnum::line, for pointing at the variant itself
+    #[serde(skip)]
+    pub line: usize,
+    pub response: Option<ResponseAttribute>,
+    pub fields: Vec<(String, String)>,
+    pub serde: SerdeAttributes,
 }
 
 impl RocketEnum {
-    pub fn parse_enum(enm: &syn::ItemEnum) -> Option<Self> {
+    pub fn parse_enum(enm: &syn::ItemEnum) -> Self {
         let attrs = RocketAttribute::from_enum(enm);
+        let derives = RocketAttribute::derives(&attrs);
 
-        if let Some(_) = attrs.into_iter().find_map(|attr| {
-            if let RocketAttribute::DeriveResponder = attr {
-                Some(())
-            } else {
-                None
-            }
-        }) {
-            Some(RocketEnum {
-                ident: crate::ast_formatting::format_idnt(&enm.ident),
-                variants: enm
-                    .variants
-                    .pairs()
-                    .filter_map(|variant| {
-                        let variant = variant.value();
-                        let attrs = RocketAttribute::from_variant(variant);
+        RocketEnum {
+            ident: crate::ast_formatting::format_idnt(&enm.ident),
+            generics: RocketGeneric::parse_generics(&enm.generics),
+            docs: crate::ast_formatting::format_docs(&enm.attrs),
+            serde: SerdeAttributes::parse(&enm.attrs, &syn::Fields::Unit),
+            module: vec![],
+            line: crate::ast_formatting::format_line(enm),
+            variants: enm
+                .variants
+                .pairs()
+                .map(|variant| {
+                    let variant = variant.value();
+                    let attrs = RocketAttribute::from_variant(variant);
 
-                        if let Some(res_attr) = attrs.into_iter().find_map(|attr| {
+                    let fields = match variant.fields.to_owned() {
+                        syn::Fields::Named(fields) => fields
+                            .named
+                            .iter()
+                            .map(|field| {
+                                (
+                                    crate::ast_formatting::format_idnt(
+                                        &field.ident.to_owned().unwrap(),
+                                    ),
+                                    crate::ast_formatting::format_type(&field.ty),
+                                )
+                            })
+                            .collect(),
+                        syn::Fields::Unnamed(fields) => fields
+                            .unnamed
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, field)| {
+                                (
+                                    idx.to_string(),
+                                    crate::ast_formatting::format_type(&field.ty),
+                                )
+                            })
+                            .collect(),
+                        syn::Fields::Unit => vec![],
+                    };
+
+                    RocketVariant {
+                        ident: crate::ast_formatting::format_idnt(&variant.ident),
+                        line: crate::ast_formatting::format_line(variant),
+                        response: attrs.into_iter().find_map(|attr| {
                             if let RocketAttribute::Response(res_attr) = attr {
                                 Some(res_attr)
                             } else {
                                 None
                             }
-                        }) {
-                            let fields = match variant.fields.to_owned() {
-                                syn::Fields::Named(fields) => fields
-                                    .named
-                                    .iter()
-                                    .map(|field| {
-                                        (
-                                            crate::ast_formatting::format_idnt(
-                                                &field.ident.to_owned().unwrap(),
-                                            ),
-                                            crate::ast_formatting::format_type(&field.ty),
-                                        )
-                                    })
-                                    .collect(),
-                                syn::Fields::Unnamed(fields) => fields
-                                    .unnamed
-                                    .iter()
-                                    .enumerate()
-                                    .map(|(idx, field)| {
-                                        (
-                                            idx.to_string(),
-                                            crate::ast_formatting::format_type(&field.ty),
-                                        )
-                                    })
-                                    .collect(),
-                                syn::Fields::Unit => vec![],
-                            };
+                        }),
+                        serde: SerdeAttributes::parse(&variant.attrs, &variant.fields),
+                        fields,
+                    }
+                })
+                .collect(),
+            direction: BodyDirection::from_derives(&derives),
+            derives,
+        }
+    }
 
-                            Some(RocketVariant {
-                                ident: crate::ast_formatting::format_idnt(&variant.ident),
-                                response: res_attr,
-                                fields,
-                            })
-                        } else {
-                            None
-                        }
-                    })
-                    .collect(),
-            })
-        } else {
-            None
+    /// the name serde gives the variant, its own rename or the enum's rename_all
+    pub fn variant_name(&self, variant: &RocketVariant) -> String {
+        match &variant.serde.rename {
+            Some(rename) => rename.to_owned(),
+            None => crate::rocket_serde::rename_variant(
+                self.serde.rename_all.as_deref(),
+                &variant.ident,
+            ),
+        }
+    }
+
+    /// same as RocketStruct::instantiate but for every variant's fields
+    pub fn instantiate(&self, args: &[TypeRef]) -> Self {
+        let params: HashMap<String, TypeRef> = self
+            .generics
+            .iter()
+            .map(|generic| generic.ident.to_owned())
+            .zip(args.iter().cloned())
+            .collect();
+
+        RocketEnum {
+            ident: TypeRef::Path {
+                path: vec![self.ident.to_owned()],
+                args: args.to_vec(),
+            }
+            .to_string(),
+            generics: vec![],
+            variants: self
+                .variants
+                .iter()
+                .map(|variant| RocketVariant {
+                    fields: variant
+                        .fields
+                        .iter()
+                        .map(|(name, ty)| {
+                            (
+                                name.to_owned(),
+                                TypeRef::parse(ty).substitute(&params).format(),
+                            )
+                        })
+                        .collect(),
+                    ..variant.clone()
+                })
+                .collect(),
+            derives: self.derives.clone(),
+            direction: self.direction,
+            docs: self.docs.clone(),
+            serde: self.serde.clone(),
+            module: self.module.clone(),
+            line: self.line,
         }
     }
+
+    pub fn field_types(&self) -> Vec<TypeRef> {
+        self.variants
+            .iter()
+            .flat_map(|variant| variant.fields.iter())
+            .map(|(_, ty)| TypeRef::parse(ty))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -112,44 +187,132 @@ mod test {
         );
         assert_eq!(
             x,
-            Some(RocketEnum {
+            RocketEnum {
                 ident: "MyResponseEnum".to_string(),
+                generics: vec![],
                 variants: vec![
                     RocketVariant {
+                        serde: SerdeAttributes::default(),
                         ident: "GoodStuff".to_string(),
-                        response: ResponseAttribute {
-                            status: 200,
+                        line: 4,
+                        response: Some(ResponseAttribute {
+                            status: Some(200),
                             content_type: Some("application/json".to_string())
-                        },
+                        }),
                         fields: vec![
                             (0.to_string(), "i32".to_string()),
                             (1.to_string(), "String".to_string())
                         ]
                     },
                     RocketVariant {
+                        serde: SerdeAttributes::default(),
                         ident: "BadRequest".to_string(),
-                        response: ResponseAttribute {
-                            status: 400,
+                        line: 6,
+                        response: Some(ResponseAttribute {
+                            status: Some(400),
                             content_type: None,
-                        },
+                        }),
                         fields: vec![
                             (0.to_string(), "i32".to_string()),
                             (1.to_string(), "i32".to_string())
                         ]
                     },
                     RocketVariant {
+                        serde: SerdeAttributes::default(),
                         ident: "InternalError".to_string(),
-                        response: ResponseAttribute {
-                            status: 500,
+                        line: 8,
+                        response: Some(ResponseAttribute {
+                            status: Some(500),
                             content_type: Some("text".to_string())
-                        },
+                        }),
                         fields: vec![
                             ("body".to_string(), "String".to_string()),
                             ("header1".to_string(), "i32".to_string())
                         ]
                     }
                 ],
-            })
+                derives: vec!["Responder".to_string()],
+                direction: Some(BodyDirection::Response),
+                docs: None,
+                serde: SerdeAttributes::default(),
+                module: vec![],
+                line: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_enums_without_responder() {
+        let x = RocketEnum::parse_enum(
+            &syn::parse_str(
+                "
+            #[derive(Serialize, Deserialize, FromParam)]
+            enum Role {
+                Admin,
+                Member { since: u32 },
+            }
+            ",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            x,
+            RocketEnum {
+                ident: "Role".to_string(),
+                generics: vec![],
+                variants: vec![
+                    RocketVariant {
+                        serde: SerdeAttributes::default(),
+                        ident: "Admin".to_string(),
+                        line: 4,
+                        response: None,
+                        fields: vec![],
+                    },
+                    RocketVariant {
+                        serde: SerdeAttributes::default(),
+                        ident: "Member".to_string(),
+                        line: 5,
+                        response: None,
+                        fields: vec![("since".to_string(), "u32".to_string())],
+                    },
+                ],
+                derives: vec![
+                    "Serialize".to_string(),
+                    "Deserialize".to_string(),
+                    "FromParam".to_string()
+                ],
+                direction: Some(BodyDirection::Both),
+                docs: None,
+                serde: SerdeAttributes::default(),
+                module: vec![],
+                line: 2,
+            },
+            "Keeps enums that don't derive Responder along with their derives"
+        );
+    }
+
+    #[test]
+    fn instantiates_generic_enums() {
+        let x = RocketEnum::parse_enum(
+            &syn::parse_str(
+                "
+            #[derive(Responder)]
+            enum Reply<T> {
+                #[response(status = 200)]
+                Ok(Json<T>),
+                #[response(status = 404)]
+                Missing(String),
+            }
+            ",
+            )
+            .unwrap(),
+        )
+        .instantiate(&[TypeRef::parse("User")]);
+        assert_eq!(x.ident, "Reply<User>");
+        assert_eq!(
+            x.field_types(),
+            vec![TypeRef::parse("Json<User>"), TypeRef::parse("String")],
+            "Fills in the type parameters of every variant"
         );
     }
 }
@@ -0,0 +1,79 @@
+/// a type parameter on a struct or enum, like the `T` in `struct Page<T>`.
+/// the bounds are gathered from both `<T: Bound>` and the where clause
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketGeneric {
+    pub ident: String,
+    pub bounds: Vec<String>,
+}
+
+impl RocketGeneric {
+    pub fn parse_generics(generics: &syn::Generics) -> Vec<Self> {
+        generics
+            .type_params()
+            .map(|param| {
+                let where_bounds = generics
+                    .where_clause
+                    .iter()
+                    .flat_map(|clause| clause.predicates.iter())
+                    .filter_map(|predicate| {
+                        if let syn::WherePredicate::Type(predicate) = predicate {
+                            if crate::ast_formatting::format_type(&predicate.bounded_ty)
+                                == crate::ast_formatting::format_idnt(&param.ident)
+                            {
+                                return Some(predicate.bounds.iter());
+                            }
+                        }
+                        None
+                    })
+                    .flatten();
+
+                RocketGeneric {
+                    ident: crate::ast_formatting::format_idnt(&param.ident),
+                    bounds: param
+                        .bounds
+                        .iter()
+                        .chain(where_bounds)
+                        .map(crate::ast_formatting::format_bound)
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_bounds_from_params_and_where_clause() {
+        let item: syn::ItemStruct = syn::parse_str(
+            "
+            struct Page<'a, T: Serialize, U>
+            where
+                T: Clone,
+                U: Default + Debug,
+            {
+                items: &'a [T],
+                extra: U,
+            }
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            RocketGeneric::parse_generics(&item.generics),
+            vec![
+                RocketGeneric {
+                    ident: "T".to_string(),
+                    bounds: vec!["Serialize".to_string(), "Clone".to_string()],
+                },
+                RocketGeneric {
+                    ident: "U".to_string(),
+                    bounds: vec!["Default".to_string(), "Debug".to_string()],
+                },
+            ],
+            "Collects type parameters and their bounds, skipping lifetimes"
+        );
+    }
+}
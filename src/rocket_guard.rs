@@ -0,0 +1,401 @@
+use crate::guard::GuardKind;
+use syn::visit::Visit;
+
+/// `impl FromRequest for User` is where a request guard reads the request.
+/// the headers and cookies it looks at and the statuses it fails with are
+/// picked out of `from_request`, so routes taking a `User` can be
+/// documented with them
+#[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketGuard {
+    pub ident: String,
+    pub headers: Vec<String>,
+    pub cookies: Vec<String>,
+    // Outcome::Failure / Outcome::Error, into_outcome and or_error
+    pub statuses: Vec<u32>,
+    // the scheme it expects in Authorization, when it checks for one
+    pub auth_scheme: Option<String>,
+}
+
+/// what a local holds, for `let headers = req.headers();`
+#[derive(Clone, Copy, PartialEq)]
+enum Source {
+    Headers,
+    Cookies,
+}
+
+#[derive(Default)]
+struct GuardVisitor {
+    guard: RocketGuard,
+    locals: Vec<(String, Source)>,
+}
+
+impl GuardVisitor {
+    /// `req.headers()`, `request.cookies()` or a local bound to one of them
+    fn source(&self, expr: &syn::Expr) -> Option<Source> {
+        match expr {
+            syn::Expr::MethodCall(call) if call.method == "headers" => Some(Source::Headers),
+            syn::Expr::MethodCall(call) if call.method == "cookies" => Some(Source::Cookies),
+            syn::Expr::Path(path) => path.path.get_ident().and_then(|ident| {
+                self.locals
+                    .iter()
+                    .rev()
+                    .find(|(local, _)| ident == local)
+                    .map(|(_, source)| *source)
+            }),
+            syn::Expr::Reference(reference) => self.source(&reference.expr),
+            syn::Expr::Paren(paren) => self.source(&paren.expr),
+            _ => None,
+        }
+    }
+
+    fn add_status(&mut self, expr: &syn::Expr) {
+        // the status comes first in a (Status, Error) pair
+        let expr = match expr {
+            syn::Expr::Tuple(tuple) => match tuple.elems.first() {
+                Some(expr) => expr,
+                None => return,
+            },
+            expr => expr,
+        };
+        if let Some(status) = status_code(expr) {
+            if !self.guard.statuses.contains(&status) {
+                self.guard.statuses.push(status);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for GuardVisitor {
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        syn::visit::visit_local(self, local);
+
+        if let (syn::Pat::Ident(pat), Some((_, init))) = (&local.pat, &local.init) {
+            if let Some(source) = self.source(init) {
+                self.locals.push((pat.ident.to_string(), source));
+            }
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        syn::visit::visit_expr_method_call(self, call);
+
+        let method = call.method.to_string();
+        let name = match call.args.first() {
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(name),
+                ..
+            })) => Some(name.value()),
+            _ => None,
+        };
+        match (self.source(&call.receiver), method.as_str(), name) {
+            (Some(Source::Headers), "get" | "get_one" | "contains", Some(name)) => {
+                if !self.guard.headers.contains(&name) {
+                    self.guard.headers.push(name)
+                }
+            }
+            (Some(Source::Cookies), "get" | "get_private" | "get_pending", Some(name)) => {
+                if !self.guard.cookies.contains(&name) {
+                    self.guard.cookies.push(name)
+                }
+            }
+            (_, "into_outcome" | "or_error", _) => {
+                if let Some(arg) = call.args.first() {
+                    self.add_status(arg)
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        syn::visit::visit_expr_call(self, call);
+
+        // Outcome::Failure in rocket 0.4, Outcome::Error in 0.5
+        if let syn::Expr::Path(path) = &*call.func {
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            let failure = match segments.as_slice() {
+                [.., outcome, last] => {
+                    outcome == "Outcome" && (last == "Failure" || last == "Error")
+                }
+                [last] => last == "Failure",
+                [] => false,
+            };
+            if let (true, Some(arg)) = (failure, call.args.first()) {
+                self.add_status(arg)
+            }
+        }
+    }
+
+    fn visit_lit_str(&mut self, lit: &'ast syn::LitStr) {
+        // `strip_prefix("Bearer ")` and the like
+        let value = lit.value();
+        let scheme = value.trim();
+        if self.guard.auth_scheme.is_none()
+            && (scheme.eq_ignore_ascii_case("bearer") || scheme.eq_ignore_ascii_case("basic"))
+        {
+            self.guard.auth_scheme = Some(scheme.to_lowercase());
+        }
+    }
+}
+
+/// `Status::Unauthorized`, `Status::new(401)` or `Status::from_code(401)`
+fn status_code(expr: &syn::Expr) -> Option<u32> {
+    match expr {
+        syn::Expr::Path(path) => {
+            let segments = &path.path.segments;
+            let name = segments.last()?.ident.to_string();
+            if segments.len() < 2 || segments[segments.len() - 2].ident != "Status" {
+                return None;
+            }
+            // the constants are named after the reason phrase
+            let normalize = |name: &str| {
+                name.chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .collect::<String>()
+                    .to_lowercase()
+            };
+            (100..600).find(|status| {
+                normalize(crate::rocket_wrapper::status_reason(*status)) == normalize(&name)
+            })
+        }
+        syn::Expr::Call(call) => match (&*call.func, call.args.first()) {
+            (
+                syn::Expr::Path(path),
+                Some(syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(code),
+                    ..
+                })),
+            ) if path.path.segments.len() >= 2
+                && path.path.segments[path.path.segments.len() - 2].ident == "Status" =>
+            {
+                code.base10_parse().ok()
+            }
+            _ => None,
+        },
+        syn::Expr::Reference(reference) => status_code(&reference.expr),
+        _ => None,
+    }
+}
+
+impl RocketGuard {
+    /// only impls of FromRequest, the guard is the type it's implemented for
+    pub fn parse_impl(item: &syn::ItemImpl) -> Option<Self> {
+        let (_, path, _) = item.trait_.as_ref()?;
+        if path.segments.last()?.ident != "FromRequest" {
+            return None;
+        }
+        let ident = match &*item.self_ty {
+            syn::Type::Path(ty) => ty.path.segments.last()?.ident.to_string(),
+            _ => return None,
+        };
+
+        let mut visitor = GuardVisitor::default();
+        item.items.iter().for_each(|item| {
+            if let syn::ImplItem::Method(method) = item {
+                if method.sig.ident == "from_request" {
+                    visitor.visit_impl_item_method(method)
+                }
+            }
+        });
+        Some(RocketGuard {
+            ident,
+            ..visitor.guard
+        })
+    }
+
+    /// turning a request away with 401 or 403, or checking for a scheme in
+    /// Authorization, is what auth guards do
+    pub fn is_auth(&self) -> bool {
+        self.auth_scheme.is_some()
+            || self
+                .statuses
+                .iter()
+                .any(|status| *status == 401 || *status == 403)
+    }
+
+    /// what the guard reads, the way the config would classify it.
+    /// Authorization is auth, not just any header. without it, an auth
+    /// guard's credentials are the headers and cookies named like keys,
+    /// tokens or sessions, or everything it reads when none are
+    pub fn inputs(&self) -> Vec<GuardKind> {
+        let authorization = self
+            .headers
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case("authorization"));
+        let named_like_key = |name: &String| {
+            let name = name.to_lowercase();
+            ["key", "token", "auth", "session", "secret", "sid"]
+                .iter()
+                .any(|word| name.contains(word))
+        };
+        let any_named_like_key = self.headers.iter().chain(&self.cookies).any(named_like_key);
+        let is_key = |name: &String| {
+            !authorization && self.is_auth() && (named_like_key(name) || !any_named_like_key)
+        };
+
+        self.headers
+            .iter()
+            .map(|name| {
+                if name.eq_ignore_ascii_case("authorization") {
+                    GuardKind::Auth {
+                        scheme: self
+                            .auth_scheme
+                            .to_owned()
+                            .unwrap_or_else(|| "bearer".to_string()),
+                    }
+                } else if is_key(name) {
+                    GuardKind::ApiKey {
+                        name: name.to_owned(),
+                        location: "header".to_string(),
+                    }
+                } else {
+                    GuardKind::Header {
+                        name: name.to_owned(),
+                    }
+                }
+            })
+            .chain(self.cookies.iter().map(|name| {
+                if is_key(name) {
+                    GuardKind::ApiKey {
+                        name: name.to_owned(),
+                        location: "cookie".to_string(),
+                    }
+                } else {
+                    GuardKind::Cookie {
+                        name: name.to_owned(),
+                    }
+                }
+            }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn finds_what_a_guard_reads_and_how_it_fails() {
+        let guard = RocketGuard::parse_impl(
+            &syn::parse_str(
+                "
+                #[rocket::async_trait]
+                impl<'r> FromRequest<'r> for User {
+                    type Error = AuthError;
+
+                    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+                        let headers = req.headers();
+                        let token = match headers.get_one(\"Authorization\") {
+                            Some(value) => value.strip_prefix(\"Bearer \"),
+                            None => return Outcome::Error((Status::Unauthorized, AuthError::Missing)),
+                        };
+                        let session = req.cookies().get_private(\"session\");
+                        let db = req.guard::<Db>().await.into_outcome((Status::ServiceUnavailable, AuthError::Db))?;
+                        if req.headers().contains(\"X-Banned\") {
+                            return Outcome::Error((Status::new(403), AuthError::Banned));
+                        }
+                        Outcome::Success(User)
+                    }
+                }
+                ",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            guard,
+            RocketGuard {
+                ident: "User".to_string(),
+                headers: vec!["Authorization".to_string(), "X-Banned".to_string()],
+                cookies: vec!["session".to_string()],
+                statuses: vec![401, 503, 403],
+                auth_scheme: Some("bearer".to_string()),
+            }
+        );
+        assert_eq!(
+            guard.inputs(),
+            vec![
+                GuardKind::Auth {
+                    scheme: "bearer".to_string()
+                },
+                GuardKind::Header {
+                    name: "X-Banned".to_string()
+                },
+                GuardKind::Cookie {
+                    name: "session".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            RocketGuard::parse_impl(&syn::parse_str("impl Display for User {}").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn classifies_api_keys_by_how_the_guard_fails() {
+        let guard = |body: &str| {
+            RocketGuard::parse_impl(
+                &syn::parse_str(&format!(
+                    "impl<'r> FromRequest<'r> for Key {{
+                        async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ()> {{ {} }}
+                    }}",
+                    body
+                ))
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            guard(
+                "
+                let locale = req.headers().get_one(\"Accept-Language\");
+                match req.headers().get_one(\"X-Api-Key\") {
+                    Some(key) => Outcome::Success(Key),
+                    None => Outcome::Error((Status::Unauthorized, ())),
+                }
+                "
+            )
+            .inputs(),
+            vec![
+                GuardKind::Header {
+                    name: "Accept-Language".to_string()
+                },
+                GuardKind::ApiKey {
+                    name: "X-Api-Key".to_string(),
+                    location: "header".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            guard(
+                "
+                req.cookies()
+                    .get_private(\"sid\")
+                    .map(|_| Key)
+                    .or_forward(())
+                    .or_error((Status::Forbidden, ()))
+                "
+            )
+            .inputs(),
+            vec![GuardKind::ApiKey {
+                name: "sid".to_string(),
+                location: "cookie".to_string()
+            }]
+        );
+        assert_eq!(
+            guard("req.headers().get_one(\"X-Api-Key\").map(|_| Key).or_forward(())").inputs(),
+            vec![GuardKind::Header {
+                name: "X-Api-Key".to_string()
+            }],
+            "Without a 401 or 403 it's just a header"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::rocket_route::RocketRoute;
+use syn::visit::Visit;
+
+/// `rocket::ignite().mount("/api", routes![a, b])` puts routes `a` and `b`
+/// under `/api`, so the path in the route attribute is only part of the story
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketMount {
+    pub base: String,
+    // the paths in `routes![]` as written, `users::index`
+    pub routes: Vec<String>,
+    // the module the mount call is in, which those paths are relative to
+    #[serde(default)]
+    pub module: Vec<String>,
+}
+
+struct MountVisitor {
+    mounts: Vec<RocketMount>,
+}
+
+impl<'ast> Visit<'ast> for MountVisitor {
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        // the receiver first so chained mounts come out in the order written
+        syn::visit::visit_expr_method_call(self, call);
+
+        if call.method == "mount" && call.args.len() == 2 {
+            if let (
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(base),
+                    ..
+                }),
+                syn::Expr::Macro(syn::ExprMacro { mac, .. }),
+            ) = (&call.args[0], &call.args[1])
+            {
+                if mac.path.is_ident("routes") {
+                    let routes = mac
+                        .parse_body_with(
+                            syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                        )
+                        .map(|paths| {
+                            paths
+                                .iter()
+                                .map(|path| {
+                                    path.segments
+                                        .iter()
+                                        .map(|segment| {
+                                            crate::ast_formatting::format_idnt(&segment.ident)
+                                        })
+                                        .collect::<Vec<_>>()
+                                        .join("::")
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
+                    self.mounts.push(RocketMount {
+                        base: base.value(),
+                        routes,
+                        module: vec![],
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl RocketMount {
+    /// every mount call anywhere in the function body
+    pub fn parse_fn(function: &syn::ItemFn) -> Vec<Self> {
+        let mut visitor = MountVisitor { mounts: vec![] };
+        visitor.visit_item_fn(function);
+        visitor.mounts
+    }
+
+    /// the routes this call mounts, as indexes into `routes`. a path is
+    /// resolved from the mount's module first, and when nothing is there
+    /// (the handler was brought in with `use`) it's matched against the
+    /// end of every route's path
+    pub fn mounted(&self, routes: &[RocketRoute]) -> Vec<usize> {
+        let route_path = |route: &RocketRoute| {
+            route
+                .module
+                .iter()
+                .cloned()
+                .chain(std::iter::once(route.ident.to_owned()))
+                .collect::<Vec<_>>()
+        };
+
+        self.routes
+            .iter()
+            .flat_map(|path| {
+                let segments: Vec<String> = path.split("::").map(|s| s.to_string()).collect();
+                let resolved = self.resolve(&segments);
+                let exact: Vec<usize> = (0..routes.len())
+                    .filter(|idx| route_path(&routes[*idx]) == resolved)
+                    .collect();
+                if !exact.is_empty() {
+                    return exact;
+                }
+                let relative: Vec<&String> = segments
+                    .iter()
+                    .skip_while(|segment| ["crate", "self", "super"].contains(&segment.as_str()))
+                    .collect();
+                (0..routes.len())
+                    .filter(|idx| {
+                        let path = route_path(&routes[*idx]);
+                        path.len() >= relative.len()
+                            && path[path.len() - relative.len()..]
+                                .iter()
+                                .zip(&relative)
+                                .all(|(a, b)| a == *b)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// `crate::` starts from the root, `self::` and `super::` from the
+    /// mount's module and anything else is relative to it
+    fn resolve(&self, segments: &[String]) -> Vec<String> {
+        let mut resolved = self.module.to_owned();
+        let mut segments = segments.iter().peekable();
+        if segments.peek().map(|s| s.as_str()) == Some("crate") {
+            resolved.clear();
+            segments.next();
+        }
+        segments.for_each(|segment| match segment.as_str() {
+            "self" => (),
+            "super" => {
+                resolved.pop();
+            }
+            _ => resolved.push(segment.to_owned()),
+        });
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_chained_mounts() {
+        let result = RocketMount::parse_fn(
+            &syn::parse_str(
+                "
+                fn main() {
+                    rocket::ignite()
+                        .mount(\"/\", routes![index])
+                        .mount(\"/api/users\", routes![users::list, users::get,])
+                        .launch();
+                }
+                ",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            result,
+            vec![
+                RocketMount {
+                    base: "/".to_string(),
+                    routes: vec!["index".to_string()],
+                    module: vec![],
+                },
+                RocketMount {
+                    base: "/api/users".to_string(),
+                    routes: vec!["users::list".to_string(), "users::get".to_string()],
+                    module: vec![],
+                },
+            ],
+            "Finds every mount and the routes in it"
+        );
+    }
+}
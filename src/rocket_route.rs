@@ -1,19 +1,60 @@
 use crate::rocket_attribute::{RocketAttribute, RouteAttribute};
+use crate::rocket_document::RocketDocument;
+use crate::rocket_uri::{RocketUri, UriSegment};
+use crate::rocket_wrapper::{RocketBody, RocketResponse};
+use crate::type_ref::TypeRef;
 
 /// the only functions we are interested in are ones with the route attributes.
 /// these represent the routes that are exposed.
-
-#[derive(Debug, PartialEq, serde::Serialize)]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RocketRoute {
-    ident: String,
-    handler: Function,
-    route: RouteAttribute,
+    pub ident: String,
+    pub handler: Function,
+    pub route: RouteAttribute,
+    pub uri: RocketUri,
+    pub docs: Option<String>,
+    // filled in by the document
+    pub module: Vec<String>,
+    pub mounts: Vec<String>,
+    // where it starts in its file, for pointing at it. left out of the json
+    // since it changes with every edit above the item
+    #[serde(skip)]
+    pub line: usize,
+    pub params: Vec<RocketParam>,
+    pub body: Option<RocketBody>,
+    pub responses: Vec<RocketResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Function {
+    pub args: Vec<(String, String)>,
+    // what the handler was written with, aliases and all
+    pub ret: String,
+    // what it actually is once aliases are expanded
+    pub ret_type: TypeRef,
+}
+
+/// a handler argument and where rocket gets it from
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketParam {
+    pub ident: String,
+    pub ty: TypeRef,
+    pub kind: ParamKind,
 }
 
-#[derive(Debug, Clone, PartialEq, serde::Serialize)]
-struct Function {
-    args: Vec<(String, String)>,
-    ret: String,
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    // <id> and <path..> in the path
+    Path,
+    // <q> in the query
+    Query,
+    // <opts..> in the query, a FromForm struct whose fields are the params
+    QueryForm,
+    // data = "<arg>"
+    Data,
+    // anything else is a request guard
+    Guard,
 }
 
 impl RocketRoute {
@@ -45,13 +86,133 @@ impl RocketRoute {
                         })
                         .collect(),
                     ret: crate::ast_formatting::format_ret_type(&function.sig.output),
+                    ret_type: TypeRef::from_ret_type(&function.sig.output),
                 },
+                uri: RocketUri::parse(&route_attr.path),
+                docs: crate::ast_formatting::format_docs(&function.attrs),
+                module: vec![],
+                line: crate::ast_formatting::format_line(function),
+                mounts: vec![],
                 route: route_attr,
+                params: vec![],
+                body: None,
+                responses: vec![],
             })
         } else {
             None
         }
     }
+
+    /// expand aliases in the return type and work out what the request body
+    /// and responses look like
+    pub fn resolve_types(&mut self, document: &RocketDocument) {
+        self.handler.ret_type = document.expand_aliases(&self.handler.ret_type);
+        self.params = self
+            .handler
+            .args
+            .iter()
+            .map(|(ident, ty)| RocketParam {
+                ident: ident.to_owned(),
+                ty: document.expand_aliases(&TypeRef::parse(ty)),
+                kind: self.param_kind(ident),
+            })
+            .collect();
+        self.body = self.data_arg().map(|(_, ty)| {
+            crate::rocket_wrapper::request_body(
+                &document.expand_aliases(&TypeRef::parse(ty)),
+                self.route.format.as_deref(),
+            )
+        });
+        self.responses = document.responses(&self.handler.ret_type);
+
+        // a failing guard answers before the handler runs. Option and Result
+        // guards hand the failure to the handler instead
+        let statuses: Vec<u32> = self
+            .params
+            .iter()
+            .filter(|param| param.kind == ParamKind::Guard)
+            .filter(|param| !matches!(param.ty.ident(), Some("Option") | Some("Result")))
+            .filter_map(|param| document.request_guard(&param.ty))
+            .flat_map(|guard| guard.statuses.iter().copied())
+            .collect();
+        statuses.into_iter().for_each(|status| {
+            if !self.responses.iter().any(|res| res.status == Some(status)) {
+                self.responses.push(RocketResponse {
+                    status: Some(status),
+                    content_type: None,
+                    body: None,
+                })
+            }
+        });
+    }
+
+    /// the uri for every place the route is mounted. a route that isn't
+    /// mounted anywhere we know about is treated as mounted at `/`
+    pub fn full_uris(&self) -> Vec<RocketUri> {
+        if self.mounts.is_empty() {
+            vec![self.uri.to_owned()]
+        } else {
+            self.mounts
+                .iter()
+                .map(|base| RocketUri::mounted(base, &self.route.path))
+                .collect()
+        }
+    }
+
+    /// the bases `full_uris` is built from, in the same order
+    pub fn mounts_or_root(&self) -> Vec<String> {
+        if self.mounts.is_empty() {
+            vec!["/".to_string()]
+        } else {
+            self.mounts.to_owned()
+        }
+    }
+
+    /// the argument named in `data = "<arg>"`
+    fn data_arg(&self) -> Option<&(String, String)> {
+        let name = self.data_name()?;
+        self.handler.args.iter().find(|(arg, _)| arg == name)
+    }
+
+    /// what `data = "<arg>"` names, whether or not there's an argument
+    /// called that
+    pub fn data_name(&self) -> Option<&str> {
+        self.route
+            .data
+            .as_deref()
+            .map(|data| data.trim_start_matches('<').trim_end_matches('>'))
+    }
+
+    fn param_kind(&self, ident: &str) -> ParamKind {
+        let named = |segment: &&UriSegment| segment.name() == Some(ident);
+
+        if self.uri.path.iter().any(|segment| named(&segment)) {
+            ParamKind::Path
+        } else if let Some(segment) = self.uri.query.iter().find(named) {
+            match segment {
+                UriSegment::Trailing { .. } => ParamKind::QueryForm,
+                _ => ParamKind::Query,
+            }
+        } else if self.data_name() == Some(ident) {
+            ParamKind::Data
+        } else {
+            ParamKind::Guard
+        }
+    }
+
+    pub fn params_of_kind(&self, kind: ParamKind) -> impl Iterator<Item = &RocketParam> {
+        self.params.iter().filter(move |param| param.kind == kind)
+    }
+
+    /// the argument types and the return type
+    pub fn used_types(&self) -> Vec<TypeRef> {
+        self.handler
+            .args
+            .iter()
+            .map(|(_, ty)| TypeRef::parse(ty))
+            .chain(std::iter::once(self.handler.ret_type.to_owned()))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -88,8 +249,17 @@ mod test {
                         ("arg1".to_string(), "String".to_string()),
                         ("arg2".to_string(), "CustomType".to_string())
                     ],
-                    ret: "Result < User , Error >".to_string()
-                }
+                    ret: "Result < User , Error >".to_string(),
+                    ret_type: TypeRef::parse("Result<User, Error>"),
+                },
+                uri: RocketUri::parse("/some/path"),
+                docs: None,
+                module: vec![],
+                line: 2,
+                mounts: vec![],
+                params: vec![],
+                body: None,
+                responses: vec![],
             }),
             "Parses a function with a route attribute properly"
         )
@@ -145,10 +315,82 @@ mod test {
                         ("arg14".to_string(), "String".to_string()),
                         ("arg2".to_string(), "Option < Auth >".to_string())
                     ],
-                    ret: "i32".to_string()
-                }
+                    ret: "i32".to_string(),
+                    ret_type: TypeRef::parse("i32"),
+                },
+                uri: RocketUri::parse("/"),
+                docs: None,
+                module: vec![],
+                line: 2,
+                mounts: vec![],
+                params: vec![],
+                body: None,
+                responses: vec![],
             }),
             "Parses functions that have at least one route attribute"
         );
     }
+
+    #[test]
+    fn resolves_body_and_responses() {
+        let mut result = RocketRoute::parse_fn(
+            &syn::parse_str(
+                "
+                #[post(\"/users\", format = \"json\", data = \"<user>\")]
+                fn create(user: Json<NewUser>) -> Option<Json<User>> {
+
+                }
+                ",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        result.resolve_types(&RocketDocument::default());
+
+        assert_eq!(
+            result.body,
+            Some(crate::rocket_wrapper::request_body(
+                &TypeRef::parse("Json<NewUser>"),
+                Some("json")
+            )),
+            "Uses the argument named by data as the request body"
+        );
+        assert_eq!(
+            result.responses,
+            crate::rocket_wrapper::responses(&TypeRef::parse("Option<Json<User>>"), &|_| None),
+        );
+    }
+
+    #[test]
+    fn classifies_arguments() {
+        let mut result = RocketRoute::parse_fn(
+            &syn::parse_str(
+                "
+                #[put(\"/users/<id>?<notify>&<opts..>\", data = \"<user>\")]
+                fn update(id: u64, notify: bool, opts: Form<Options>, user: Json<User>, auth: Admin) {
+
+                }
+                ",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        result.resolve_types(&RocketDocument::default());
+
+        assert_eq!(
+            result
+                .params
+                .iter()
+                .map(|param| (param.ident.as_str(), param.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("id", ParamKind::Path),
+                ("notify", ParamKind::Query),
+                ("opts", ParamKind::QueryForm),
+                ("user", ParamKind::Data),
+                ("auth", ParamKind::Guard),
+            ],
+            "Classifies arguments by where in the request they come from"
+        );
+    }
 }
@@ -0,0 +1,310 @@
+use crate::rocket_attribute::BodyDirection;
+use crate::type_ref::TypeRef;
+use std::collections::BTreeMap;
+
+/// the `#[serde(..)]` attributes that change what a type looks like on the
+/// wire. on a struct or enum these are the container attributes, on an enum
+/// variant only `rename`, `rename_all` and `skip` mean anything
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SerdeAttributes {
+    pub rename: Option<String>,
+    pub rename_all: Option<String>,
+    // internally tagged, #[serde(tag = "type")]
+    pub tag: Option<String>,
+    // adjacently tagged when there's a tag too
+    pub content: Option<String>,
+    pub untagged: bool,
+    pub transparent: bool,
+    pub deny_unknown_fields: bool,
+    // #[serde(default)] on the container, every field can be left out
+    pub default: bool,
+    pub skip: Skip,
+    // keyed by field name, only fields with serde attributes are in here
+    pub fields: BTreeMap<String, SerdeField>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SerdeField {
+    pub rename: Option<String>,
+    pub skip: Skip,
+    // default or skip_serializing_if, either way it doesn't have to be there
+    pub optional: bool,
+    pub flatten: bool,
+}
+
+/// `skip_serializing` and `skip_deserializing` leave something out going
+/// one way only, `skip` is both
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Skip {
+    pub serializing: bool,
+    pub deserializing: bool,
+}
+
+impl Skip {
+    fn set(&mut self, name: &str) {
+        match name {
+            "skip" => {
+                self.serializing = true;
+                self.deserializing = true;
+            }
+            "skip_serializing" => self.serializing = true,
+            "skip_deserializing" => self.deserializing = true,
+            _ => (),
+        }
+    }
+
+    /// whether it's missing from a body going `direction`. for a type that
+    /// goes both ways (or isn't a body) only what neither has is missing
+    pub fn skipped(&self, direction: Option<BodyDirection>) -> bool {
+        match direction {
+            Some(BodyDirection::Request) => self.deserializing,
+            Some(BodyDirection::Response) => self.serializing,
+            _ => self.serializing && self.deserializing,
+        }
+    }
+}
+
+/// the name=value and bare words inside every `#[serde(..)]`. for
+/// `rename(serialize = "a", deserialize = "b")` the serialize name wins
+fn serde_metas(attrs: &[syn::Attribute]) -> Vec<(String, Option<String>)> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                path.get_ident().map(|ident| (ident.to_string(), None))
+            }
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => match (&nv.lit, nv.path.get_ident())
+            {
+                (syn::Lit::Str(value), Some(ident)) => {
+                    Some((ident.to_string(), Some(value.value())))
+                }
+                (_, Some(ident)) => Some((ident.to_string(), None)),
+                _ => None,
+            },
+            syn::NestedMeta::Meta(syn::Meta::List(list)) => {
+                let value = list.nested.iter().find_map(|nested| match nested {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Str(value),
+                        ..
+                    })) if path.is_ident("serialize") => Some(value.value()),
+                    _ => None,
+                });
+                list.path
+                    .get_ident()
+                    .map(|ident| (ident.to_string(), value))
+            }
+            syn::NestedMeta::Lit(_) => None,
+        })
+        .collect()
+}
+
+impl SerdeField {
+    /// skipped one way only on a type that goes both ways, so it's only
+    /// there in one direction
+    pub fn one_way(&self, direction: Option<BodyDirection>) -> bool {
+        self.skip.serializing != self.skip.deserializing
+            && !matches!(
+                direction,
+                Some(BodyDirection::Request) | Some(BodyDirection::Response)
+            )
+    }
+
+    pub fn parse(attrs: &[syn::Attribute]) -> Option<Self> {
+        let metas = serde_metas(attrs);
+        if metas.is_empty() {
+            return None;
+        }
+
+        let mut field = SerdeField::default();
+        metas
+            .into_iter()
+            .for_each(|(name, value)| match name.as_str() {
+                "rename" => field.rename = value,
+                "skip" | "skip_serializing" | "skip_deserializing" => field.skip.set(&name),
+                "default" | "skip_serializing_if" => field.optional = true,
+                "flatten" => field.flatten = true,
+                _ => (),
+            });
+        Some(field)
+    }
+}
+
+impl SerdeAttributes {
+    /// the container (or variant) attributes plus the ones on each field
+    pub fn parse(attrs: &[syn::Attribute], fields: &syn::Fields) -> Self {
+        let mut serde = SerdeAttributes::default();
+        serde_metas(attrs)
+            .into_iter()
+            .for_each(|(name, value)| match name.as_str() {
+                "rename" => serde.rename = value,
+                "rename_all" => serde.rename_all = value,
+                "tag" => serde.tag = value,
+                "content" => serde.content = value,
+                "untagged" => serde.untagged = true,
+                "transparent" => serde.transparent = true,
+                "deny_unknown_fields" => serde.deny_unknown_fields = true,
+                "default" => serde.default = true,
+                "skip" | "skip_serializing" | "skip_deserializing" => serde.skip.set(&name),
+                _ => (),
+            });
+
+        serde.fields = fields
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, field)| {
+                let name = field
+                    .ident
+                    .as_ref()
+                    .map(crate::ast_formatting::format_idnt)
+                    .unwrap_or_else(|| idx.to_string());
+                SerdeField::parse(&field.attrs).map(|field| (name, field))
+            })
+            .collect();
+        serde
+    }
+
+    pub fn field(&self, name: &str) -> Option<&SerdeField> {
+        self.fields.get(name)
+    }
+
+    /// whether a field of type `ty` has to be in a body going `direction`.
+    /// `Option`s, defaults and fields that are only there one way can be
+    /// left out
+    pub fn required(&self, name: &str, ty: &TypeRef, direction: Option<BodyDirection>) -> bool {
+        let field = self.field(name).cloned().unwrap_or_default();
+        ty.ident() != Some("Option")
+            && !field.optional
+            && !self.default
+            && !field.one_way(direction)
+    }
+
+    /// the name a field goes by in the serialized form
+    pub fn field_name(&self, name: &str) -> String {
+        match self.field(name).and_then(|field| field.rename.as_ref()) {
+            Some(rename) => rename.to_owned(),
+            None => rename_field(self.rename_all.as_deref(), name),
+        }
+    }
+}
+
+fn snake_to_pascal(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn pascal_to_snake(name: &str) -> String {
+    let mut snake = String::new();
+    name.chars().enumerate().for_each(|(idx, c)| {
+        if c.is_ascii_uppercase() && idx > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    });
+    snake
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// `rename_all` for a field, which starts out snake_case
+pub fn rename_field(rule: Option<&str>, name: &str) -> String {
+    match rule {
+        Some("UPPERCASE") | Some("SCREAMING_SNAKE_CASE") => name.to_ascii_uppercase(),
+        Some("PascalCase") => snake_to_pascal(name),
+        Some("camelCase") => lower_first(&snake_to_pascal(name)),
+        Some("kebab-case") => name.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => name.to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_string(),
+    }
+}
+
+/// `rename_all` for a variant, which starts out PascalCase
+pub fn rename_variant(rule: Option<&str>, name: &str) -> String {
+    match rule {
+        Some("lowercase") => name.to_ascii_lowercase(),
+        Some("UPPERCASE") => name.to_ascii_uppercase(),
+        Some("camelCase") => lower_first(name),
+        Some("snake_case") => pascal_to_snake(name),
+        Some("SCREAMING_SNAKE_CASE") => pascal_to_snake(name).to_ascii_uppercase(),
+        Some("kebab-case") => pascal_to_snake(name).replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => {
+            pascal_to_snake(name).to_ascii_uppercase().replace('_', "-")
+        }
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_container_and_field_attributes() {
+        let strct: syn::ItemStruct = syn::parse_str(
+            "
+            #[derive(Serialize)]
+            #[serde(rename_all = \"camelCase\", deny_unknown_fields, default)]
+            struct User {
+                user_id: u64,
+                #[serde(rename(serialize = \"nick\", deserialize = \"nickname\"), default)]
+                nickname: String,
+                #[serde(skip)]
+                secret: String,
+                #[serde(skip_serializing)]
+                password: String,
+            }
+            ",
+        )
+        .unwrap();
+        let serde = SerdeAttributes::parse(&strct.attrs, &strct.fields);
+
+        assert_eq!(serde.rename_all.as_deref(), Some("camelCase"));
+        assert!(serde.deny_unknown_fields);
+        assert_eq!(serde.field_name("user_id"), "userId");
+        assert_eq!(serde.field_name("nickname"), "nick");
+        assert_eq!(
+            serde.field("nickname").map(|field| field.optional),
+            Some(true)
+        );
+        assert!(serde.default);
+        let skip = |name: &str, direction| serde.field(name).unwrap().skip.skipped(direction);
+        assert!(skip("secret", None));
+        assert!(!skip("password", None));
+        assert!(
+            !skip("password", Some(BodyDirection::Request)),
+            "Clients still send fields that are only skipped when serializing"
+        );
+        assert!(skip("password", Some(BodyDirection::Response)));
+    }
+
+    #[test]
+    fn renames_like_serde() {
+        assert_eq!(rename_field(Some("PascalCase"), "created_at"), "CreatedAt");
+        assert_eq!(
+            rename_field(Some("SCREAMING-KEBAB-CASE"), "created_at"),
+            "CREATED-AT"
+        );
+        assert_eq!(rename_variant(Some("snake_case"), "NotFound"), "not_found");
+        assert_eq!(rename_variant(Some("kebab-case"), "NotFound"), "not-found");
+        assert_eq!(rename_variant(Some("camelCase"), "NotFound"), "notFound");
+    }
+}
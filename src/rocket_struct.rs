@@ -1,23 +1,43 @@
-use crate::rocket_attribute::{ResponseAttribute, RocketAttribute};
+use crate::rocket_attribute::{BodyDirection, ResponseAttribute, RocketAttribute};
+use crate::rocket_generic::RocketGeneric;
+use crate::rocket_serde::SerdeAttributes;
+use crate::type_ref::TypeRef;
+use std::collections::{BTreeMap, HashMap};
 
 /// there are at least 2 cases where we would be interested in structs:
 ///   1. request guards or parameter guards
 ///   2. when it's being used in the response
+///
 /// in the first scenario, it could be any struct
 /// in the second scenario, it should derive Responder and also have a response
 /// attrbute with status/content_type information
-
-#[derive(Debug, PartialEq, serde::Serialize)]
+/// the derives also tell us whether the struct is a request body, a response
+/// body or both
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct RocketStruct {
-    ident: String,
+    pub ident: String,
+    pub generics: Vec<RocketGeneric>,
     // so for unnamed ill just go with (0, type), (1, type) like a fake array
-    fields: Vec<(String, String)>,
-    response: Option<ResponseAttribute>,
+    pub fields: Vec<(String, String)>,
+    // the `///` comments on named fields, keyed by the field's rust name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub field_docs: BTreeMap<String, String>,
+    pub response: Option<ResponseAttribute>,
+    pub derives: Vec<String>,
+    pub direction: Option<BodyDirection>,
+    pub docs: Option<String>,
+    pub serde: SerdeAttributes,
+    // filled in by the document, the parser doesn't know where it is
+    pub module: Vec<String>,
+    // the line it starts on, kept out of the json like RocketRoute::line
+    #[serde(skip)]
+    pub line: usize,
 }
 
 impl RocketStruct {
     pub fn parse_struct(s: &syn::ItemStruct) -> Self {
         let attrs = RocketAttribute::from_struct(s);
+        let derives = RocketAttribute::derives(&attrs);
 
         let fields = match s.fields.to_owned() {
             syn::Fields::Named(fields) => fields
@@ -43,10 +63,27 @@ impl RocketStruct {
                 .collect(),
             syn::Fields::Unit => vec![],
         };
+        let field_docs = s
+            .fields
+            .iter()
+            .filter_map(|field| {
+                let ident = field.ident.as_ref()?;
+                crate::ast_formatting::format_docs(&field.attrs)
+                    .map(|docs| (crate::ast_formatting::format_idnt(ident), docs))
+            })
+            .collect();
 
         RocketStruct {
             ident: crate::ast_formatting::format_idnt(&s.ident),
+            generics: RocketGeneric::parse_generics(&s.generics),
+            docs: crate::ast_formatting::format_docs(&s.attrs),
+            serde: SerdeAttributes::parse(&s.attrs, &s.fields),
+            module: vec![],
+            line: crate::ast_formatting::format_line(s),
             fields,
+            field_docs,
+            direction: BodyDirection::from_derives(&derives),
+            derives,
             response: attrs.into_iter().find_map(|attr| {
                 if let RocketAttribute::Response(response) = attr {
                     Some(response)
@@ -56,6 +93,51 @@ impl RocketStruct {
             }),
         }
     }
+
+    /// a copy of a generic struct with the type parameters filled in, so
+    /// `Page<User>` gets a `Vec<User>` field instead of a `Vec<T>` one
+    pub fn instantiate(&self, args: &[TypeRef]) -> Self {
+        let params: HashMap<String, TypeRef> = self
+            .generics
+            .iter()
+            .map(|generic| generic.ident.to_owned())
+            .zip(args.iter().cloned())
+            .collect();
+
+        RocketStruct {
+            ident: TypeRef::Path {
+                path: vec![self.ident.to_owned()],
+                args: args.to_vec(),
+            }
+            .to_string(),
+            generics: vec![],
+            fields: self
+                .fields
+                .iter()
+                .map(|(name, ty)| {
+                    (
+                        name.to_owned(),
+                        TypeRef::parse(ty).substitute(&params).format(),
+                    )
+                })
+                .collect(),
+            field_docs: self.field_docs.clone(),
+            response: self.response.clone(),
+            derives: self.derives.clone(),
+            direction: self.direction,
+            docs: self.docs.clone(),
+            serde: self.serde.clone(),
+            module: self.module.clone(),
+            line: self.line,
+        }
+    }
+
+    pub fn field_types(&self) -> Vec<TypeRef> {
+        self.fields
+            .iter()
+            .map(|(_, ty)| TypeRef::parse(ty))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -68,6 +150,7 @@ mod test {
             &syn::parse_str(
                 "
                 pub struct MyStruct {
+                    /// the first one
                     field1: i32,
                     field2: AnotherStruct,
                     field3: (i32, u8),
@@ -80,12 +163,22 @@ mod test {
             result,
             RocketStruct {
                 ident: "MyStruct".to_string(),
+                generics: vec![],
                 fields: vec![
                     ("field1".to_string(), "i32".to_string()),
                     ("field2".to_string(), "AnotherStruct".to_string()),
                     ("field3".to_string(), "(i32 , u8)".to_string())
                 ],
+                field_docs: vec![("field1".to_string(), "the first one".to_string())]
+                    .into_iter()
+                    .collect(),
                 response: None,
+                derives: vec![],
+                direction: None,
+                docs: None,
+                serde: SerdeAttributes::default(),
+                module: vec![],
+                line: 2,
             },
             "Parses struct properly"
         );
@@ -105,11 +198,19 @@ mod test {
             result,
             RocketStruct {
                 ident: "Point".to_string(),
+                generics: vec![],
                 fields: vec![
                     (0.to_string(), "i32".to_string()),
                     (1.to_string(), "i32".to_string())
                 ],
+                field_docs: BTreeMap::new(),
                 response: None,
+                derives: vec![],
+                direction: None,
+                docs: None,
+                serde: SerdeAttributes::default(),
+                module: vec![],
+                line: 2,
             },
             "Parses struct properly"
         );
@@ -130,13 +231,97 @@ mod test {
             result,
             RocketStruct {
                 ident: "MyResponse".to_string(),
+                generics: vec![],
                 fields: vec![(0.to_string(), "String".to_string())],
+                field_docs: BTreeMap::new(),
                 response: Some(ResponseAttribute {
-                    status: 404,
+                    status: Some(404),
                     content_type: None
                 }),
+                derives: vec![],
+                direction: None,
+                docs: None,
+                serde: SerdeAttributes::default(),
+                module: vec![],
+                line: 2,
             },
             "Parses struct properly"
         );
     }
+
+    #[test]
+    fn parses_structs_with_body_derives() {
+        let result = RocketStruct::parse_struct(
+            &syn::parse_str(
+                "
+                #[derive(Debug, Deserialize)]
+                struct LoginData {
+                    username: String,
+                }
+                ",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            result,
+            RocketStruct {
+                ident: "LoginData".to_string(),
+                generics: vec![],
+                fields: vec![("username".to_string(), "String".to_string())],
+                field_docs: BTreeMap::new(),
+                response: None,
+                derives: vec!["Debug".to_string(), "Deserialize".to_string()],
+                direction: Some(BodyDirection::Request),
+                docs: None,
+                serde: SerdeAttributes::default(),
+                module: vec![],
+                line: 2,
+            },
+            "Records derives and labels the struct as a request body"
+        );
+    }
+
+    #[test]
+    fn instantiates_generic_structs() {
+        let result = RocketStruct::parse_struct(
+            &syn::parse_str(
+                "
+                #[derive(Serialize)]
+                struct Page<T> {
+                    items: Vec<T>,
+                    total: u64,
+                }
+                ",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            result.generics,
+            vec![RocketGeneric {
+                ident: "T".to_string(),
+                bounds: vec![],
+            }],
+            "Records type parameters"
+        );
+        assert_eq!(
+            result.instantiate(&[TypeRef::parse("User")]),
+            RocketStruct {
+                ident: "Page<User>".to_string(),
+                generics: vec![],
+                fields: vec![
+                    ("items".to_string(), "Vec < User >".to_string()),
+                    ("total".to_string(), "u64".to_string())
+                ],
+                field_docs: BTreeMap::new(),
+                response: None,
+                derives: vec!["Serialize".to_string()],
+                direction: Some(BodyDirection::Response),
+                docs: None,
+                serde: SerdeAttributes::default(),
+                module: vec![],
+                line: 2,
+            },
+            "Fills in the type parameters of every field"
+        );
+    }
 }
@@ -0,0 +1,155 @@
+/// a route path like `/users/<id>/files/<path..>?<q>&<opts..>&lang=en` split
+/// up into its segments so we know which handler arguments come from the path
+/// and which come from the query string
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketUri {
+    pub path: Vec<UriSegment>,
+    pub query: Vec<UriSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UriSegment {
+    // users
+    Static { value: String },
+    // <id>
+    Dynamic { name: String },
+    // <path..>
+    Trailing { name: String },
+}
+
+impl UriSegment {
+    fn parse(segment: &str) -> Self {
+        match segment
+            .strip_prefix('<')
+            .and_then(|segment| segment.strip_suffix('>'))
+        {
+            Some(name) => match name.strip_suffix("..") {
+                Some(name) => UriSegment::Trailing {
+                    name: name.to_string(),
+                },
+                None => UriSegment::Dynamic {
+                    name: name.to_string(),
+                },
+            },
+            None => UriSegment::Static {
+                value: segment.to_string(),
+            },
+        }
+    }
+
+    /// the name of the handler argument this segment binds to
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            UriSegment::Static { .. } => None,
+            UriSegment::Dynamic { name } | UriSegment::Trailing { name } => Some(name),
+        }
+    }
+}
+
+impl RocketUri {
+    pub fn parse(uri: &str) -> Self {
+        let (path, query) = match uri.find('?') {
+            Some(idx) => (&uri[..idx], &uri[idx + 1..]),
+            None => (uri, ""),
+        };
+
+        RocketUri {
+            path: path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(UriSegment::parse)
+                .collect(),
+            query: query
+                .split('&')
+                .filter(|segment| !segment.is_empty())
+                .map(UriSegment::parse)
+                .collect(),
+        }
+    }
+
+    /// the uri of a route mounted at `base`
+    pub fn mounted(base: &str, path: &str) -> Self {
+        RocketUri::parse(&format!(
+            "{}/{}",
+            base.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        ))
+    }
+
+    /// the path with `<param>` turned into `{param}`, the way most docs
+    /// formats (openapi, .http files, etc) want it
+    pub fn templated_path(&self) -> String {
+        let path = self
+            .path
+            .iter()
+            .map(|segment| match segment {
+                UriSegment::Static { value } => value.to_owned(),
+                UriSegment::Dynamic { name } | UriSegment::Trailing { name } => {
+                    format!("{{{}}}", name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        format!("/{}", path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_path_and_query_segments() {
+        assert_eq!(
+            RocketUri::parse("/users/<id>/files/<path..>?<q>&<opts..>&lang=en"),
+            RocketUri {
+                path: vec![
+                    UriSegment::Static {
+                        value: "users".to_string()
+                    },
+                    UriSegment::Dynamic {
+                        name: "id".to_string()
+                    },
+                    UriSegment::Static {
+                        value: "files".to_string()
+                    },
+                    UriSegment::Trailing {
+                        name: "path".to_string()
+                    },
+                ],
+                query: vec![
+                    UriSegment::Dynamic {
+                        name: "q".to_string()
+                    },
+                    UriSegment::Trailing {
+                        name: "opts".to_string()
+                    },
+                    UriSegment::Static {
+                        value: "lang=en".to_string()
+                    },
+                ],
+            },
+            "Splits the uri into path and query segments"
+        );
+    }
+
+    #[test]
+    fn mounts_under_base() {
+        assert_eq!(
+            RocketUri::mounted("/api/", "/users/<id>").templated_path(),
+            "/api/users/{id}"
+        );
+        assert_eq!(RocketUri::mounted("/", "/").templated_path(), "/");
+        assert_eq!(RocketUri::mounted("/api", "/").templated_path(), "/api");
+    }
+
+    #[test]
+    fn templates_path() {
+        assert_eq!(
+            RocketUri::parse("/hello/<name>/<age>").templated_path(),
+            "/hello/{name}/{age}"
+        );
+        assert_eq!(RocketUri::parse("/").templated_path(), "/");
+    }
+}
@@ -0,0 +1,337 @@
+//! rocket (and rocket_contrib) ship a bunch of wrapper types that say a lot
+//! about what goes over the wire. `Json<T>` is a json body shaped like `T`,
+//! `Option<T>` is whatever `T` is or a 404, etc. this is where that knowledge
+//! lives so the rest of the code only has to deal with bodies and responses
+
+use crate::type_ref::TypeRef;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketBody {
+    pub media_type: Option<String>,
+    // None when the body is just bytes (Data, TempFile, Vec<u8>)
+    pub schema: Option<TypeRef>,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RocketResponse {
+    // None when the status is only known at runtime (Status, status::Custom)
+    pub status: Option<u32>,
+    pub content_type: Option<String>,
+    pub body: Option<TypeRef>,
+}
+
+/// the `format` in a route attribute can be a full media type or one of
+/// rocket's shorthands
+pub fn media_type(format: &str) -> String {
+    match format {
+        "any" => "*/*",
+        "binary" => "application/octet-stream",
+        "bytes" => "application/octet-stream",
+        "css" => "text/css",
+        "form" => "application/x-www-form-urlencoded",
+        "html" => "text/html",
+        "javascript" => "application/javascript",
+        "json" => "application/json",
+        "msgpack" => "application/msgpack",
+        "multipart" => "multipart/form-data",
+        "plain" | "text" => "text/plain",
+        "xml" => "text/xml",
+        format => format,
+    }
+    .to_string()
+}
+
+/// the reason phrase for a status code, for docs that want a description
+pub fn status_reason(status: u32) -> &'static str {
+    match status {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        409 => "Conflict",
+        410 => "Gone",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        418 => "I'm a teapot",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Response",
+    }
+}
+
+/// what the request body looks like for a handler's `data` argument
+pub fn request_body(ty: &TypeRef, format: Option<&str>) -> RocketBody {
+    let format = format.map(media_type);
+    let body = |media_type: &str, schema: Option<&TypeRef>| RocketBody {
+        media_type: Some(format.to_owned().unwrap_or_else(|| media_type.to_string())),
+        schema: schema.cloned(),
+        required: true,
+    };
+
+    match (ty.ident(), ty.args()) {
+        (Some("Option"), [inner]) => RocketBody {
+            required: false,
+            ..request_body(inner, format.as_deref())
+        },
+        (Some("Json"), [inner]) => body("application/json", Some(inner)),
+        (Some("MsgPack"), [inner]) => body("application/msgpack", Some(inner)),
+        (Some("Form"), [inner]) | (Some("LenientForm"), [inner]) => {
+            body("application/x-www-form-urlencoded", Some(inner))
+        }
+        (Some("Data"), _) | (Some("TempFile"), _) | (Some("Capped"), _) => {
+            body("application/octet-stream", None)
+        }
+        (Some("Vec"), [TypeRef::Path { path, .. }])
+            if path.last().map(|s| s.as_str()) == Some("u8") =>
+        {
+            body("application/octet-stream", None)
+        }
+        (Some("String"), _) | (Some("str"), _) => body("text/plain", Some(ty)),
+        _ => match ty {
+            TypeRef::Reference { elem } => request_body(elem, format.as_deref()),
+            // some custom FromData type, all we know is what the route says
+            _ => RocketBody {
+                media_type: format,
+                schema: Some(ty.to_owned()),
+                required: true,
+            },
+        },
+    }
+}
+
+/// every response a handler returning `ty` can produce, as far as the wrapper
+/// types tell us. types that aren't wrappers are handed to `custom` (which
+/// knows about the project's own responders) and if it doesn't know them
+/// either they are assumed to be a 200 with that type as the body
+pub fn responses(
+    ty: &TypeRef,
+    custom: &dyn Fn(&TypeRef) -> Option<Vec<RocketResponse>>,
+) -> Vec<RocketResponse> {
+    let response =
+        |status: Option<u32>, content_type: Option<&str>, body: Option<&TypeRef>| RocketResponse {
+            status,
+            content_type: content_type.map(|content_type| content_type.to_string()),
+            body: body.cloned(),
+        };
+    // status::Created<T> and friends keep the body but force the status
+    let with_status = |status: u32, inner: &TypeRef| -> Vec<RocketResponse> {
+        responses(inner, custom)
+            .into_iter()
+            .map(|res| RocketResponse {
+                status: Some(status),
+                ..res
+            })
+            .collect()
+    };
+
+    match (ty.ident(), ty.args()) {
+        (Some("Option"), [inner]) => {
+            let mut res = responses(inner, custom);
+            res.push(response(Some(404), None, None));
+            res
+        }
+        (Some("Result"), [ok, err]) => {
+            let mut res = responses(ok, custom);
+            res.extend(responses(err, custom));
+            res
+        }
+        (Some("Json"), [inner]) => vec![response(Some(200), Some("application/json"), Some(inner))],
+        (Some("MsgPack"), [inner]) => {
+            vec![response(
+                Some(200),
+                Some("application/msgpack"),
+                Some(inner),
+            )]
+        }
+        (Some("Html"), [inner]) | (Some("RawHtml"), [inner]) => {
+            vec![response(Some(200), Some("text/html"), Some(inner))]
+        }
+        (Some("RawJson"), [inner]) => {
+            vec![response(Some(200), Some("application/json"), Some(inner))]
+        }
+        (Some("Plain"), [inner]) | (Some("RawText"), [inner]) => {
+            vec![response(Some(200), Some("text/plain"), Some(inner))]
+        }
+        (Some("Xml"), [inner]) | (Some("RawXml"), [inner]) => {
+            vec![response(Some(200), Some("text/xml"), Some(inner))]
+        }
+        (Some("Template"), _) => vec![response(Some(200), Some("text/html"), None)],
+        (Some("NamedFile"), _) => vec![response(Some(200), Some("application/octet-stream"), None)],
+        (Some("Redirect"), _) => vec![response(Some(303), None, None)],
+        (Some("Status"), _) => vec![response(None, None, None)],
+        (Some("NoContent"), _) => vec![response(Some(204), None, None)],
+        (Some("Created"), [inner]) => with_status(201, inner),
+        (Some("Created"), []) => vec![response(Some(201), None, None)],
+        (Some("Accepted"), [inner]) => with_status(202, inner),
+        (Some("BadRequest"), [inner]) => with_status(400, inner),
+        (Some("Unauthorized"), [inner]) => with_status(401, inner),
+        (Some("Forbidden"), [inner]) => with_status(403, inner),
+        (Some("NotFound"), [inner]) => with_status(404, inner),
+        (Some("Conflict"), [inner]) => with_status(409, inner),
+        (Some("Custom"), [inner]) => responses(inner, custom)
+            .into_iter()
+            .map(|res| RocketResponse {
+                status: None,
+                ..res
+            })
+            .collect(),
+        // the content type is picked at runtime, the body is still the inner one
+        (Some("Content"), [inner]) => responses(inner, custom)
+            .into_iter()
+            .map(|res| RocketResponse {
+                content_type: None,
+                ..res
+            })
+            .collect(),
+        // flash messages are a cookie on top of the actual response
+        (Some("Flash"), [inner]) | (Some("Box"), [inner]) => responses(inner, custom),
+        (Some("Vec"), [TypeRef::Path { path, .. }])
+            if path.last().map(|s| s.as_str()) == Some("u8") =>
+        {
+            vec![response(Some(200), Some("application/octet-stream"), None)]
+        }
+        (Some("String"), _) | (Some("str"), _) => {
+            vec![response(Some(200), Some("text/plain"), Some(ty))]
+        }
+        _ => match ty {
+            TypeRef::Tuple { elems } if elems.is_empty() => vec![response(Some(200), None, None)],
+            TypeRef::Reference { elem } => responses(elem, custom),
+            _ => custom(ty).unwrap_or_else(|| vec![response(Some(200), None, Some(ty))]),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn option_json_is_json_or_404() {
+        assert_eq!(
+            responses(&TypeRef::parse("Option<Json<User>>"), &|_| None),
+            vec![
+                RocketResponse {
+                    status: Some(200),
+                    content_type: Some("application/json".to_string()),
+                    body: Some(TypeRef::parse("User")),
+                },
+                RocketResponse {
+                    status: Some(404),
+                    content_type: None,
+                    body: None,
+                },
+            ],
+            "Option adds a 404 to whatever the inner type responds with"
+        );
+    }
+
+    #[test]
+    fn result_responds_with_both_sides() {
+        assert_eq!(
+            responses(
+                &TypeRef::parse("Result<status::Created<Json<User>>, status::BadRequest<String>>"),
+                &|_| None
+            ),
+            vec![
+                RocketResponse {
+                    status: Some(201),
+                    content_type: Some("application/json".to_string()),
+                    body: Some(TypeRef::parse("User")),
+                },
+                RocketResponse {
+                    status: Some(400),
+                    content_type: Some("text/plain".to_string()),
+                    body: Some(TypeRef::parse("String")),
+                },
+            ],
+            "Result responds with either the ok or the err type"
+        );
+    }
+
+    #[test]
+    fn runtime_statuses_are_unknown() {
+        assert_eq!(
+            responses(&TypeRef::parse("status::Custom<Template>"), &|_| None),
+            vec![RocketResponse {
+                status: None,
+                content_type: Some("text/html".to_string()),
+                body: None,
+            }],
+        );
+    }
+
+    #[test]
+    fn unknown_types_go_through_custom() {
+        let custom = |ty: &TypeRef| {
+            if ty.ident() == Some("LoginResponse") {
+                Some(vec![RocketResponse {
+                    status: Some(401),
+                    content_type: None,
+                    body: None,
+                }])
+            } else {
+                None
+            }
+        };
+        assert_eq!(
+            responses(&TypeRef::parse("status::Accepted<LoginResponse>"), &custom),
+            vec![RocketResponse {
+                status: Some(202),
+                content_type: None,
+                body: None,
+            }],
+            "Wrappers still apply on top of custom responders"
+        );
+    }
+
+    #[test]
+    fn request_bodies_from_wrappers() {
+        assert_eq!(
+            request_body(&TypeRef::parse("Json<LoginData>"), None),
+            RocketBody {
+                media_type: Some("application/json".to_string()),
+                schema: Some(TypeRef::parse("LoginData")),
+                required: true,
+            }
+        );
+        assert_eq!(
+            request_body(&TypeRef::parse("Option<Form<LoginData>>"), None),
+            RocketBody {
+                media_type: Some("application/x-www-form-urlencoded".to_string()),
+                schema: Some(TypeRef::parse("LoginData")),
+                required: false,
+            },
+            "Option makes the body optional"
+        );
+        assert_eq!(
+            request_body(&TypeRef::parse("Data"), Some("json")),
+            RocketBody {
+                media_type: Some("application/json".to_string()),
+                schema: None,
+                required: true,
+            },
+            "Raw data takes the media type from the route's format"
+        );
+    }
+}
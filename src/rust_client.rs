@@ -0,0 +1,880 @@
+use crate::rocket_attribute::derives_trait;
+use crate::rocket_document::RocketDocument;
+use crate::rocket_enum::RocketEnum;
+use crate::rocket_generic::RocketGeneric;
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_serde::SerdeAttributes;
+use crate::rocket_struct::RocketStruct;
+use crate::rocket_uri::UriSegment;
+use crate::rocket_wrapper::status_reason;
+use crate::type_mapping::{builtin_schema, TypeMapping};
+use crate::type_ref::TypeRef;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+/// the part of the client that doesn't depend on the routes
+const CLIENT_RUNTIME: &str = r#"#[derive(Debug)]
+pub enum Error {
+    InvalidUrl(String),
+    Http(reqwest::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::InvalidUrl(url) => write!(f, "invalid base url {}", url),
+            Error::Http(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+
+/// calls the api at `base_url`. point it at a local mock server in tests
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Client::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// for timeouts, default headers, auth and so on
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Client {
+            http,
+            base_url: base_url.into(),
+        }
+    }
+
+    fn url<I>(&self, segments: I) -> Result<reqwest::Url, Error>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut url = reqwest::Url::parse(&self.base_url)
+            .map_err(|_| Error::InvalidUrl(self.base_url.to_owned()))?;
+        url.path_segments_mut()
+            .map_err(|_| Error::InvalidUrl(self.base_url.to_owned()))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+"#;
+
+/// how the generated client gets its types
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RustClientOptions {
+    /// the crate the api's types live in. types from the document that
+    /// derive both Serialize and Deserialize are used from there, by their
+    /// module path, instead of being mirrored
+    pub types_crate: Option<String>,
+}
+
+/// a rust module with a reqwest based `Client` that has an async method per
+/// route. every status a route can answer with is a variant of the method's
+/// response enum
+pub fn to_rust_client(document: &RocketDocument, options: &RustClientOptions) -> String {
+    let generator = Generator { document, options };
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// generated by rocket-doc-gen, do not edit\n\
+         #![allow(dead_code, unused_mut, clippy::all)]\n\n\
+         use serde::{{Deserialize, Serialize}};\n"
+    )
+    .unwrap();
+
+    document.body_types().iter().for_each(|(module, ident)| {
+        if let Some(strct) = document
+            .structs
+            .iter()
+            .find(|strct| &strct.module == module && &strct.ident == ident)
+        {
+            if !generator.reused(&strct.derives) {
+                out.push_str(&generator.mirror_struct(strct));
+                out.push('\n');
+            }
+        } else if let Some(enm) = document
+            .enums
+            .iter()
+            .find(|enm| &enm.module == module && &enm.ident == ident)
+        {
+            if !generator.reused(&enm.derives) {
+                out.push_str(&generator.mirror_enum(enm));
+                out.push('\n');
+            }
+        }
+    });
+
+    let functions = route_functions(document);
+    functions.iter().for_each(|(name, route)| {
+        out.push_str(&generator.response_enum(name, route));
+        out.push('\n');
+    });
+
+    out.push_str(CLIENT_RUNTIME);
+    functions.iter().for_each(|(name, route)| {
+        out.push('\n');
+        out.push_str(&generator.route_method(name, route));
+    });
+    out.push_str("}\n");
+    out
+}
+
+/// handler names are already snake_case, the module goes in front when two
+/// modules have a handler with the same name
+fn route_functions(document: &RocketDocument) -> Vec<(String, &RocketRoute)> {
+    let mut seen = HashSet::new();
+    document
+        .routes
+        .iter()
+        .map(|route| {
+            let mut name = route.ident.to_owned();
+            if !seen.insert(name.to_owned()) {
+                name = route
+                    .module
+                    .iter()
+                    .chain(std::iter::once(&route.ident))
+                    .map(|segment| segment.as_str())
+                    .collect::<Vec<_>>()
+                    .join("_");
+                seen.insert(name.to_owned());
+            }
+            (name, route)
+        })
+        .collect()
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| {
+                    first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+                })
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// `404` is `NotFound`, statuses without a reason phrase are `Status599`
+fn status_variant(status: u32) -> String {
+    match status_reason(status) {
+        "Response" => format!("Status{}", status),
+        reason => pascal_case(&reason.replace('\'', "")),
+    }
+}
+
+/// std types serde handles that aren't in the prelude, by the name they're
+/// usually imported as
+fn std_path(ident: &str) -> Option<&'static str> {
+    Some(match ident {
+        "HashMap" => "std::collections::HashMap",
+        "HashSet" => "std::collections::HashSet",
+        "BTreeMap" => "std::collections::BTreeMap",
+        "BTreeSet" => "std::collections::BTreeSet",
+        "VecDeque" => "std::collections::VecDeque",
+        "Duration" => "std::time::Duration",
+        "SystemTime" => "std::time::SystemTime",
+        "IpAddr" => "std::net::IpAddr",
+        "Ipv4Addr" => "std::net::Ipv4Addr",
+        "Ipv6Addr" => "std::net::Ipv6Addr",
+        "SocketAddr" => "std::net::SocketAddr",
+        "PathBuf" => "std::path::PathBuf",
+        _ => return None,
+    })
+}
+
+/// the rust type for a mapped or built in schema. what's a string on the
+/// wire (dates, uuids, decimals, urls) is a `String`, so the client doesn't
+/// need chrono and friends
+fn schema_rust_type(schema: &Value) -> String {
+    if let Some(schemas) = schema.get("anyOf").and_then(|schemas| schemas.as_array()) {
+        let types: BTreeSet<String> = schemas.iter().map(schema_rust_type).collect();
+        return match types.len() {
+            1 => types.into_iter().next().unwrap(),
+            _ => "serde_json::Value".to_string(),
+        };
+    }
+    match schema.get("type").and_then(|ty| ty.as_str()) {
+        Some("string") => "String".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("integer") if schema["minimum"].as_i64() >= Some(0) => "u64".to_string(),
+        Some("integer") => "i64".to_string(),
+        Some("number") => "f64".to_string(),
+        Some("array") => format!(
+            "Vec<{}>",
+            schema
+                .get("items")
+                .map(schema_rust_type)
+                .unwrap_or_else(|| "serde_json::Value".to_string())
+        ),
+        _ => "serde_json::Value".to_string(),
+    }
+}
+
+fn generics(generics: &[RocketGeneric]) -> String {
+    if generics.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<{}>",
+            generics
+                .iter()
+                .map(|generic| generic.ident.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+fn doc_comment(docs: &Option<String>, indent: &str) -> String {
+    docs.iter()
+        .flat_map(|docs| docs.lines())
+        .map(|line| format!("{}/// {}\n", indent, line).replace("/// \n", "///\n"))
+        .collect()
+}
+
+/// the container attributes the mirror needs to (de)serialize the same way
+fn container_attributes(serde: &SerdeAttributes, indent: &str) -> String {
+    let mut attrs = vec![];
+    if let Some(rename_all) = &serde.rename_all {
+        attrs.push(format!("rename_all = {:?}", rename_all));
+    }
+    if let Some(tag) = &serde.tag {
+        attrs.push(format!("tag = {:?}", tag));
+    }
+    if let Some(content) = &serde.content {
+        attrs.push(format!("content = {:?}", content));
+    }
+    if serde.untagged {
+        attrs.push("untagged".to_string());
+    }
+    if serde.transparent {
+        attrs.push("transparent".to_string());
+    }
+    if serde.deny_unknown_fields {
+        attrs.push("deny_unknown_fields".to_string());
+    }
+    if attrs.is_empty() {
+        String::new()
+    } else {
+        format!("{}#[serde({})]\n", indent, attrs.join(", "))
+    }
+}
+
+struct Generator<'a> {
+    document: &'a RocketDocument,
+    options: &'a RustClientOptions,
+}
+
+impl<'a> Generator<'a> {
+    /// a type from the document is used from the api's crate when we know
+    /// what that crate is and the type can go both ways
+    fn reused(&self, derives: &[String]) -> bool {
+        self.options.types_crate.is_some()
+            && derives_trait(derives, "Serialize")
+            && derives_trait(derives, "Deserialize")
+    }
+
+    /// the mirror's name. when another module has a type with the same name
+    /// the module goes in front, `api::items::User` is `ApiItemsUser`
+    fn mirror_name(&self, module: &[String], ident: &str) -> String {
+        let name = self.document.type_name(ident, module);
+        let mut segments: Vec<&str> = name.split("::").collect();
+        let ident = segments.pop().unwrap_or_default();
+        segments
+            .iter()
+            .flat_map(|segment| segment.split('_'))
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .chain(std::iter::once(ident.to_string()))
+            .collect()
+    }
+
+    /// the path to a type in the document, from the api's crate or the mirror
+    fn type_path(&self, ty: &TypeRef) -> Option<String> {
+        let (module, ident) = self.document.declared(ty)?;
+        let derives = self
+            .document
+            .structs
+            .iter()
+            .find(|strct| strct.module == module && strct.ident == ident)
+            .map(|strct| &strct.derives)
+            .or_else(|| {
+                self.document
+                    .enums
+                    .iter()
+                    .find(|enm| enm.module == module && enm.ident == ident)
+                    .map(|enm| &enm.derives)
+            })?;
+
+        match &self.options.types_crate {
+            Some(krate) if self.reused(derives) => Some(
+                std::iter::once(krate)
+                    .chain(module.iter())
+                    .chain(std::iter::once(&ident))
+                    .map(|segment| segment.as_str())
+                    .collect::<Vec<_>>()
+                    .join("::"),
+            ),
+            _ => Some(self.mirror_name(&module, &ident)),
+        }
+    }
+
+    /// a type that isn't the document's own, by the project's mapping or the
+    /// built in table
+    fn mapped_type(&self, ty: &TypeRef) -> Option<String> {
+        match self.document.type_mappings.find(ty) {
+            Some(TypeMapping::Type(mapped)) if &TypeRef::parse(mapped) != ty => {
+                Some(self.rust_type(&TypeRef::parse(mapped)))
+            }
+            Some(TypeMapping::Schema(schema)) => Some(schema_rust_type(schema)),
+            _ if self.document.declared(ty).is_some() => None,
+            _ => builtin_schema(ty).map(|schema| schema_rust_type(&schema)),
+        }
+    }
+
+    /// the owned type a client deals in. rocket's wrappers are dropped,
+    /// borrowed data becomes owned since it's deserialized, and types we
+    /// can't name end up as plain json. other crates' types that aren't
+    /// mapped or built in are used as they're written
+    fn rust_type(&self, ty: &TypeRef) -> String {
+        let join = |args: &[TypeRef]| {
+            args.iter()
+                .map(|arg| self.rust_type(arg))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        if let Some(mapped) = self.mapped_type(ty) {
+            return mapped;
+        }
+        match (ty.ident(), ty.args()) {
+            (Some("str"), []) => "String".to_string(),
+            (Some("Json"), [inner])
+            | (Some("MsgPack"), [inner])
+            | (Some("Form"), [inner])
+            | (Some("LenientForm"), [inner])
+            | (Some("Cow"), [.., inner])
+            | (Some("Rc"), [inner])
+            | (Some("Arc"), [inner]) => self.rust_type(inner),
+            _ => match ty {
+                TypeRef::Path { path, args } => {
+                    let name = match (self.type_path(ty), path.as_slice()) {
+                        (Some(name), _) => name,
+                        (None, [ident]) => std_path(ident).unwrap_or(ident).to_string(),
+                        // the document's own but left out, there's nothing to name
+                        (None, [first, ..])
+                            if ["crate", "self", "super"].contains(&first.as_str()) =>
+                        {
+                            return "serde_json::Value".to_string()
+                        }
+                        (None, path) => path.join("::"),
+                    };
+                    if args.is_empty() {
+                        name
+                    } else {
+                        format!("{}<{}>", name, join(args))
+                    }
+                }
+                TypeRef::Tuple { elems } if elems.len() == 1 => format!("({},)", join(elems)),
+                TypeRef::Tuple { elems } => format!("({})", join(elems)),
+                TypeRef::Array { elem } => format!("Vec<{}>", self.rust_type(elem)),
+                TypeRef::Reference { elem } => self.rust_type(elem),
+                TypeRef::Other { .. } => "serde_json::Value".to_string(),
+            },
+        }
+    }
+
+    /// fields for a struct or a variant, `pub` for struct fields. skipped
+    /// fields are left out, the mirror only has what's on the wire
+    fn fields(
+        &self,
+        fields: &[(String, String)],
+        serde: &SerdeAttributes,
+        vis: &str,
+        indent: &str,
+    ) -> String {
+        let fields: Vec<_> = fields
+            .iter()
+            .filter(|(name, _)| {
+                !serde
+                    .field(name)
+                    .map(|field| field.skip.skipped(None))
+                    .unwrap_or(false)
+            })
+            .collect();
+        let unnamed = fields
+            .first()
+            .map(|(name, _)| name.parse::<usize>().is_ok())
+            .unwrap_or(false);
+
+        if fields.is_empty() {
+            String::new()
+        } else if unnamed {
+            format!(
+                "({})",
+                fields
+                    .iter()
+                    .map(|(_, ty)| format!("{}{}", vis, self.rust_type(&TypeRef::parse(ty))))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        } else {
+            let mut out = " {\n".to_string();
+            fields.iter().for_each(|(name, ty)| {
+                if let Some(field) = serde.field(name) {
+                    let mut attrs = vec![];
+                    if let Some(rename) = &field.rename {
+                        attrs.push(format!("rename = {:?}", rename));
+                    }
+                    if field.optional {
+                        attrs.push("default".to_string());
+                    }
+                    // the server never sends it, the client only sends it
+                    if field.skip.serializing {
+                        attrs.push("skip_deserializing".to_string());
+                    }
+                    if field.flatten {
+                        attrs.push("flatten".to_string());
+                    }
+                    if !attrs.is_empty() {
+                        writeln!(out, "{}    #[serde({})]", indent, attrs.join(", ")).unwrap();
+                    }
+                }
+                writeln!(
+                    out,
+                    "{}    {}{}: {},",
+                    indent,
+                    vis,
+                    name,
+                    self.rust_type(&TypeRef::parse(ty))
+                )
+                .unwrap();
+            });
+            write!(out, "{}}}", indent).unwrap();
+            out
+        }
+    }
+
+    fn mirror_struct(&self, strct: &RocketStruct) -> String {
+        let fields = self.fields(&strct.fields, &strct.serde, "pub ", "");
+        let end = if fields.starts_with(" {") {
+            "\n"
+        } else {
+            ";\n"
+        };
+        format!(
+            "{}#[derive(Debug, Clone, Serialize, Deserialize)]\n{}pub struct {}{}{}{}",
+            doc_comment(&strct.docs, ""),
+            container_attributes(&strct.serde, ""),
+            self.mirror_name(&strct.module, &strct.ident),
+            generics(&strct.generics),
+            fields,
+            end
+        )
+    }
+
+    fn mirror_enum(&self, enm: &RocketEnum) -> String {
+        let mut out = format!(
+            "{}#[derive(Debug, Clone, Serialize, Deserialize)]\n{}pub enum {}{} {{\n",
+            doc_comment(&enm.docs, ""),
+            container_attributes(&enm.serde, ""),
+            self.mirror_name(&enm.module, &enm.ident),
+            generics(&enm.generics)
+        );
+        enm.variants
+            .iter()
+            .filter(|variant| !variant.serde.skip.skipped(None))
+            .for_each(|variant| {
+                let mut attrs = vec![];
+                if let Some(rename) = &variant.serde.rename {
+                    attrs.push(format!("rename = {:?}", rename));
+                }
+                if let Some(rename_all) = &variant.serde.rename_all {
+                    attrs.push(format!("rename_all = {:?}", rename_all));
+                }
+                if !attrs.is_empty() {
+                    writeln!(out, "    #[serde({})]", attrs.join(", ")).unwrap();
+                }
+                writeln!(
+                    out,
+                    "    {}{},",
+                    variant.ident,
+                    self.fields(&variant.fields, &variant.serde, "", "    ")
+                )
+                .unwrap();
+            });
+        out.push_str("}\n");
+        out
+    }
+
+    /// what a response body is read as, json when it's a type we know and
+    /// text or bytes otherwise
+    fn response_body(
+        &self,
+        body: &Option<TypeRef>,
+        content_type: &Option<String>,
+    ) -> Option<(String, &'static str)> {
+        let body = body.as_ref()?;
+        let json = content_type
+            .as_deref()
+            .map(|content_type| content_type.contains("json"))
+            .unwrap_or(false);
+
+        match body.ident() {
+            Some("Vec") if body.args().first().and_then(|arg| arg.ident()) == Some("u8") => {
+                Some(("Vec<u8>".to_string(), "response.bytes().await?.to_vec()"))
+            }
+            _ if json => Some((self.rust_type(body), "response.json().await?")),
+            Some("String") | Some("str") => Some(("String".to_string(), "response.text().await?")),
+            _ if content_type.is_some() => Some(("String".to_string(), "response.text().await?")),
+            _ => None,
+        }
+    }
+
+    fn response_enum(&self, name: &str, route: &RocketRoute) -> String {
+        let mut out = format!(
+            "#[derive(Debug)]\npub enum {}Response {{\n",
+            pascal_case(name)
+        );
+        let mut seen = HashSet::new();
+        route
+            .responses
+            .iter()
+            .filter_map(|res| res.status.map(|status| (status, res)))
+            .filter(|(status, _)| seen.insert(*status))
+            .for_each(|(status, res)| {
+                match self.response_body(&res.body, &res.content_type) {
+                    Some((ty, _)) => writeln!(out, "    {}({}),", status_variant(status), ty),
+                    None => writeln!(out, "    {},", status_variant(status)),
+                }
+                .unwrap()
+            });
+        out.push_str("    /// a status the route isn't documented to answer with\n");
+        out.push_str("    Other { status: u16, body: String },\n}\n");
+        out
+    }
+
+    fn route_method(&self, name: &str, route: &RocketRoute) -> String {
+        let param_type = |ty: &TypeRef| match (ty.ident(), ty.args()) {
+            (_, []) if matches!(ty, TypeRef::Reference { .. }) => match ty {
+                TypeRef::Reference { elem } if elem.ident() == Some("str") => "&str".to_string(),
+                ty => self.rust_type(ty),
+            },
+            (Some("String"), []) | (Some("str"), []) => "&str".to_string(),
+            (Some("Option"), [inner]) if matches!(inner.ident(), Some("String") | Some("str")) => {
+                "Option<&str>".to_string()
+            }
+            _ => self.rust_type(&unwrap_result(ty)),
+        };
+        let uri = route
+            .full_uris()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| route.uri.to_owned());
+
+        let trailing = uri.path.iter().find_map(|segment| match segment {
+            UriSegment::Trailing { name } => Some(name),
+            _ => None,
+        });
+
+        let mut args = vec!["&self".to_string()];
+        route.params_of_kind(ParamKind::Path).for_each(|param| {
+            // a `<path..>` is sent as is, its slashes separate segments
+            let ty = match trailing {
+                Some(name) if *name == param.ident => "&str".to_string(),
+                _ => param_type(&param.ty),
+            };
+            args.push(format!("{}: {}", param.ident, ty))
+        });
+
+        let mut query = vec![];
+        route.uri.query.iter().for_each(|segment| match segment {
+            UriSegment::Static { value } => {
+                let mut kv = value.splitn(2, '=');
+                query.push(format!(
+                    "        request = request.query(&[({:?}, {:?})]);\n",
+                    kv.next().unwrap_or_default(),
+                    kv.next().unwrap_or_default()
+                ));
+            }
+            _ => route
+                .params
+                .iter()
+                .filter(|param| Some(param.ident.as_str()) == segment.name())
+                .for_each(|param| {
+                    if param.kind == ParamKind::QueryForm {
+                        args.push(format!(
+                            "{}: &{}",
+                            param.ident,
+                            self.rust_type(&unwrap_result(&param.ty))
+                        ));
+                        query.push(format!(
+                            "        request = request.query({});\n",
+                            param.ident
+                        ));
+                    } else {
+                        args.push(format!("{}: {}", param.ident, param_type(&param.ty)));
+                        query.push(format!(
+                            "        request = request.query(&[({:?}, &{})]);\n",
+                            param.ident, param.ident
+                        ));
+                    }
+                }),
+        });
+
+        let body = route.body.as_ref().map(|body| {
+            let media_type = body.media_type.as_deref().unwrap_or_default();
+            match &body.schema {
+                Some(schema) if media_type.contains("form") => {
+                    args.push(format!("body: &{}", self.rust_type(schema)));
+                    "        request = request.form(body);\n".to_string()
+                }
+                Some(schema) if media_type.contains("json") || body.media_type.is_none() => {
+                    args.push(format!("body: &{}", self.rust_type(schema)));
+                    "        request = request.json(body);\n".to_string()
+                }
+                _ => {
+                    args.push("body: Vec<u8>".to_string());
+                    let mut set = "        request = request.body(body);\n".to_string();
+                    if !media_type.is_empty() {
+                        set.insert_str(
+                            0,
+                            &format!(
+                                "        request = request.header(reqwest::header::CONTENT_TYPE, {:?});\n",
+                                media_type
+                            ),
+                        );
+                    }
+                    set
+                }
+            }
+        });
+
+        let segments = uri
+            .path
+            .iter()
+            .filter_map(|segment| match segment {
+                UriSegment::Static { value } => Some(format!("{:?}", value)),
+                UriSegment::Dynamic { name } => Some(format!("&{}.to_string()", name)),
+                UriSegment::Trailing { .. } => None,
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let segments = match trailing {
+            Some(name) => format!("[{}].iter().copied().chain({}.split('/'))", segments, name),
+            None => format!("&[{}]", segments),
+        };
+
+        let mut out = doc_comment(&route.docs, "    ");
+        writeln!(
+            out,
+            "    pub async fn {}({}) -> Result<{}Response, Error> {{",
+            name,
+            args.join(", "),
+            pascal_case(name)
+        )
+        .unwrap();
+        writeln!(out, "        let url = self.url({})?;", segments).unwrap();
+        writeln!(
+            out,
+            "        let mut request = self.http.request(reqwest::Method::{}, url);",
+            route.route.method.to_uppercase()
+        )
+        .unwrap();
+        query.iter().for_each(|line| out.push_str(line));
+        if let Some(body) = body {
+            out.push_str(&body);
+        }
+        out.push_str("        let response = request.send().await?;\n");
+        out.push_str("        Ok(match response.status().as_u16() {\n");
+
+        let mut seen = HashSet::new();
+        route
+            .responses
+            .iter()
+            .filter_map(|res| res.status.map(|status| (status, res)))
+            .filter(|(status, _)| seen.insert(*status))
+            .for_each(|(status, res)| {
+                let variant = format!("{}Response::{}", pascal_case(name), status_variant(status));
+                match self.response_body(&res.body, &res.content_type) {
+                    Some((_, read)) => {
+                        writeln!(out, "            {} => {}({}),", status, variant, read)
+                    }
+                    None => writeln!(out, "            {} => {},", status, variant),
+                }
+                .unwrap()
+            });
+        writeln!(
+            out,
+            "            status => {}Response::Other {{\n                status,\n                body: response.text().await?,\n            }},\n        }})\n    }}",
+            pascal_case(name)
+        )
+        .unwrap();
+        out
+    }
+}
+
+/// `Result<T, E>` params are rocket validating them for us, the client
+/// sends a T
+fn unwrap_result(ty: &TypeRef) -> TypeRef {
+    match (ty.ident(), ty.args()) {
+        (Some("Result"), [inner, ..]) => inner.to_owned(),
+        _ => ty.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document() -> RocketDocument {
+        let ast = syn::parse_str(
+            "
+            mod models {
+                /// Someone with an account.
+                #[derive(Serialize, Deserialize)]
+                #[serde(rename_all = \"camelCase\")]
+                pub struct User { user_id: u64, #[serde(default)] nickname: Option<String> }
+                #[derive(Deserialize)]
+                pub struct NewUser { name: String }
+            }
+
+            #[put(\"/users/<id>?<notify>\", format = \"json\", data = \"<user>\")]
+            fn update_user(id: u64, notify: Option<bool>, user: Json<NewUser>) -> Option<Json<User>> {}
+            ",
+        )
+        .unwrap();
+        RocketDocument::parse_file(&ast)
+    }
+
+    #[test]
+    fn generates_mirror_types_and_methods() {
+        let result = to_rust_client(&document(), &RustClientOptions::default());
+
+        assert!(
+            syn::parse_file(&result).is_ok(),
+            "The generated module is valid rust:\n{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "/// Someone with an account.\n#[derive(Debug, Clone, Serialize, Deserialize)]\n\
+                 #[serde(rename_all = \"camelCase\")]\npub struct User {\n    pub user_id: u64,\n    \
+                 #[serde(default)]\n    pub nickname: Option<String>,\n}\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("pub enum UpdateUserResponse {\n    Ok(User),\n    NotFound,\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "    pub async fn update_user(&self, id: u64, notify: Option<bool>, body: &NewUser) -> Result<UpdateUserResponse, Error> {\n        \
+                 let url = self.url(&[\"users\", &id.to_string()])?;\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("            200 => UpdateUserResponse::Ok(response.json().await?),\n"),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn mirrors_same_named_types_separately() {
+        let ast = syn::parse_str(
+            "
+            #[derive(Serialize)]
+            struct User { name: String, created: chrono::DateTime<Utc> }
+
+            mod api {
+                mod items {
+                    #[derive(Serialize)]
+                    struct User { id: uuid::Uuid, tags: HashMap<String, String> }
+
+                    #[get(\"/items/<id>\")]
+                    fn item(id: u64) -> Json<User> {}
+                }
+            }
+
+            #[get(\"/me\")]
+            fn me() -> Json<User> {}
+            ",
+        )
+        .unwrap();
+        let result = to_rust_client(
+            &RocketDocument::parse_file(&ast),
+            &RustClientOptions::default(),
+        );
+
+        assert!(
+            result.contains(
+                "pub struct CrateUser {\n    pub name: String,\n    pub created: String,\n}\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("pub struct ApiItemsUser {\n    pub id: String,\n    pub tags: std::collections::HashMap<String, String>,\n}\n"),
+            "Well known types from other crates are what they look like on the wire\n{}",
+            result
+        );
+        assert!(
+            result.contains("pub enum MeResponse {\n    Ok(CrateUser),\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("pub enum ItemResponse {\n    Ok(ApiItemsUser),\n"),
+            "{}",
+            result
+        );
+        assert!(!result.contains("crate::"), "{}", result);
+    }
+
+    #[test]
+    fn reuses_types_from_the_api_crate() {
+        let result = to_rust_client(
+            &document(),
+            &RustClientOptions {
+                types_crate: Some("users_api".to_string()),
+            },
+        );
+        assert!(!result.contains("pub struct User "), "{}", result);
+        assert!(
+            result.contains("Ok(users_api::models::User)"),
+            "Types that go both ways come from the api crate"
+        );
+        assert!(
+            result.contains("pub struct NewUser "),
+            "Types that only deserialize are mirrored"
+        );
+    }
+}
@@ -0,0 +1,630 @@
+use crate::rocket_attribute::BodyDirection;
+use crate::rocket_document::RocketDocument;
+use crate::rocket_enum::{RocketEnum, RocketVariant};
+use crate::rocket_serde::SerdeAttributes;
+use crate::rocket_struct::RocketStruct;
+use crate::type_mapping::{builtin_schema, TypeMapping, TypeMappings};
+use crate::type_ref::TypeRef;
+use serde_json::{json, Map, Value};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+/// turns types into json schemas. structs and enums from the document become
+/// `$ref`s to a definition (`#/components/schemas/` for openapi, `#/$defs/`
+/// for plain json schema) and everything else is inlined
+pub struct SchemaGenerator<'a> {
+    document: &'a RocketDocument,
+    ref_prefix: &'a str,
+    // checked before the document's own mappings
+    mappings: TypeMappings,
+}
+
+impl<'a> SchemaGenerator<'a> {
+    pub fn new(document: &'a RocketDocument, ref_prefix: &'a str) -> Self {
+        SchemaGenerator {
+            document,
+            ref_prefix,
+            mappings: TypeMappings::default(),
+        }
+    }
+
+    pub fn with_mappings(mut self, mappings: TypeMappings) -> Self {
+        self.mappings = mappings;
+        self
+    }
+
+    fn mapping(&self, ty: &TypeRef) -> Option<&TypeMapping> {
+        self.mappings
+            .find(ty)
+            .or_else(|| self.document.type_mappings.find(ty))
+    }
+
+    /// definition names can't have `<`, `>`, `,` or spaces in them, so an
+    /// instance like `Page<User>` becomes `Page_User`
+    pub fn definition_name(ident: &str) -> String {
+        ident
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-'))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    /// the project's type mappings come first, then std types and the
+    /// document's own structs and enums, then the built in table
+    pub fn type_schema(&self, ty: &TypeRef) -> Value {
+        match self.mapping(ty) {
+            Some(TypeMapping::Schema(schema)) => return schema.to_owned(),
+            Some(TypeMapping::Type(mapped)) => {
+                let mapped = TypeRef::parse(mapped);
+                if &mapped != ty {
+                    return self.type_schema(&mapped);
+                }
+            }
+            None => (),
+        }
+
+        // the range is only spelled out where it's narrower than the format's
+        let integer = |format: &str, range: Option<(i64, i64)>| match range {
+            Some((minimum, maximum)) => {
+                json!({ "type": "integer", "format": format, "minimum": minimum, "maximum": maximum })
+            }
+            None => json!({ "type": "integer", "format": format }),
+        };
+        let unsigned = |format: &str| json!({ "type": "integer", "format": format, "minimum": 0 });
+
+        match (ty.ident(), ty.args()) {
+            (Some("bool"), []) => json!({ "type": "boolean" }),
+            (Some("i8"), []) => integer("int32", Some((i8::MIN as i64, i8::MAX as i64))),
+            (Some("i16"), []) => integer("int32", Some((i16::MIN as i64, i16::MAX as i64))),
+            (Some("i32"), []) => integer("int32", None),
+            (Some("u8"), []) => integer("int32", Some((0, u8::MAX as i64))),
+            (Some("u16"), []) => integer("int32", Some((0, u16::MAX as i64))),
+            (Some("u32"), []) => integer("int64", Some((0, u32::MAX as i64))),
+            (Some("i64"), []) | (Some("i128"), []) | (Some("isize"), []) => integer("int64", None),
+            (Some("u64"), []) | (Some("u128"), []) | (Some("usize"), []) => unsigned("int64"),
+            (Some("f32"), []) => json!({ "type": "number", "format": "float" }),
+            (Some("f64"), []) => json!({ "type": "number", "format": "double" }),
+            (Some("String"), []) | (Some("str"), []) | (Some("char"), []) => {
+                json!({ "type": "string" })
+            }
+            (Some("Option"), [inner]) => json!({
+                "anyOf": [self.type_schema(inner), { "type": "null" }]
+            }),
+            (Some("Vec"), [inner])
+            | (Some("VecDeque"), [inner])
+            | (Some("LinkedList"), [inner])
+            | (Some("BinaryHeap"), [inner]) => json!({
+                "type": "array",
+                "items": self.type_schema(inner)
+            }),
+            (Some("HashSet"), [inner]) | (Some("BTreeSet"), [inner]) => json!({
+                "type": "array",
+                "items": self.type_schema(inner),
+                "uniqueItems": true
+            }),
+            (Some("HashMap"), [_, value])
+            | (Some("BTreeMap"), [_, value])
+            | (Some("IndexMap"), [_, value]) => json!({
+                "type": "object",
+                "additionalProperties": self.type_schema(value)
+            }),
+            // smart pointers and body wrappers serialize as whatever is inside
+            (Some("Box"), [inner])
+            | (Some("Rc"), [inner])
+            | (Some("Arc"), [inner])
+            | (Some("Cow"), [inner])
+            | (Some("Json"), [inner])
+            | (Some("MsgPack"), [inner])
+            | (Some("Form"), [inner])
+            | (Some("LenientForm"), [inner]) => self.type_schema(inner),
+            _ => match ty {
+                TypeRef::Tuple { elems } if elems.is_empty() => json!({ "type": "null" }),
+                TypeRef::Tuple { elems } => json!({
+                    "type": "array",
+                    "prefixItems": elems.iter().map(|elem| self.type_schema(elem)).collect::<Vec<_>>(),
+                    "minItems": elems.len(),
+                    "maxItems": elems.len()
+                }),
+                TypeRef::Array { elem } => json!({
+                    "type": "array",
+                    "items": self.type_schema(elem)
+                }),
+                TypeRef::Reference { elem } => self.type_schema(elem),
+                _ => {
+                    if let Some(ident) = self
+                        .document
+                        .find_struct(ty)
+                        .map(|s| self.document.type_name(&s.ident, &s.module))
+                        .or_else(|| {
+                            self.document
+                                .find_enum(ty)
+                                .map(|e| self.document.type_name(&e.ident, &e.module))
+                        })
+                    {
+                        json!({
+                            "$ref": format!("{}{}", self.ref_prefix, Self::definition_name(&ident))
+                        })
+                    } else if let Some(schema) = builtin_schema(ty) {
+                        schema
+                    } else {
+                        // something we know nothing about, at least say what it was
+                        json!({ "description": ty.to_string() })
+                    }
+                }
+            },
+        }
+    }
+
+    /// fields, named or unnamed, as a schema. Option fields aren't required
+    /// and neither are ones serde fills in with a default. renamed fields go
+    /// by their new name and skipped ones aren't there at all. on a type
+    /// that goes both ways, fields skipped one way are readOnly/writeOnly
+    fn fields_schema(
+        &self,
+        fields: &[(String, String)],
+        serde: &SerdeAttributes,
+        direction: Option<BodyDirection>,
+    ) -> Value {
+        let fields: Vec<&(String, String)> = fields
+            .iter()
+            .filter(|(name, _)| {
+                !serde
+                    .field(name)
+                    .map(|field| field.skip.skipped(direction))
+                    .unwrap_or(false)
+            })
+            .collect();
+        let unnamed = fields
+            .first()
+            .map(|(name, _)| name.parse::<usize>().is_ok())
+            .unwrap_or(false);
+
+        match fields.as_slice() {
+            [] => json!({ "type": "null" }),
+            // newtypes and transparent structs serialize as the thing they wrap
+            [(_, ty)] if unnamed || serde.transparent => self.type_schema(&TypeRef::parse(ty)),
+            _ if unnamed => self.type_schema(&TypeRef::Tuple {
+                elems: fields.iter().map(|(_, ty)| TypeRef::parse(ty)).collect(),
+            }),
+            _ => {
+                let mut properties = Map::new();
+                let mut required = vec![];
+                let mut flattened = vec![];
+                fields.iter().for_each(|(name, ty)| {
+                    let ty = TypeRef::parse(ty);
+                    let field = serde.field(name).cloned().unwrap_or_default();
+                    if field.flatten {
+                        flattened.push(self.type_schema(&ty));
+                        return;
+                    }
+                    let mut schema = self.type_schema(&ty);
+                    // only left in because the type goes the other way too
+                    if field.one_way(direction) {
+                        let keyword = if field.skip.deserializing {
+                            "readOnly"
+                        } else {
+                            "writeOnly"
+                        };
+                        schema = json!({ "allOf": [schema], keyword: true });
+                    }
+                    if serde.required(name, &ty, direction) {
+                        required.push(json!(serde.field_name(name)));
+                    }
+                    properties.insert(serde.field_name(name), schema);
+                });
+
+                let mut object = json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required
+                });
+                if flattened.is_empty() {
+                    if serde.deny_unknown_fields {
+                        object["additionalProperties"] = json!(false);
+                    }
+                    object
+                } else {
+                    // flattened fields put their own properties next to ours
+                    flattened.insert(0, object);
+                    json!({ "allOf": flattened })
+                }
+            }
+        }
+    }
+
+    pub fn struct_schema(&self, strct: &RocketStruct) -> Value {
+        self.fields_schema(&strct.fields, &strct.serde, strct.direction)
+    }
+
+    /// every representation serde has for enums. externally tagged is the
+    /// default, unit variants are just their name and everything else is
+    /// `{ "Variant": content }`. `tag` puts the name in a field next to the
+    /// content (or in a field of its own with `content` as well) and
+    /// untagged is only the content
+    pub fn enum_schema(&self, enm: &RocketEnum) -> Value {
+        let variants: Vec<_> = enm
+            .variants
+            .iter()
+            .filter(|variant| !variant.serde.skip.skipped(enm.direction))
+            .map(|variant| (enm.variant_name(variant), variant))
+            .collect();
+        let content = |variant: &RocketVariant| {
+            self.fields_schema(&variant.fields, &variant.serde, enm.direction)
+        };
+        let tagged = |tag: &str, name: &str| {
+            json!({
+                "type": "object",
+                "properties": { tag: { "const": name } },
+                "required": [tag]
+            })
+        };
+
+        let schemas = match (&enm.serde.tag, &enm.serde.content) {
+            _ if enm.serde.untagged => variants
+                .iter()
+                .map(|(_, variant)| content(variant))
+                .collect::<Vec<_>>(),
+            (Some(tag), Some(content_field)) => variants
+                .iter()
+                .map(|(name, variant)| {
+                    let mut schema = tagged(tag, name);
+                    if !variant.fields.is_empty() {
+                        schema["properties"][content_field] = content(variant);
+                        schema["required"] = json!([tag, content_field]);
+                    }
+                    schema
+                })
+                .collect(),
+            (Some(tag), None) => variants
+                .iter()
+                .map(|(name, variant)| {
+                    let mut schema = content(variant);
+                    match schema.get("properties") {
+                        // struct variants get the tag as one more field
+                        Some(_) => {
+                            schema["properties"][tag] = json!({ "const": name });
+                            schema["required"]
+                                .as_array_mut()
+                                .unwrap()
+                                .insert(0, json!(tag));
+                            schema
+                        }
+                        None if variant.fields.is_empty() => tagged(tag, name),
+                        // newtype variants holding a struct, its fields and the tag
+                        None => json!({ "allOf": [tagged(tag, name), schema] }),
+                    }
+                })
+                .collect(),
+            (None, _) => {
+                if variants
+                    .iter()
+                    .all(|(_, variant)| variant.fields.is_empty())
+                {
+                    return json!({
+                        "type": "string",
+                        "enum": variants.iter().map(|(name, _)| name).collect::<Vec<_>>()
+                    });
+                }
+                variants
+                    .iter()
+                    .map(|(name, variant)| {
+                        if variant.fields.is_empty() {
+                            json!({ "const": name })
+                        } else {
+                            json!({
+                                "type": "object",
+                                "properties": { name.to_owned(): content(variant) },
+                                "required": [name],
+                                "additionalProperties": false
+                            })
+                        }
+                    })
+                    .collect()
+            }
+        };
+
+        json!({ "oneOf": schemas })
+    }
+
+    /// schemas for every struct/enum reachable from the root types, keyed by
+    /// definition name
+    pub fn definitions(&self, roots: Vec<TypeRef>) -> BTreeMap<String, Value> {
+        let mut definitions = BTreeMap::new();
+        let mut pending = roots;
+
+        while let Some(ty) = pending.pop() {
+            pending.extend(ty.children().into_iter().cloned());
+            // a mapped type is documented as what it's mapped to, not as itself
+            if let Some(mapping) = self.mapping(&ty) {
+                if let TypeMapping::Type(mapped) = mapping {
+                    pending.push(TypeRef::parse(mapped));
+                }
+                continue;
+            }
+
+            if let Some(strct) = self.document.find_struct(&ty) {
+                let name = self.document.type_name(&strct.ident, &strct.module);
+                if let Entry::Vacant(entry) = definitions.entry(Self::definition_name(&name)) {
+                    entry.insert(with_docs(self.struct_schema(strct), &strct.docs));
+                    pending.extend(strct.field_types());
+                }
+            } else if let Some(enm) = self.document.find_enum(&ty) {
+                let name = self.document.type_name(&enm.ident, &enm.module);
+                if let Entry::Vacant(entry) = definitions.entry(Self::definition_name(&name)) {
+                    entry.insert(with_docs(self.enum_schema(enm), &enm.docs));
+                    pending.extend(enm.field_types());
+                }
+            }
+        }
+
+        definitions
+    }
+}
+
+/// doc comments end up as the schema's description
+fn with_docs(mut schema: Value, docs: &Option<String>) -> Value {
+    if let (Some(docs), Some(object)) = (docs, schema.as_object_mut()) {
+        object.insert("description".to_string(), json!(docs));
+    }
+    schema
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document(content: &str) -> RocketDocument {
+        RocketDocument::parse_file(&syn::parse_str(content).unwrap())
+    }
+
+    #[test]
+    fn schemas_for_std_types() {
+        let document = RocketDocument::default();
+        let generator = SchemaGenerator::new(&document, "#/$defs/");
+        assert_eq!(
+            generator.type_schema(&TypeRef::parse("HashMap<String, Vec<u8>>")),
+            json!({
+                "type": "object",
+                "additionalProperties": {
+                    "type": "array",
+                    "items": { "type": "integer", "format": "int32", "minimum": 0, "maximum": 255 }
+                }
+            })
+        );
+        assert_eq!(
+            generator.type_schema(&TypeRef::parse("Option<bool>")),
+            json!({ "anyOf": [{ "type": "boolean" }, { "type": "null" }] })
+        );
+    }
+
+    #[test]
+    fn maps_well_known_and_configured_types() {
+        let mut document = document(
+            "
+            struct Email(String);
+            struct User { id: uuid::Uuid, email: Email, joined: DateTime<Utc>, age: u8 }
+            ",
+        );
+        document.type_mappings = serde_json::from_value(json!({
+            "Email": { "type": "string", "format": "email" },
+            "DateTime": "i64"
+        }))
+        .unwrap();
+        let generator = SchemaGenerator::new(&document, "#/$defs/");
+
+        assert_eq!(
+            generator.definitions(vec![TypeRef::parse("User")])["User"]["properties"],
+            json!({
+                "id": { "type": "string", "format": "uuid" },
+                "email": { "type": "string", "format": "email" },
+                "joined": { "type": "integer", "format": "int64" },
+                "age": { "type": "integer", "format": "int32", "minimum": 0, "maximum": 255 }
+            })
+        );
+        assert_eq!(
+            generator
+                .definitions(vec![TypeRef::parse("User")])
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["User"],
+            "Mapped types don't get a definition of their own"
+        );
+    }
+
+    #[test]
+    fn structs_are_refs_with_definitions() {
+        let document = document(
+            "
+            struct Page<T> { items: Vec<T>, next: Option<String> }
+            struct User { id: u64 }
+            enum Role { Admin, Member }
+
+            #[get(\"/\")]
+            fn index() -> Json<Page<User>> {}
+            ",
+        );
+        let generator = SchemaGenerator::new(&document, "#/$defs/");
+
+        assert_eq!(
+            generator.type_schema(&TypeRef::parse("Page<User>")),
+            json!({ "$ref": "#/$defs/Page_User" })
+        );
+        let definitions = generator.definitions(vec![TypeRef::parse("Page<User>")]);
+        assert_eq!(
+            definitions.keys().collect::<Vec<_>>(),
+            vec!["Page_User", "User"],
+            "Collects every struct reachable from the roots"
+        );
+        assert_eq!(
+            definitions["Page_User"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "items": { "type": "array", "items": { "$ref": "#/$defs/User" } },
+                    "next": { "anyOf": [{ "type": "string" }, { "type": "null" }] }
+                },
+                "required": ["items"]
+            }),
+            "Option fields are not required"
+        );
+        assert_eq!(
+            generator.enum_schema(&document.enums[0]),
+            json!({ "type": "string", "enum": ["Admin", "Member"] })
+        );
+    }
+
+    #[test]
+    fn honors_serde_attributes() {
+        let document = document(
+            "
+            #[serde(rename_all = \"camelCase\", deny_unknown_fields)]
+            struct User {
+                user_id: u64,
+                #[serde(default)]
+                display_name: String,
+                #[serde(skip)]
+                password: String,
+            }
+
+            #[serde(tag = \"type\", rename_all = \"snake_case\")]
+            enum Event {
+                SignedUp { user_id: u64 },
+                #[serde(rename = \"bye\")]
+                Deleted,
+            }
+
+            #[serde(tag = \"t\", content = \"c\")]
+            enum Adjacent { Text(String), Empty }
+
+            #[serde(untagged)]
+            enum Either { Number(u64), Word(String) }
+            ",
+        );
+        let generator = SchemaGenerator::new(&document, "#/$defs/");
+
+        assert_eq!(
+            generator.struct_schema(&document.structs[0]),
+            json!({
+                "type": "object",
+                "properties": {
+                    "userId": { "type": "integer", "format": "int64", "minimum": 0 },
+                    "displayName": { "type": "string" }
+                },
+                "required": ["userId"],
+                "additionalProperties": false
+            })
+        );
+        assert_eq!(
+            generator.enum_schema(&document.enums[0]),
+            json!({ "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "user_id": { "type": "integer", "format": "int64", "minimum": 0 },
+                        "type": { "const": "signed_up" }
+                    },
+                    "required": ["type", "user_id"]
+                },
+                {
+                    "type": "object",
+                    "properties": { "type": { "const": "bye" } },
+                    "required": ["type"]
+                }
+            ]}),
+            "Internally tagged"
+        );
+        assert_eq!(
+            generator.enum_schema(&document.enums[1]),
+            json!({ "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "t": { "const": "Text" }, "c": { "type": "string" } },
+                    "required": ["t", "c"]
+                },
+                {
+                    "type": "object",
+                    "properties": { "t": { "const": "Empty" } },
+                    "required": ["t"]
+                }
+            ]}),
+            "Adjacently tagged"
+        );
+        assert_eq!(
+            generator.enum_schema(&document.enums[2]),
+            json!({ "oneOf": [
+                { "type": "integer", "format": "int64", "minimum": 0 },
+                { "type": "string" }
+            ]}),
+            "Untagged"
+        );
+    }
+
+    #[test]
+    fn skips_fields_by_direction() {
+        let document = document(
+            "
+            #[derive(Deserialize)]
+            #[serde(default)]
+            struct Login { name: String, #[serde(skip_serializing)] password: String }
+
+            #[derive(Serialize, Deserialize)]
+            struct Account { #[serde(skip_serializing)] password: String, #[serde(skip_deserializing)] id: u64 }
+            ",
+        );
+        let generator = SchemaGenerator::new(&document, "#/$defs/");
+
+        assert_eq!(
+            generator.struct_schema(&document.structs[0]),
+            json!({
+                "type": "object",
+                "properties": { "name": { "type": "string" }, "password": { "type": "string" } },
+                "required": []
+            }),
+            "Request bodies keep fields only skipped when serializing"
+        );
+        assert_eq!(
+            generator.struct_schema(&document.structs[1])["properties"],
+            json!({
+                "password": { "allOf": [{ "type": "string" }], "writeOnly": true },
+                "id": { "allOf": [{ "type": "integer", "format": "int64", "minimum": 0 }], "readOnly": true }
+            })
+        );
+    }
+
+    #[test]
+    fn tells_same_named_types_apart() {
+        let document = document(
+            "
+            mod users {
+                #[derive(Serialize)]
+                struct User { name: String }
+
+                #[get(\"/\")]
+                fn index() -> Json<User> {}
+            }
+
+            mod admin {
+                #[derive(Serialize)]
+                struct User { id: u64 }
+
+                #[get(\"/\")]
+                fn index() -> Json<User> {}
+            }
+            ",
+        );
+        let generator = SchemaGenerator::new(&document, "#/$defs/");
+        let definitions = generator.definitions(vec![
+            document.routes[0].responses[0].body.clone().unwrap(),
+            document.routes[1].responses[0].body.clone().unwrap(),
+        ]);
+
+        assert_eq!(
+            definitions.keys().collect::<Vec<_>>(),
+            vec!["admin_User", "users_User"]
+        );
+        assert_eq!(
+            definitions["admin_User"]["properties"],
+            json!({ "id": { "type": "integer", "format": "int64", "minimum": 0 } })
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! the requests as text to paste: a `.http` file for the vs code rest client
+//! and jetbrains' http client, and a curl command per route
+
+use crate::collection::{sample_requests, SampleBody, SampleRequest, BASE_URL};
+use crate::rocket_document::RocketDocument;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// a `.http` file with `@baseUrl` at the top and a `###` separated request
+/// per route. path params are variables set to an example value right above
+/// the request, named after it since variables are shared by the whole file
+/// and the same param can be a number in one route and a string in another
+pub fn to_http_file(document: &RocketDocument) -> String {
+    let modules = sample_requests(document);
+    let identifier = |name: &str| {
+        name.split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("_")
+    };
+
+    let mut out = format!("@baseUrl = {}\n", BASE_URL);
+    let mut prefixes = BTreeSet::new();
+    modules.iter().for_each(|(module, requests)| {
+        requests.iter().for_each(|request| {
+            write!(out, "\n### {}::{}\n", module, request.name).unwrap();
+            // handlers with the same name in two modules get the module too
+            let mut prefix = identifier(&request.name);
+            if !prefixes.insert(prefix.to_owned()) {
+                prefix = identifier(&format!("{}_{}", module, request.name));
+                prefixes.insert(prefix.to_owned());
+            }
+            request.path_params.iter().for_each(|param| {
+                writeln!(out, "@{}_{} = {}", prefix, param.name, param.value).unwrap()
+            });
+            out.push_str(&comments(request));
+            writeln!(
+                out,
+                "{} {{{{baseUrl}}}}{}",
+                request.method,
+                request.path_and_query(|name| format!("{{{{{}_{}}}}}", prefix, name))
+            )
+            .unwrap();
+            request.headers.iter().for_each(|(name, value)| {
+                writeln!(out, "{}: {}", name, value).unwrap();
+            });
+            match request.body_text() {
+                Some(body) if !body.is_empty() => writeln!(out, "\n{}", body).unwrap(),
+                _ => (),
+            }
+        })
+    });
+    out
+}
+
+/// the docs and the query params that are left out, as `#` comments
+fn comments(request: &SampleRequest) -> String {
+    let mut out = String::new();
+    request
+        .docs
+        .iter()
+        .flat_map(|docs| docs.lines())
+        .for_each(|line| writeln!(out, "# {}", line).unwrap());
+    request
+        .query
+        .iter()
+        .filter(|param| !param.required)
+        .for_each(|param| writeln!(out, "# optional: {}={}", param.name, param.value).unwrap());
+    out
+}
+
+/// single quotes for the shell, a `'` inside is closed, escaped and reopened
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// a curl command for the request against `$BASE_URL`. path params are
+/// `{name}` placeholders, so `--globoff` keeps curl from expanding them
+pub fn curl_command(request: &SampleRequest) -> String {
+    let mut parts = vec!["curl --globoff".to_string()];
+    // `-X HEAD` still waits for a body that never comes
+    match request.method.as_str() {
+        "GET" => (),
+        "HEAD" => parts.push("--head".to_string()),
+        method => parts.push(format!("-X {}", method)),
+    }
+    parts.push(format!(
+        "\"$BASE_URL\"{}",
+        shell_quote(&request.path_and_query(|name| format!("{{{}}}", name)))
+    ));
+    request.headers.iter().for_each(|(name, value)| {
+        parts.push(format!(
+            "-H {}",
+            shell_quote(&format!("{}: {}", name, value))
+        ))
+    });
+    match &request.body {
+        Some(SampleBody::Json(value)) => {
+            parts.push(format!("--data-raw {}", shell_quote(&value.to_string())))
+        }
+        Some(SampleBody::Form(fields)) => fields.iter().for_each(|(name, value)| {
+            parts.push(format!(
+                "--data-urlencode {}",
+                shell_quote(&format!("{}={}", name, value))
+            ))
+        }),
+        // bytes we can't make up come from a file
+        Some(SampleBody::Text(text)) if text.is_empty() => {
+            parts.push("--data-binary @body.bin".to_string())
+        }
+        Some(SampleBody::Text(text)) => parts.push(format!("--data-raw {}", shell_quote(text))),
+        None => (),
+    }
+    parts.join(" \\\n  ")
+}
+
+/// a shell script with a commented curl command per route
+pub fn to_curl(document: &RocketDocument) -> String {
+    let mut out = format!("#!/bin/sh\nBASE_URL=\"${{BASE_URL:-{}}}\"\n", BASE_URL);
+    sample_requests(document)
+        .iter()
+        .for_each(|(module, requests)| {
+            requests.iter().for_each(|request| {
+                write!(out, "\n# {}::{}\n", module, request.name).unwrap();
+                out.push_str(&comments(request));
+                writeln!(out, "{}", curl_command(request)).unwrap();
+            })
+        });
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn document() -> RocketDocument {
+        let ast = syn::parse_str(
+            "
+            #[derive(Deserialize)]
+            struct NewUser { name: String }
+
+            /// Changes a user.
+            #[put(\"/users/<id>?<notify>\", format = \"json\", data = \"<user>\")]
+            fn update_user(id: u64, notify: Option<bool>, user: Json<NewUser>) {}
+            ",
+        )
+        .unwrap();
+        RocketDocument::parse_file(&ast)
+    }
+
+    #[test]
+    fn writes_an_http_file() {
+        assert_eq!(
+            to_http_file(&document()),
+            "@baseUrl = http://localhost:8000\n\n\
+             ### crate::update_user\n\
+             @update_user_id = 0\n\
+             # Changes a user.\n\
+             # optional: notify=true\n\
+             PUT {{baseUrl}}/users/{{update_user_id}}\n\
+             Content-Type: application/json\n\n\
+             {\n  \"name\": \"string\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn writes_curl_commands() {
+        let requests = sample_requests(&document());
+        assert_eq!(
+            curl_command(&requests["crate"][0]),
+            "curl --globoff \\\n  -X PUT \\\n  \"$BASE_URL\"'/users/{id}' \\\n  \
+             -H 'Content-Type: application/json' \\\n  --data-raw '{\"name\":\"string\"}'"
+        );
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+
+        let ast = syn::parse_str("#[head(\"/users\")]\nfn users_exist() {}").unwrap();
+        let requests = sample_requests(&RocketDocument::parse_file(&ast));
+        assert_eq!(
+            curl_command(&requests["crate"][0]),
+            "curl --globoff \\\n  --head \\\n  \"$BASE_URL\"'/users'",
+            "HEAD routes don't wait for a body"
+        );
+    }
+}
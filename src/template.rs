@@ -0,0 +1,244 @@
+use crate::example::ExampleGenerator;
+use crate::openapi::OpenApiInfo;
+use crate::rocket_document::RocketDocument;
+use crate::rocket_uri::{RocketUri, UriSegment};
+use crate::rocket_wrapper::status_reason;
+use crate::type_ref::TypeRef;
+use handlebars::{handlebars_helper, no_escape, Handlebars};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const EXTENSION: &str = "hbs";
+
+/// `{{type ty}}`, a TypeRef from the document or a field type string as rust
+/// would write it
+fn render_type(ty: &Value) -> String {
+    match ty {
+        Value::String(ty) => TypeRef::parse(ty).to_string(),
+        ty => serde_json::from_value::<TypeRef>(ty.to_owned())
+            .map(|ty| ty.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// `{{status 404}}` is `404 Not Found`, a missing status is decided by the
+/// handler at runtime
+fn render_status(status: &Value) -> String {
+    match status.as_u64() {
+        Some(status) => format!("{} {}", status, status_reason(status as u32)),
+        None => "default".to_string(),
+    }
+}
+
+/// `{{uri route.uri}}` is `/users/{id}`, `{{uri route.uri style="rocket"}}`
+/// is the path the way it's written in the route attribute
+fn render_uri(uri: &Value, style: &str) -> String {
+    let uri = match serde_json::from_value::<RocketUri>(uri.to_owned()) {
+        Ok(uri) => uri,
+        Err(_) => return uri.as_str().unwrap_or_default().to_string(),
+    };
+    if style != "rocket" {
+        return uri.templated_path();
+    }
+
+    let segment = |segment: &UriSegment| match segment {
+        UriSegment::Static { value } => value.to_owned(),
+        UriSegment::Dynamic { name } => format!("<{}>", name),
+        UriSegment::Trailing { name } => format!("<{}..>", name),
+    };
+    let path = uri.path.iter().map(segment).collect::<Vec<_>>().join("/");
+    let query = uri.query.iter().map(segment).collect::<Vec<_>>().join("&");
+    if query.is_empty() {
+        format!("/{}", path)
+    } else {
+        format!("/{}?{}", path, query)
+    }
+}
+
+handlebars_helper!(type_helper: |ty: Json| render_type(ty));
+handlebars_helper!(status_helper: |status: Json| render_status(status));
+handlebars_helper!(uri_helper: |uri: Json, { style: str = "openapi" }| render_uri(uri, style));
+handlebars_helper!(json_helper: |value: Json| serde_json::to_string_pretty(value).unwrap_or_default());
+
+/// what templates are rendered against. the document as it's serialized for
+/// the json format, plus the info block, every route's mounted paths and an
+/// `example` on bodies and responses so templates don't have to work those
+/// out themselves
+pub fn template_context(document: &RocketDocument, info: &OpenApiInfo) -> Value {
+    let examples = ExampleGenerator::new(document);
+    let mut context = serde_json::to_value(document).unwrap();
+    if let Some(routes) = context["routes"].as_array_mut() {
+        routes
+            .iter_mut()
+            .zip(&document.routes)
+            .for_each(|(value, route)| {
+                value["full_uris"] = json!(route.full_uris());
+                if let Some(schema) = route.body.as_ref().and_then(|body| body.schema.as_ref()) {
+                    value["body"]["example"] = examples.example(schema);
+                }
+                route.responses.iter().enumerate().for_each(|(idx, res)| {
+                    if let Some(body) = &res.body {
+                        value["responses"][idx]["example"] = examples.example(body);
+                    }
+                });
+            });
+    }
+    context["info"] =
+        json!({ "title": info.title, "version": info.version, "servers": info.servers });
+    context
+}
+
+/// every `*.hbs` file under `dir`, keyed by its path relative to `dir` without
+/// the extension. that key is also the name other templates use for it as a
+/// partial
+fn load_templates(dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    fn visit(root: &Path, path: &Path, out: &mut BTreeMap<String, String>) -> Result<(), String> {
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path)
+                .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+            for entry in entries {
+                let entry = entry.map_err(|err| format!("{}: {}", path.display(), err))?;
+                visit(root, &entry.path(), out)?;
+            }
+        } else if path
+            .extension()
+            .map(|ext| ext == EXTENSION)
+            .unwrap_or(false)
+        {
+            let name = path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .with_extension("")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            let content = std::fs::read_to_string(path)
+                .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+            out.insert(name, content);
+        }
+        Ok(())
+    }
+
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let mut templates = BTreeMap::new();
+    visit(dir, dir, &mut templates)?;
+    Ok(templates)
+}
+
+/// renders every template to an output file named like the template without
+/// `.hbs`, so `api.md.hbs` becomes `api.md`. templates whose file name starts
+/// with `_` are only there to be used as partials and aren't rendered on
+/// their own
+pub fn render_templates(
+    templates: &BTreeMap<String, String>,
+    context: &Value,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut registry = Handlebars::new();
+    registry.set_strict_mode(true);
+    registry.register_helper("type", Box::new(type_helper));
+    registry.register_helper("status", Box::new(status_helper));
+    registry.register_helper("uri", Box::new(uri_helper));
+    registry.register_helper("json", Box::new(json_helper));
+    for (name, content) in templates {
+        registry
+            .register_template_string(name, content)
+            .map_err(|err| format!("template {}: {}", name, err))?;
+    }
+
+    let mut rendered = BTreeMap::new();
+    for name in templates.keys() {
+        let partial = name
+            .rsplit('/')
+            .next()
+            .map(|file| file.starts_with('_'))
+            .unwrap_or(false);
+        if partial {
+            continue;
+        }
+
+        // only html output wants html escaping, in markdown `Option<T>` should
+        // stay `Option<T>`
+        if name.ends_with(".html") || name.ends_with(".htm") {
+            registry.register_escape_fn(handlebars::html_escape);
+        } else {
+            registry.register_escape_fn(no_escape);
+        }
+        let output = registry
+            .render(name, context)
+            .map_err(|err| format!("template {}: {}", name, err))?;
+        rendered.insert(name.to_owned(), output);
+    }
+    Ok(rendered)
+}
+
+pub fn render_template_dir(
+    document: &RocketDocument,
+    info: &OpenApiInfo,
+    dir: &Path,
+) -> Result<BTreeMap<String, String>, String> {
+    render_templates(&load_templates(dir)?, &template_context(document, info))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn templates(templates: &[(&str, &str)]) -> BTreeMap<String, String> {
+        templates
+            .iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn renders_templates_with_helpers_and_partials() {
+        let ast = syn::parse_str(
+            "
+            #[get(\"/users/<id>?<fields..>\")]
+            fn get_user(id: u64, fields: Fields) -> Option<Json<User>> {}
+            ",
+        )
+        .unwrap();
+        let document = RocketDocument::parse_file(&ast);
+        let result = render_templates(
+            &templates(&[
+                (
+                    "api.md",
+                    "# {{info.title}}\n{{#each routes}}{{> _route}}{{/each}}",
+                ),
+                (
+                    "_route",
+                    "## {{route.method}} {{uri uri}} ({{uri uri style=\"rocket\"}})\n\
+                     returns `{{type handler.ret_type}}`\n\
+                     {{#each responses}}- {{status status}}\n{{/each}}",
+                ),
+            ]),
+            &template_context(&document, &OpenApiInfo::default()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.keys().collect::<Vec<_>>(),
+            vec!["api.md"],
+            "Partials are not rendered on their own"
+        );
+        assert_eq!(
+            result["api.md"],
+            "# API\n## get /users/{id} (/users/<id>?<fields..>)\n\
+             returns `Option<Json<User>>`\n- 200 OK\n- 404 Not Found\n"
+        );
+    }
+
+    #[test]
+    fn reports_missing_values() {
+        let result = render_templates(
+            &templates(&[("out.txt", "{{nope}}")]),
+            &template_context(&RocketDocument::default(), &OpenApiInfo::default()),
+        );
+        assert!(result.unwrap_err().starts_with("template out.txt:"));
+    }
+}
@@ -0,0 +1,173 @@
+//! types the schema can't be worked out from. the built in table covers the
+//! crates most apis pull in (uuid, chrono, time, rust_decimal, url, std::net)
+//! and a project can add its own, mostly for newtypes with a custom serde
+//! impl, or replace the built in ones
+
+use crate::type_ref::TypeRef;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// what a type is documented as. a string is another rust type to use the
+/// schema of (`"UserId": "u64"`) and anything else is the schema itself
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum TypeMapping {
+    Type(String),
+    Schema(Value),
+}
+
+/// the project's mappings, keyed by the type as it's written in the code.
+/// `chrono::DateTime<Utc>`, `chrono::DateTime` and `DateTime` all match a
+/// field of type `chrono::DateTime<Utc>`, the most specific key wins
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct TypeMappings(pub BTreeMap<String, TypeMapping>);
+
+impl TypeMappings {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `{ "Email": { "type": "string", "format": "email" }, "UserId": "u64" }`
+    /// from a json file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+        serde_json::from_str(&content)
+            .map_err(|err| format!("could not parse {}: {}", path.display(), err))
+    }
+
+    pub fn find(&self, ty: &TypeRef) -> Option<&TypeMapping> {
+        if self.0.is_empty() {
+            return None;
+        }
+        lookup_keys(ty).iter().find_map(|key| self.0.get(key))
+    }
+}
+
+/// the keys a type can be found under, most specific first
+pub(crate) fn lookup_keys(ty: &TypeRef) -> Vec<String> {
+    match ty {
+        TypeRef::Path { path, args } => {
+            let mut keys = vec![ty.to_string(), path.join("::")];
+            if let Some(ident) = path.last() {
+                if path.len() > 1 && !args.is_empty() {
+                    keys.push(
+                        TypeRef::Path {
+                            path: vec![ident.to_owned()],
+                            args: args.to_vec(),
+                        }
+                        .to_string(),
+                    );
+                }
+                keys.push(ident.to_owned());
+            }
+            keys.dedup();
+            keys
+        }
+        ty => vec![ty.to_string()],
+    }
+}
+
+/// schemas for well known types from std and popular crates, by the type's
+/// last path segment. these only apply to types the document doesn't
+/// declare itself. names a project could easily have its own type for
+/// (`Date`, `Time`, `Value`) only match bare or with their crate in front,
+/// `crate::events::Time` is somebody else's
+pub fn builtin_schema(ty: &TypeRef) -> Option<Value> {
+    let string = |format: &str| json!({ "type": "string", "format": format });
+    let non_zero = |format: &str| json!({ "type": "integer", "format": format, "minimum": 1 });
+    let from = |crates: &[&str]| match ty {
+        TypeRef::Path { path, .. } if path.len() > 1 => {
+            crates.contains(&path[..path.len() - 1].join("::").as_str())
+        }
+        _ => true,
+    };
+
+    Some(match ty.ident()? {
+        "Uuid" | "Hyphenated" => string("uuid"),
+        // chrono, time and jiff
+        "DateTime" | "NaiveDateTime" | "OffsetDateTime" | "PrimitiveDateTime" | "Timestamp"
+        | "Zoned" => string("date-time"),
+        "NaiveDate" => string("date"),
+        "Date" if from(&["time", "jiff::civil"]) => string("date"),
+        "NaiveTime" => string("time"),
+        "Time" if from(&["time", "jiff::civil"]) => string("time"),
+        // serde doesn't write it as a date but as time since the epoch
+        "SystemTime" => json!({
+            "type": "object",
+            "properties": {
+                "secs_since_epoch": { "type": "integer", "format": "int64", "minimum": 0 },
+                "nanos_since_epoch": { "type": "integer", "format": "int64", "minimum": 0, "maximum": u32::MAX }
+            },
+            "required": ["secs_since_epoch", "nanos_since_epoch"]
+        }),
+        // rust_decimal and bigdecimal serialize as strings to keep precision
+        "Decimal" | "BigDecimal" => string("decimal"),
+        "Url" | "Uri" => string("uri"),
+        "Ipv4Addr" => string("ipv4"),
+        "Ipv6Addr" => string("ipv6"),
+        "IpAddr" => json!({ "anyOf": [string("ipv4"), string("ipv6")] }),
+        "SocketAddr" | "SocketAddrV4" | "SocketAddrV6" => json!({ "type": "string" }),
+        "PathBuf" | "Path" | "OsString" => json!({ "type": "string" }),
+        "NonZeroU8" | "NonZeroU16" | "NonZeroU32" => non_zero("int32"),
+        "NonZeroU64" | "NonZeroU128" | "NonZeroUsize" => non_zero("int64"),
+        // serde_json::Value, anything goes
+        "Value" if ty.args().is_empty() && from(&["serde_json"]) => json!({}),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn finds_the_most_specific_mapping() {
+        let mappings: TypeMappings = serde_json::from_value(json!({
+            "DateTime": { "type": "integer", "format": "unix-time" },
+            "chrono::DateTime<Local>": "String",
+            "UserId": "u64"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            mappings.find(&TypeRef::parse("chrono::DateTime<Local>")),
+            Some(&TypeMapping::Type("String".to_string()))
+        );
+        assert_eq!(
+            mappings.find(&TypeRef::parse("chrono::DateTime<Utc>")),
+            Some(&TypeMapping::Schema(
+                json!({ "type": "integer", "format": "unix-time" })
+            ))
+        );
+        assert_eq!(
+            mappings.find(&TypeRef::parse("crate::ids::UserId")),
+            Some(&TypeMapping::Type("u64".to_string()))
+        );
+        assert_eq!(
+            builtin_schema(&TypeRef::parse("uuid::Uuid")),
+            Some(json!({ "type": "string", "format": "uuid" }))
+        );
+    }
+
+    #[test]
+    fn leaves_other_crates_common_names_alone() {
+        assert_eq!(
+            builtin_schema(&TypeRef::parse("time::Date")),
+            Some(json!({ "type": "string", "format": "date" }))
+        );
+        assert_eq!(
+            builtin_schema(&TypeRef::parse("serde_json::Value")),
+            Some(json!({}))
+        );
+        assert_eq!(builtin_schema(&TypeRef::parse("crate::events::Time")), None);
+        assert_eq!(builtin_schema(&TypeRef::parse("toml::Value")), None);
+        assert_eq!(
+            builtin_schema(&TypeRef::parse("std::time::SystemTime")).unwrap()["required"],
+            json!(["secs_since_epoch", "nanos_since_epoch"]),
+            "SystemTime is what serde writes, not a date"
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// a structured version of a syn::Type. the strings from format_type are fine
+/// for display but we need to be able to look inside types to expand aliases,
+/// fill in generics and figure out what a wrapper like Json<T> is wrapping
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeRef {
+    // some::path::Name<Args, ..>
+    Path {
+        path: Vec<String>,
+        args: Vec<TypeRef>,
+    },
+    // (A, B) and () which is just a tuple with nothing in it
+    Tuple {
+        elems: Vec<TypeRef>,
+    },
+    // [T] and [T; N]
+    Array {
+        elem: Box<TypeRef>,
+    },
+    // &T, &mut T, &'a T
+    Reference {
+        elem: Box<TypeRef>,
+    },
+    // fn pointers, impl Trait, etc. nothing we can do much with
+    Other {
+        repr: String,
+    },
+}
+
+impl TypeRef {
+    pub fn from_type(ty: &syn::Type) -> Self {
+        match ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => TypeRef::Path {
+                path: path
+                    .segments
+                    .iter()
+                    .map(|segment| crate::ast_formatting::format_idnt(&segment.ident))
+                    .collect(),
+                args: path
+                    .segments
+                    .last()
+                    .map(|segment| match &segment.arguments {
+                        syn::PathArguments::AngleBracketed(args) => args
+                            .args
+                            .iter()
+                            .filter_map(|arg| {
+                                if let syn::GenericArgument::Type(ty) = arg {
+                                    Some(TypeRef::from_type(ty))
+                                } else {
+                                    None
+                                }
+                            })
+                            .collect(),
+                        _ => vec![],
+                    })
+                    .unwrap_or_default(),
+            },
+            syn::Type::Tuple(tuple) => TypeRef::Tuple {
+                elems: tuple.elems.iter().map(TypeRef::from_type).collect(),
+            },
+            syn::Type::Array(syn::TypeArray { box elem, .. })
+            | syn::Type::Slice(syn::TypeSlice { box elem, .. }) => TypeRef::Array {
+                elem: Box::new(TypeRef::from_type(elem)),
+            },
+            syn::Type::Reference(syn::TypeReference { box elem, .. }) => TypeRef::Reference {
+                elem: Box::new(TypeRef::from_type(elem)),
+            },
+            syn::Type::Paren(syn::TypeParen { box elem, .. })
+            | syn::Type::Group(syn::TypeGroup { box elem, .. }) => TypeRef::from_type(elem),
+            _ => TypeRef::Other {
+                repr: crate::ast_formatting::format_type(ty),
+            },
+        }
+    }
+
+    pub fn from_ret_type(ty: &syn::ReturnType) -> Self {
+        match ty {
+            syn::ReturnType::Default => TypeRef::Tuple { elems: vec![] },
+            syn::ReturnType::Type(_, box ty) => TypeRef::from_type(ty),
+        }
+    }
+
+    /// for when all we have is one of the strings from format_type
+    pub fn parse(ty: &str) -> Self {
+        syn::parse_str(ty)
+            .map(|ty| TypeRef::from_type(&ty))
+            .unwrap_or_else(|_| TypeRef::Other {
+                repr: ty.to_string(),
+            })
+    }
+
+    /// the opposite of parse, so the string lines up with the ones from
+    /// format_type that the rest of the document uses
+    pub fn format(&self) -> String {
+        syn::parse_str(&self.to_string())
+            .map(|ty| crate::ast_formatting::format_type(&ty))
+            .unwrap_or_else(|_| self.to_string())
+    }
+
+    /// the last segment of a path type, so `Json` for `rocket_contrib::json::Json<T>`
+    pub fn ident(&self) -> Option<&str> {
+        if let TypeRef::Path { path, .. } = self {
+            path.last().map(|segment| segment.as_str())
+        } else {
+            None
+        }
+    }
+
+    pub fn args(&self) -> &[TypeRef] {
+        if let TypeRef::Path { args, .. } = self {
+            args
+        } else {
+            &[]
+        }
+    }
+
+    /// every type directly inside this one, so the arguments of a path or the
+    /// elements of a tuple
+    pub fn children(&self) -> Vec<&TypeRef> {
+        match self {
+            TypeRef::Path { args, .. } => args.iter().collect(),
+            TypeRef::Tuple { elems } => elems.iter().collect(),
+            TypeRef::Array { elem } | TypeRef::Reference { elem } => vec![elem],
+            TypeRef::Other { .. } => vec![],
+        }
+    }
+
+    /// replace generic parameters (single segment paths with no arguments)
+    /// with whatever they are mapped to
+    pub fn substitute(&self, params: &HashMap<String, TypeRef>) -> Self {
+        match self {
+            TypeRef::Path { path, args } => {
+                if let ([name], []) = (path.as_slice(), args.as_slice()) {
+                    if let Some(ty) = params.get(name) {
+                        return ty.to_owned();
+                    }
+                }
+                TypeRef::Path {
+                    path: path.to_owned(),
+                    args: args.iter().map(|arg| arg.substitute(params)).collect(),
+                }
+            }
+            TypeRef::Tuple { elems } => TypeRef::Tuple {
+                elems: elems.iter().map(|elem| elem.substitute(params)).collect(),
+            },
+            TypeRef::Array { elem } => TypeRef::Array {
+                elem: Box::new(elem.substitute(params)),
+            },
+            TypeRef::Reference { elem } => TypeRef::Reference {
+                elem: Box::new(elem.substitute(params)),
+            },
+            TypeRef::Other { .. } => self.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn join(types: &[TypeRef]) -> String {
+            types
+                .iter()
+                .map(|ty| ty.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        match self {
+            TypeRef::Path { path, args } if args.is_empty() => write!(f, "{}", path.join("::")),
+            TypeRef::Path { path, args } => write!(f, "{}<{}>", path.join("::"), join(args)),
+            TypeRef::Tuple { elems } if elems.len() == 1 => write!(f, "({},)", elems[0]),
+            TypeRef::Tuple { elems } => write!(f, "({})", join(elems)),
+            TypeRef::Array { elem } => write!(f, "[{}]", elem),
+            TypeRef::Reference { elem } => write!(f, "&{}", elem),
+            TypeRef::Other { repr } => write!(f, "{}", repr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_nested_generics() {
+        let ty = TypeRef::parse("Result<Json<Vec<User>>, &'static str>");
+        assert_eq!(ty.ident(), Some("Result"));
+        assert_eq!(
+            TypeRef::parse("Json<Vec<User>>"),
+            TypeRef::Path {
+                path: vec!["Json".to_string()],
+                args: vec![TypeRef::Path {
+                    path: vec!["Vec".to_string()],
+                    args: vec![TypeRef::Path {
+                        path: vec!["User".to_string()],
+                        args: vec![]
+                    }]
+                }]
+            },
+            "Keeps generic arguments of nested types"
+        );
+        assert_eq!(
+            ty.to_string(),
+            "Result<Json<Vec<User>>, &str>",
+            "Displays types the way they would be written"
+        );
+    }
+
+    #[test]
+    fn parses_unit_return_type() {
+        assert_eq!(
+            TypeRef::from_ret_type(&syn::parse_str("").unwrap()),
+            TypeRef::Tuple { elems: vec![] },
+            "An unspecified return type is the unit type"
+        );
+    }
+
+    #[test]
+    fn formats_like_format_type() {
+        assert_eq!(
+            TypeRef::parse("Vec<Option<User>>").format(),
+            crate::ast_formatting::format_type(&syn::parse_str("Vec<Option<User>>").unwrap()),
+            "Formats the same way as the strings in the rest of the document"
+        );
+    }
+
+    #[test]
+    fn substitutes_generic_parameters() {
+        let mut params = HashMap::new();
+        params.insert("T".to_string(), TypeRef::parse("User"));
+
+        assert_eq!(
+            TypeRef::parse("Result<Json<T>, (T, Error)>")
+                .substitute(&params)
+                .to_string(),
+            "Result<Json<User>, (User, Error)>",
+            "Replaces every use of a generic parameter"
+        );
+    }
+}
@@ -0,0 +1,773 @@
+use crate::rocket_document::RocketDocument;
+use crate::rocket_enum::{RocketEnum, RocketVariant};
+use crate::rocket_route::{ParamKind, RocketRoute};
+use crate::rocket_serde::SerdeAttributes;
+use crate::rocket_struct::RocketStruct;
+use crate::rocket_uri::UriSegment;
+use crate::schema::SchemaGenerator;
+use crate::type_mapping::{builtin_schema, TypeMapping};
+use crate::type_ref::TypeRef;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+/// the part of the client that doesn't depend on the routes
+const CLIENT_RUNTIME: &str = r#"export interface ClientOptions {
+  baseUrl?: string;
+  headers?: Record<string, string>;
+  fetch?: typeof fetch;
+}
+
+type QueryValue = string | number | boolean | null | undefined | QueryValue[];
+
+function buildQuery(query: Record<string, unknown>): string {
+  const params = new URLSearchParams();
+  Object.entries(query).forEach(([key, value]) => {
+    const values = Array.isArray(value) ? value : [value];
+    values.forEach((value: QueryValue) => {
+      if (value !== null && value !== undefined) params.append(key, String(value));
+    });
+  });
+  const search = params.toString();
+  return search ? `?${search}` : "";
+}
+
+async function readBody(response: Response): Promise<unknown> {
+  const contentType = response.headers.get("content-type") ?? "";
+  if (contentType.includes("json")) return response.json();
+  return response.text();
+}
+"#;
+
+/// `.ts` definitions for every struct and enum the routes use plus a client
+/// with a typed function per route. responses come back as a union of
+/// `{ status, body }` for every status the route can answer with
+pub fn to_typescript(document: &RocketDocument) -> String {
+    let mut out = String::new();
+    writeln!(out, "// generated by rocket-doc-gen, do not edit\n").unwrap();
+
+    let body_types = document.body_types();
+    body_types.iter().for_each(|(module, ident)| {
+        if let Some(strct) = document
+            .structs
+            .iter()
+            .find(|strct| &strct.module == module && &strct.ident == ident)
+        {
+            out.push_str(&struct_type(document, strct));
+        } else if let Some(enm) = document
+            .enums
+            .iter()
+            .find(|enm| &enm.module == module && &enm.ident == ident)
+        {
+            out.push_str(&enum_type(document, enm));
+        }
+        out.push('\n');
+    });
+    let types: BTreeSet<String> = body_types
+        .iter()
+        .map(|(module, ident)| type_name(document, module, ident))
+        .collect();
+
+    out.push_str(CLIENT_RUNTIME);
+    out.push('\n');
+    let routes = route_functions(document, &types);
+    routes
+        .iter()
+        .for_each(|route| out.push_str(&response_type(document, route)));
+
+    writeln!(
+        out,
+        "\nexport function createClient(options: ClientOptions = {{}}) {{\n  \
+         const baseUrl = options.baseUrl ?? \"\";\n  \
+         const doFetch = options.fetch ?? fetch;\n\n  \
+         async function request(method: string, path: string, query: Record<string, unknown>, body?: unknown, contentType?: string) {{\n    \
+         const headers: Record<string, string> = {{ ...options.headers }};\n    \
+         let payload: BodyInit | undefined;\n    \
+         if (body !== undefined) {{\n      \
+         if (contentType) headers[\"content-type\"] = contentType;\n      \
+         if (contentType?.includes(\"json\")) payload = JSON.stringify(body);\n      \
+         else if (contentType === \"application/x-www-form-urlencoded\") payload = new URLSearchParams(body as Record<string, string>);\n      \
+         else payload = body as BodyInit;\n    \
+         }}\n    \
+         const response = await doFetch(baseUrl + path + buildQuery(query), {{ method, headers, body: payload }});\n    \
+         return {{ status: response.status, body: await readBody(response) }};\n  \
+         }}\n\n  \
+         return {{"
+    )
+    .unwrap();
+    routes
+        .iter()
+        .for_each(|route| out.push_str(&route_function(document, route)));
+    writeln!(out, "  }};\n}}").unwrap();
+
+    out
+}
+
+/// the typescript for a schema's `type`, for mapped and well known types
+fn schema_ts_type(schema: &Value) -> String {
+    if let Some(schemas) = schema.get("anyOf").and_then(|schemas| schemas.as_array()) {
+        let types: BTreeSet<String> = schemas.iter().map(schema_ts_type).collect();
+        return types.into_iter().collect::<Vec<_>>().join(" | ");
+    }
+    if let Some(properties) = schema.get("properties").and_then(|props| props.as_object()) {
+        let fields = properties
+            .iter()
+            .map(|(name, schema)| format!("{}: {}", name, schema_ts_type(schema)))
+            .collect::<Vec<_>>();
+        return format!("{{ {} }}", fields.join("; "));
+    }
+    match schema.get("type").and_then(|ty| ty.as_str()) {
+        Some("string") => "string",
+        Some("integer") | Some("number") => "number",
+        Some("boolean") => "boolean",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+/// what a struct or enum is called in the `.ts` file. same named types in
+/// different modules get their module in front like their json schemas,
+/// `api_items_User`
+fn type_name(document: &RocketDocument, module: &[String], ident: &str) -> String {
+    SchemaGenerator::definition_name(&document.type_name(ident, module))
+}
+
+/// the typescript for a rust type. `generics` are the type parameters in
+/// scope, everything else we don't know is `unknown`
+fn ts_type(document: &RocketDocument, ty: &TypeRef, generics: &HashSet<String>) -> String {
+    let inner = |ty: &TypeRef| ts_type(document, ty, generics);
+
+    match document.type_mappings.find(ty) {
+        Some(TypeMapping::Type(mapped)) if &TypeRef::parse(mapped) != ty => {
+            return inner(&TypeRef::parse(mapped))
+        }
+        Some(TypeMapping::Schema(schema)) => return schema_ts_type(schema),
+        _ => (),
+    }
+
+    match (ty.ident(), ty.args()) {
+        (Some("bool"), []) => "boolean".to_string(),
+        (Some(number), [])
+            if [
+                "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128",
+                "usize", "f32", "f64",
+            ]
+            .contains(&number) =>
+        {
+            "number".to_string()
+        }
+        (Some("String"), []) | (Some("str"), []) | (Some("char"), []) => "string".to_string(),
+        (Some("Option"), [ty]) => format!("{} | null", inner(ty)),
+        (Some("Vec"), [ty])
+        | (Some("VecDeque"), [ty])
+        | (Some("LinkedList"), [ty])
+        | (Some("BinaryHeap"), [ty])
+        | (Some("HashSet"), [ty])
+        | (Some("BTreeSet"), [ty]) => array(inner(ty)),
+        (Some("HashMap"), [_, value])
+        | (Some("BTreeMap"), [_, value])
+        | (Some("IndexMap"), [_, value]) => format!("Record<string, {}>", inner(value)),
+        (Some("Box"), [ty])
+        | (Some("Rc"), [ty])
+        | (Some("Arc"), [ty])
+        | (Some("Cow"), [ty])
+        | (Some("Json"), [ty])
+        | (Some("MsgPack"), [ty])
+        | (Some("Form"), [ty])
+        | (Some("LenientForm"), [ty]) => inner(ty),
+        (Some(ident), args) if generics.contains(ident) || document.declared(ty).is_some() => {
+            let name = match document.declared(ty) {
+                Some((module, ident)) if !generics.contains(&ident) => {
+                    type_name(document, &module, &ident)
+                }
+                _ => ident.to_string(),
+            };
+            if args.is_empty() {
+                name
+            } else {
+                format!(
+                    "{}<{}>",
+                    name,
+                    args.iter().map(inner).collect::<Vec<_>>().join(", ")
+                )
+            }
+        }
+        _ => match ty {
+            TypeRef::Tuple { elems } if elems.is_empty() => "null".to_string(),
+            TypeRef::Tuple { elems } => format!(
+                "[{}]",
+                elems.iter().map(inner).collect::<Vec<_>>().join(", ")
+            ),
+            TypeRef::Array { elem } => array(inner(elem)),
+            TypeRef::Reference { elem } => inner(elem),
+            _ => builtin_schema(ty)
+                .map(|schema| schema_ts_type(&schema))
+                .unwrap_or_else(|| "unknown".to_string()),
+        },
+    }
+}
+
+/// `A | null` has to be wrapped before it can be an array
+fn array(ty: String) -> String {
+    if ty.contains(' ') {
+        format!("({})[]", ty)
+    } else {
+        format!("{}[]", ty)
+    }
+}
+
+fn doc_comment(docs: &Option<String>, indent: &str) -> String {
+    match docs {
+        Some(docs) => {
+            let mut comment = format!("{}/**\n", indent);
+            docs.lines().for_each(|line| {
+                writeln!(
+                    comment,
+                    "{} * {}",
+                    indent,
+                    line.replace("*/", "* /").trim_end()
+                )
+                .unwrap()
+            });
+            writeln!(comment, "{} */", indent).unwrap();
+            comment
+        }
+        None => String::new(),
+    }
+}
+
+fn type_params(generics: &[crate::rocket_generic::RocketGeneric]) -> String {
+    if generics.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<{}>",
+            generics
+                .iter()
+                .map(|generic| generic.ident.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// fields as a typescript type. named fields are an object literal with
+/// serde's names, optional when they're Option or have a default, unnamed
+/// ones a tuple and a single unnamed field is just that field
+fn fields_type(
+    document: &RocketDocument,
+    fields: &[(String, String)],
+    serde: &SerdeAttributes,
+    generics: &HashSet<String>,
+    indent: &str,
+) -> String {
+    let fields: Vec<&(String, String)> = fields
+        .iter()
+        .filter(|(name, _)| {
+            !serde
+                .field(name)
+                .map(|field| field.skip.skipped(None))
+                .unwrap_or(false)
+        })
+        .collect();
+    let unnamed = fields
+        .first()
+        .map(|(name, _)| name.parse::<usize>().is_ok())
+        .unwrap_or(false);
+    let ty = |ty: &str| ts_type(document, &TypeRef::parse(ty), generics);
+
+    match fields.as_slice() {
+        [] => "null".to_string(),
+        [(_, field)] if unnamed || serde.transparent => ty(field),
+        _ if unnamed => format!(
+            "[{}]",
+            fields
+                .iter()
+                .map(|(_, field)| ty(field))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => {
+            let mut object = "{\n".to_string();
+            let mut flattened = vec![];
+            fields.iter().for_each(|(name, field)| {
+                let attrs = serde.field(name).cloned().unwrap_or_default();
+                if attrs.flatten {
+                    flattened.push(ty(field));
+                    return;
+                }
+                // fields skipped one way are only there in one direction
+                let optional = attrs.optional
+                    || serde.default
+                    || attrs.skip.serializing
+                    || attrs.skip.deserializing
+                    || TypeRef::parse(field).ident() == Some("Option");
+                writeln!(
+                    object,
+                    "{}  {}{}: {};",
+                    indent,
+                    property_name(&serde.field_name(name)),
+                    if optional { "?" } else { "" },
+                    ty(field)
+                )
+                .unwrap();
+            });
+            write!(object, "{}}}", indent).unwrap();
+            flattened.insert(0, object);
+            flattened.join(" & ")
+        }
+    }
+}
+
+/// names that aren't identifiers (`kebab-case` renames) need quotes
+fn property_name(name: &str) -> String {
+    let identifier = name.chars().enumerate().all(|(idx, c)| {
+        c == '_' || c == '$' || c.is_ascii_alphabetic() || (idx > 0 && c.is_ascii_digit())
+    });
+    if identifier && !name.is_empty() {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+fn struct_type(document: &RocketDocument, strct: &RocketStruct) -> String {
+    let generics = strct
+        .generics
+        .iter()
+        .map(|generic| generic.ident.to_owned())
+        .collect();
+    let body = fields_type(document, &strct.fields, &strct.serde, &generics, "");
+    let keyword = if body.starts_with('{') && !body.contains("} &") {
+        format!(
+            "export interface {}{} ",
+            type_name(document, &strct.module, &strct.ident),
+            type_params(&strct.generics)
+        )
+    } else {
+        format!(
+            "export type {}{} = ",
+            type_name(document, &strct.module, &strct.ident),
+            type_params(&strct.generics)
+        )
+    };
+    let end = if keyword.starts_with("export interface") {
+        "\n"
+    } else {
+        ";\n"
+    };
+    format!("{}{}{}{}", doc_comment(&strct.docs, ""), keyword, body, end)
+}
+
+/// a variant in whichever of serde's enum representations the enum uses
+fn variant_type(
+    document: &RocketDocument,
+    enm: &RocketEnum,
+    variant: &RocketVariant,
+    generics: &HashSet<String>,
+) -> String {
+    let name = format!("{:?}", enm.variant_name(variant));
+    let content = || fields_type(document, &variant.fields, &variant.serde, generics, "  ");
+
+    match (&enm.serde.tag, &enm.serde.content) {
+        _ if enm.serde.untagged => content(),
+        (Some(tag), Some(_)) if variant.fields.is_empty() => {
+            format!("{{ {}: {} }}", property_name(tag), name)
+        }
+        (Some(tag), Some(content_field)) => format!(
+            "{{ {}: {}; {}: {} }}",
+            property_name(tag),
+            name,
+            property_name(content_field),
+            content()
+        ),
+        (Some(tag), None) if variant.fields.is_empty() => {
+            format!("{{ {}: {} }}", property_name(tag), name)
+        }
+        (Some(tag), None) => format!("{{ {}: {} }} & {}", property_name(tag), name, content()),
+        (None, _) if variant.fields.is_empty() => name,
+        (None, _) => format!("{{ {}: {} }}", name, content()),
+    }
+}
+
+fn enum_type(document: &RocketDocument, enm: &RocketEnum) -> String {
+    let generics = enm
+        .generics
+        .iter()
+        .map(|generic| generic.ident.to_owned())
+        .collect();
+    let variants = enm
+        .variants
+        .iter()
+        .filter(|variant| !variant.serde.skip.skipped(None))
+        .map(|variant| format!("\n  | {}", variant_type(document, enm, variant, &generics)))
+        .collect::<String>();
+
+    format!(
+        "{}export type {}{} ={};\n",
+        doc_comment(&enm.docs, ""),
+        type_name(document, &enm.module, &enm.ident),
+        type_params(&enm.generics),
+        if variants.is_empty() {
+            " never".to_string()
+        } else {
+            variants
+        }
+    )
+}
+
+/// get_user -> getUser
+fn camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    let mut upper = false;
+    name.chars().for_each(|c| {
+        if c == '_' {
+            upper = !camel.is_empty();
+        } else if upper {
+            camel.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    });
+    camel
+}
+
+fn pascal_case(name: &str) -> String {
+    let camel = camel_case(name);
+    let mut chars = camel.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// a route and the names it goes by in the client
+struct RouteFunction<'a> {
+    route: &'a RocketRoute,
+    name: String,
+    response: String,
+}
+
+/// a function name for every route, handlers with the same name in
+/// different modules get the module in front. the response type is named
+/// after the function unless a struct or enum already has that name
+fn route_functions<'a>(
+    document: &'a RocketDocument,
+    types: &BTreeSet<String>,
+) -> Vec<RouteFunction<'a>> {
+    let mut seen = HashSet::new();
+    document
+        .routes
+        .iter()
+        .map(|route| {
+            let mut name = camel_case(&route.ident);
+            if !seen.insert(name.to_owned()) {
+                name = camel_case(
+                    &route
+                        .module
+                        .iter()
+                        .chain(std::iter::once(&route.ident))
+                        .map(|segment| segment.as_str())
+                        .collect::<Vec<_>>()
+                        .join("_"),
+                );
+                seen.insert(name.to_owned());
+            }
+            let mut response = format!("{}Response", pascal_case(&name));
+            if types.contains(&response) {
+                response = format!("{}RouteResponse", pascal_case(&name));
+            }
+            RouteFunction {
+                route,
+                name,
+                response,
+            }
+        })
+        .collect()
+}
+
+fn response_type(document: &RocketDocument, function: &RouteFunction) -> String {
+    let route = function.route;
+    let mut variants: Vec<String> = vec![];
+    route.responses.iter().for_each(|res| {
+        let status = res
+            .status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "number".to_string());
+        let body = res
+            .body
+            .as_ref()
+            .map(|body| ts_type(document, body, &HashSet::new()))
+            .unwrap_or_else(|| "unknown".to_string());
+        let variant = format!("{{ status: {}; body: {} }}", status, body);
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    });
+    if variants.is_empty() {
+        variants.push("{ status: number; body: unknown }".to_string());
+    }
+
+    format!(
+        "export type {} ={};\n",
+        function.response,
+        variants
+            .iter()
+            .map(|variant| format!("\n  | {}", variant))
+            .collect::<String>()
+    )
+}
+
+/// params that aren't `Option` have to be given
+fn unwrap_param(ty: &TypeRef) -> &TypeRef {
+    match (ty.ident(), ty.args()) {
+        (Some("Option"), [inner])
+        | (Some("Result"), [inner, ..])
+        | (Some("Form"), [inner])
+        | (Some("LenientForm"), [inner]) => unwrap_param(inner),
+        _ => ty,
+    }
+}
+
+fn route_function(document: &RocketDocument, function: &RouteFunction) -> String {
+    let route = function.route;
+    let generics = HashSet::new();
+    let ty = |ty: &TypeRef| ts_type(document, unwrap_param(ty), &generics);
+    let uri = route
+        .full_uris()
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| route.uri.to_owned());
+
+    let mut args = vec![];
+    route
+        .params_of_kind(ParamKind::Path)
+        .for_each(|param| args.push(format!("{}: {}", camel_case(&param.ident), ty(&param.ty))));
+
+    let mut query_fields = vec![];
+    let mut query_forms = vec![];
+    let mut static_query = vec![];
+    route.uri.query.iter().for_each(|segment| match segment {
+        UriSegment::Static { value } => {
+            let mut kv = value.splitn(2, '=');
+            static_query.push(format!(
+                "{:?}: {:?}",
+                kv.next().unwrap_or_default(),
+                kv.next().unwrap_or_default()
+            ))
+        }
+        _ => route
+            .params
+            .iter()
+            .filter(|param| Some(param.ident.as_str()) == segment.name())
+            .for_each(|param| match param.kind {
+                ParamKind::QueryForm => query_forms.push(ty(&param.ty)),
+                _ => query_fields.push(format!(
+                    "{}{}: {}",
+                    property_name(&param.ident),
+                    if param.ty.ident() == Some("Option") {
+                        "?"
+                    } else {
+                        ""
+                    },
+                    ty(&param.ty)
+                )),
+            }),
+    });
+    let has_query = !query_fields.is_empty() || !query_forms.is_empty();
+    if has_query {
+        let mut parts = vec![];
+        if !query_fields.is_empty() {
+            parts.push(format!("{{ {} }}", query_fields.join("; ")));
+        }
+        parts.extend(query_forms);
+        args.push(format!("query: {}", parts.join(" & ")));
+    }
+
+    let body = route.body.as_ref().map(|body| {
+        let body_type = body
+            .schema
+            .as_ref()
+            .map(|schema| ts_type(document, schema, &generics))
+            .unwrap_or_else(|| "BodyInit".to_string());
+        args.push(format!(
+            "body{}: {}",
+            if body.required { "" } else { "?" },
+            body_type
+        ));
+        body.media_type
+            .as_ref()
+            .map(|media_type| format!("{:?}", media_type))
+            .unwrap_or_else(|| "undefined".to_string())
+    });
+
+    // `{id}` becomes `${encodeURIComponent(String(id))}`, a `<path..>` keeps
+    // its slashes between the segments it's made of
+    let path = uri
+        .path
+        .iter()
+        .map(|segment| match segment {
+            UriSegment::Static { value } => value.replace('`', "\\`").replace("${", "\\${"),
+            UriSegment::Dynamic { name } => {
+                format!("${{encodeURIComponent(String({}))}}", camel_case(name))
+            }
+            UriSegment::Trailing { name } => format!(
+                "${{String({}).split(\"/\").map(encodeURIComponent).join(\"/\")}}",
+                camel_case(name)
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let query = match (has_query, static_query.is_empty()) {
+        (false, true) => "{}".to_string(),
+        (true, true) => "query".to_string(),
+        (false, false) => format!("{{ {} }}", static_query.join(", ")),
+        (true, false) => format!("{{ ...query, {} }}", static_query.join(", ")),
+    };
+    let call = match body {
+        Some(content_type) => format!(
+            "request({:?}, `/{}`, {}, body, {})",
+            route.route.method.to_uppercase(),
+            path,
+            query,
+            content_type
+        ),
+        None => format!(
+            "request({:?}, `/{}`, {})",
+            route.route.method.to_uppercase(),
+            path,
+            query
+        ),
+    };
+
+    format!(
+        "{}    {}({}): Promise<{}> {{\n      return {} as Promise<{}>;\n    }},\n",
+        doc_comment(&route.docs, "    "),
+        function.name,
+        args.join(", "),
+        function.response,
+        call,
+        function.response
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generates_types_and_client() {
+        let ast = syn::parse_str(
+            "
+            /// Someone with an account.
+            #[derive(Serialize, Deserialize)]
+            #[serde(rename_all = \"camelCase\")]
+            struct User { user_id: u64, nickname: Option<String>, tags: Vec<Tag> }
+            #[derive(Serialize, Deserialize)]
+            enum Tag { Admin, Member }
+            #[derive(Serialize)]
+            struct Page<T> { items: Vec<T> }
+
+            #[put(\"/users/<id>?<notify>&v=2\", format = \"json\", data = \"<user>\")]
+            fn update_user(id: u64, notify: Option<bool>, user: Json<User>) -> Option<Json<Page<User>>> {}
+            ",
+        )
+        .unwrap();
+        let result = to_typescript(&RocketDocument::parse_file(&ast));
+
+        assert!(
+            result.contains(
+                "/**\n * Someone with an account.\n */\nexport interface User {\n  userId: number;\n  nickname?: string | null;\n  tags: Tag[];\n}\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("export type Tag =\n  | \"Admin\"\n  | \"Member\";\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("export interface Page<T> {\n  items: T[];\n}\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "export type UpdateUserResponse =\n  | { status: 200; body: Page<User> }\n  | { status: 404; body: unknown };\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "    updateUser(id: number, query: { notify?: boolean }, body: User): Promise<UpdateUserResponse> {\n      \
+                 return request(\"PUT\", `/users/${encodeURIComponent(String(id))}`, { ...query, \"v\": \"2\" }, body, \"application/json\") as Promise<UpdateUserResponse>;\n    },\n"
+            ),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn names_same_named_types_by_module() {
+        let ast = syn::parse_str(
+            "
+            #[derive(Serialize)]
+            struct User { name: String }
+
+            mod api {
+                mod items {
+                    #[derive(Serialize)]
+                    struct User { id: u64 }
+
+                    #[get(\"/items/<id>\")]
+                    fn item(id: u64) -> Json<User> {}
+                }
+            }
+
+            #[get(\"/users/<id>\")]
+            fn get_user(id: u64) -> Json<User> {}
+
+            #[get(\"/files/<path..>\")]
+            fn file(path: PathBuf) -> Vec<u8> {}
+            ",
+        )
+        .unwrap();
+        let result = to_typescript(&RocketDocument::parse_file(&ast));
+
+        assert!(
+            result.contains("export interface crate_User {\n  name: string;\n}\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("export interface api_items_User {\n  id: number;\n}\n"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "export type GetUserResponse =\n  | { status: 200; body: crate_User };\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "export type ItemResponse =\n  | { status: 200; body: api_items_User };\n"
+            ),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "`/files/${String(path).split(\"/\").map(encodeURIComponent).join(\"/\")}`"
+            ),
+            "The slashes of a trailing path aren't encoded\n{}",
+            result
+        );
+    }
+}